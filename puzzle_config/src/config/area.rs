@@ -1,10 +1,14 @@
+use time::OffsetDateTime;
+
 /// Metadata for an area on the board.
 /// Includes the name and the formatter for the area values.
 /// This is used by the target selection UI.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AreaConfig {
     name: String,
     formatter: AreaValueFormatter,
+    default_factory: DefaultValueFactory,
+    /// The value produced by the default factory when the area was created.
     default_value: String,
 }
 
@@ -12,11 +16,13 @@ impl AreaConfig {
     pub fn new(
         name: String,
         area_value_formatter: AreaValueFormatter,
-        default_value: String,
+        default_factory: DefaultValueFactory,
     ) -> Self {
+        let default_value = default_factory.produce();
         AreaConfig {
             name,
             formatter: area_value_formatter,
+            default_factory,
             default_value,
         }
     }
@@ -29,13 +35,55 @@ impl AreaConfig {
         &self.formatter
     }
 
+    /// How the default value of the area is chosen.
+    pub fn default_factory(&self) -> &DefaultValueFactory {
+        &self.default_factory
+    }
+
     pub fn default_value(&self) -> &str {
         &self.default_value
     }
 }
 
+/// Chooses the value of an area, which is selected by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefaultValueFactory {
+    /// Always the given value.
+    Fixed(String),
+    /// The current day number (1-31).
+    CurrentDay,
+    /// The current month in short format (e.g., "Jan", "Feb").
+    CurrentMonthShort,
+    /// The first digit of the current year when in two-digit format (e.g., "26" -> '2').
+    CurrentYear2FirstDigit,
+    /// The second digit of the current year when in two-digit format (e.g., "26" -> '6').
+    CurrentYear2SecondDigit,
+    /// The first digit of the current year when in four-digit format (e.g., "2026" -> '2').
+    CurrentYear4FirstDigit,
+    /// The second digit of the current year when in four-digit format (e.g., "2026" -> '0').
+    CurrentYear4SecondDigit,
+}
+
+impl DefaultValueFactory {
+    /// Produces the default value for the current local date.
+    pub fn produce(&self) -> String {
+        let date = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        match self {
+            DefaultValueFactory::Fixed(value) => value.clone(),
+            DefaultValueFactory::CurrentDay => date.day().to_string(),
+            DefaultValueFactory::CurrentMonthShort => date.month().to_string()[0..3].to_string(),
+            DefaultValueFactory::CurrentYear2FirstDigit => ((date.year() % 100) / 10).to_string(),
+            DefaultValueFactory::CurrentYear2SecondDigit => (date.year() % 10).to_string(),
+            DefaultValueFactory::CurrentYear4FirstDigit => (date.year() / 1000).to_string(),
+            DefaultValueFactory::CurrentYear4SecondDigit => {
+                ((date.year() % 1000) / 100).to_string()
+            }
+        }
+    }
+}
+
 /// Formatter for a value for an area to display on the target selection button.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AreaValueFormatter {
     /// Displays the value as is.
     Plain,
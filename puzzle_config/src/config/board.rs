@@ -4,7 +4,7 @@ use ndarray::Array2;
 use std::hash::{Hash, Hasher};
 
 /// Configuration for the board layout and areas.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BoardConfig {
     Simple {
         layout: Array2<bool>,
@@ -111,6 +111,35 @@ impl BoardConfig {
             .collect()
     }
 
+    /// Returns every target, which can be selected on the board.
+    /// A target contains one value of each area, so these are all combinations of the display
+    /// values of the areas. The targets are ordered by the value of the first area, then by the
+    /// value of the second area and so on, using the order of `get_display_values_for_area`.
+    ///
+    /// A simple board has no targets.
+    ///
+    /// returns: Vec<Target, Global>
+    pub fn all_targets(&self) -> Vec<Target> {
+        (0..self.area_count())
+            .fold(vec![Vec::new()], |targets, area_index| {
+                let values = self.get_display_values_for_area(area_index as i32);
+                targets
+                    .iter()
+                    .flat_map(|indices: &Vec<TargetIndex>| {
+                        values.iter().map(move |(_, target_index)| {
+                            let mut indices = indices.clone();
+                            indices.push(target_index.clone());
+                            indices
+                        })
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .filter(|indices| !indices.is_empty())
+            .map(|indices| Target { indices })
+            .collect()
+    }
+
     /// Formats the given target using the target template for this puzzle.
     ///
     /// The returned string can be shown in the UI to represent the target.
@@ -168,7 +197,7 @@ pub fn from_predefined_board(name: &str) -> Option<BoardConfig> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::area::{AreaConfig, AreaValueFormatter};
+    use crate::config::area::{AreaConfig, AreaValueFormatter, DefaultValueFactory};
     use crate::config::target::{TargetIndex, TargetTemplate};
     use ndarray::arr2;
 
@@ -186,12 +215,12 @@ mod tests {
             AreaConfig::new(
                 "Area 0".to_string(),
                 AreaValueFormatter::Plain,
-                "".to_string(),
+                DefaultValueFactory::Fixed("".to_string()),
             ),
             AreaConfig::new(
                 "Area 1".to_string(),
                 AreaValueFormatter::Plain,
-                "".to_string(),
+                DefaultValueFactory::Fixed("".to_string()),
             ),
         ];
 
@@ -225,4 +254,53 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_puzzle_config_all_targets() {
+        let board_config = BoardConfig::Area {
+            layout: arr2(&[[true, true], [true, true]]),
+            area_indices: arr2(&[[0, 0], [1, 1]]),
+            display_values: arr2(&[
+                ["A".to_string(), "B".to_string()],
+                ["C".to_string(), "D".to_string()],
+            ]),
+            value_order: arr2(&[[1, 0], [0, 1]]),
+            area_configs: vec![
+                AreaConfig::new(
+                    "Area 0".to_string(),
+                    AreaValueFormatter::Plain,
+                    DefaultValueFactory::Fixed("".to_string()),
+                ),
+                AreaConfig::new(
+                    "Area 1".to_string(),
+                    AreaValueFormatter::Plain,
+                    DefaultValueFactory::Fixed("".to_string()),
+                ),
+            ],
+            target_template: TargetTemplate::new("{0}, {1}"),
+        };
+
+        let targets: Vec<Vec<TargetIndex>> = board_config
+            .all_targets()
+            .into_iter()
+            .map(|target| target.indices)
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                vec![TargetIndex(0, 1), TargetIndex(1, 0)],
+                vec![TargetIndex(0, 1), TargetIndex(1, 1)],
+                vec![TargetIndex(0, 0), TargetIndex(1, 0)],
+                vec![TargetIndex(0, 0), TargetIndex(1, 1)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_puzzle_config_all_targets_simple() {
+        let board_config = BoardConfig::Simple {
+            layout: arr2(&[[true, true], [true, true]]),
+        };
+        assert!(board_config.all_targets().is_empty());
+    }
 }
@@ -2,7 +2,7 @@ use crate::config::preview::PreviewConfig;
 use crate::config::progression::ProgressionConfig;
 use crate::{PuzzleConfig, PuzzleDifficultyConfig};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PuzzleConfigCollection {
     name: String,
     description: Option<String>,
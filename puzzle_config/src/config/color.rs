@@ -36,7 +36,7 @@ const COLORS: [ColorConfig; 35] = [
     ColorConfig::from_rgb_hex(0xf6d32d), // Yellow 3
 ];
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ColorConfig {
     red: u8,
     green: u8,
@@ -106,6 +106,18 @@ impl ColorConfig {
     pub const fn alpha(&self) -> u8 {
         self.alpha
     }
+
+    /// Formats the color as a hex string.
+    ///
+    /// returns: The color in the format #RRGGBB, or #RRGGBBAA if the color is not fully opaque.
+    pub fn to_hex_string(&self) -> String {
+        let rgb = format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue);
+        if self.alpha == 255 {
+            rgb
+        } else {
+            format!("{rgb}{:02x}", self.alpha)
+        }
+    }
 }
 
 impl TryFrom<String> for ColorConfig {
@@ -173,4 +185,17 @@ mod tests {
         let invalid_hex_length = ColorConfig::try_from("#1c71d".to_string());
         assert!(invalid_hex_length.is_err());
     }
+
+    #[test]
+    fn test_color_config_to_hex_string() {
+        let color = ColorConfig::from_rgb_hex(0x1c71d8);
+        assert_eq!(color.to_hex_string(), "#1c71d8");
+
+        let color_with_alpha = ColorConfig::new(28, 113, 216, 128);
+        assert_eq!(color_with_alpha.to_hex_string(), "#1c71d880");
+        assert_eq!(
+            ColorConfig::try_from(color_with_alpha.to_hex_string()).unwrap(),
+            color_with_alpha
+        );
+    }
 }
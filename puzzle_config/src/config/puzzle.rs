@@ -9,7 +9,7 @@ pub type PuzzleId = String;
 /// Configuration for a puzzle. It describes the board layout and tiles.
 /// It additionally contains configuration for the areas to show in the target selection.
 /// The solution statistics are optional since they might not be known yet for all puzzles.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PuzzleConfig {
     /// The index of the puzzle in the collection.
     index: usize,
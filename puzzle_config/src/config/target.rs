@@ -7,7 +7,7 @@ use std::fmt::{Display, Formatter};
 /// The placeholders {0}, {1}, {2}, etc. will be replaced with the display values
 /// of the corresponding target indices.
 /// The area formatter will be applied to each value before inserting it into the template.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TargetTemplate(String);
 
 impl TargetTemplate {
@@ -15,6 +15,11 @@ impl TargetTemplate {
         TargetTemplate(template.to_string())
     }
 
+    /// The template string with its placeholders.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
     /// Formats the given target using this template.
     /// For format the value of each area, the corresponding area puzzle_config is used.
     ///
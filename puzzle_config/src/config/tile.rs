@@ -3,7 +3,7 @@ use ndarray::Array2;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
 /// Configuration for a tile that can be placed on the board.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TileConfig {
     base: Array2<bool>,
     color: ColorConfig,
@@ -19,4 +19,19 @@ pub enum ReadError {
     InvalidColor {
         message: String,
     },
+    /// The collection imports a collection, which is not installed.
+    UnknownImport {
+        collection_id: String,
+    },
+    /// The collection imports itself through the given chain of imports.
+    CyclicImport {
+        /// The ids of the collections in the cycle. The first and the last id are the same.
+        collection_ids: Vec<String>,
+    },
+    /// The collection does not match the JSON Schema of the collection format.
+    SchemaViolation {
+        /// The JSON pointer to the value violating the schema.
+        pointer: String,
+        message: String,
+    },
 }
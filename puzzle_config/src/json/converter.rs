@@ -4,13 +4,12 @@ use crate::config::preview::PreviewConfig;
 use crate::json::model::*;
 use crate::json::predefined::{Custom, Predefined};
 use crate::{
-    validation, AreaConfig, AreaValueFormatter, BoardConfig, ProgressionConfig,
-    PuzzleConfig, PuzzleConfigCollection, PuzzleDifficultyConfig, ReadError, TargetTemplate,
-    TileConfig,
+    validation, AreaConfig, AreaValueFormatter, BoardConfig, DefaultValueFactory,
+    ProgressionConfig, PuzzleConfig, PuzzleConfigCollection, PuzzleDifficultyConfig, ReadError,
+    TargetTemplate, TileConfig,
 };
 use ndarray::Array2;
 use std::num::NonZero;
-use time::OffsetDateTime;
 
 /// Trait for converting JSON model types to config types.
 pub trait Convertable<R> {
@@ -68,7 +67,9 @@ impl Convertable<PuzzleConfigCollection> for PuzzleCollection {
                 difficulty_config,
                 tiles,
                 board_config,
-                puzzle.additional_info,
+                puzzle
+                    .additional_info
+                    .map(|info| info.into_iter().collect()),
             );
             puzzle_configs.push(puzzle_config);
         }
@@ -394,50 +395,17 @@ impl Convertable<AreaConfig> for Area {
     }
 }
 
-impl Convertable<String> for DefaultFactory {
-    fn convert(self, _: &Predefined, _: &mut Custom) -> Result<String, ReadError> {
-        match self {
-            DefaultFactory::Fixed { value } => Ok(value.to_string()),
-            DefaultFactory::CurrentDay => {
-                let date =
-                    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-                Ok(date.day().to_string())
-            }
-            DefaultFactory::CurrentMonthShort => {
-                let date =
-                    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let month_str = &date.month().to_string()[0..3];
-                Ok(month_str.to_string())
-            }
-            DefaultFactory::CurrentYear2FirstDigit => {
-                let date =
-                    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let year = date.year() % 100;
-                let first_digit = year / 10;
-                Ok(first_digit.to_string())
-            }
-            DefaultFactory::CurrentYear2SecondDigit => {
-                let date =
-                    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let year = date.year() % 100;
-                let second_digit = year % 10;
-                Ok(second_digit.to_string())
-            }
-            DefaultFactory::CurrentYear4FirstDigit => {
-                let date =
-                    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let year = date.year();
-                let first_digit = year / 1000;
-                Ok(first_digit.to_string())
-            }
-            DefaultFactory::CurrentYear4SecondDigit => {
-                let date =
-                    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let year = date.year();
-                let second_digit = (year % 1000) / 100;
-                Ok(second_digit.to_string())
-            }
-        }
+impl Convertable<DefaultValueFactory> for DefaultFactory {
+    fn convert(self, _: &Predefined, _: &mut Custom) -> Result<DefaultValueFactory, ReadError> {
+        Ok(match self {
+            DefaultFactory::Fixed { value } => DefaultValueFactory::Fixed(value),
+            DefaultFactory::CurrentDay => DefaultValueFactory::CurrentDay,
+            DefaultFactory::CurrentMonthShort => DefaultValueFactory::CurrentMonthShort,
+            DefaultFactory::CurrentYear2FirstDigit => DefaultValueFactory::CurrentYear2FirstDigit,
+            DefaultFactory::CurrentYear2SecondDigit => DefaultValueFactory::CurrentYear2SecondDigit,
+            DefaultFactory::CurrentYear4FirstDigit => DefaultValueFactory::CurrentYear4FirstDigit,
+            DefaultFactory::CurrentYear4SecondDigit => DefaultValueFactory::CurrentYear4SecondDigit,
+        })
     }
 }
 
@@ -0,0 +1,426 @@
+use crate::json::converter::Convertable;
+use crate::json::model::*;
+use crate::json::predefined::{Custom, Predefined};
+use crate::{
+    AreaConfig, AreaValueFormatter, BoardConfig, ColorConfig, DefaultValueFactory, PreviewConfig,
+    ProgressionConfig, PuzzleConfig, PuzzleConfigCollection, PuzzleDifficultyConfig, TileConfig,
+};
+use ndarray::Array2;
+
+/// Trait for converting config types back to JSON model types.
+/// This is the inverse of `Convertable`.
+pub trait Exportable<R> {
+    /// Export the config type to the JSON model type.
+    ///
+    /// # Arguments
+    ///
+    /// * `predefined`: Predefined tiles and boards. Tiles and boards matching one of them are
+    ///   exported as a reference.
+    ///
+    /// returns: R
+    fn export(self, predefined: &Predefined) -> R;
+}
+
+impl Exportable<PuzzleCollection> for &PuzzleConfigCollection {
+    fn export(self, predefined: &Predefined) -> PuzzleCollection {
+        let preview = if *self.preview() == PreviewConfig::default() {
+            None
+        } else {
+            Some(self.preview().export(predefined))
+        };
+
+        PuzzleCollection {
+            puzzled_version: None,
+            name: self.name().to_string(),
+            description: self.description().clone(),
+            author: self.author().to_string(),
+            id: self.id().to_string(),
+            version: self.version().clone(),
+            // The boards of the config are already rotated, so they are written as they are.
+            allow_board_rotation: false,
+            progression: self.progression().export(predefined),
+            preview,
+            // The tiles and boards are written inline, so the collection does not need imports.
+            imports: None,
+            custom_tiles: None,
+            custom_boards: None,
+            puzzles: self
+                .puzzles()
+                .iter()
+                .enumerate()
+                .map(|puzzle| puzzle.export(predefined))
+                .collect(),
+        }
+    }
+}
+
+impl Exportable<Progression> for &ProgressionConfig {
+    fn export(self, _: &Predefined) -> Progression {
+        match self {
+            ProgressionConfig::Any => Progression::Any,
+            ProgressionConfig::Sequential => Progression::Sequential,
+        }
+    }
+}
+
+impl Exportable<Preview> for &PreviewConfig {
+    fn export(self, _: &Predefined) -> Preview {
+        Preview {
+            show_board: self.show_board(),
+            show_board_size: self.show_board_size(),
+            show_tiles: self.show_tiles(),
+            show_tile_count: self.show_tile_count(),
+        }
+    }
+}
+
+impl Exportable<Puzzle> for (usize, &PuzzleConfig) {
+    fn export(self, predefined: &Predefined) -> Puzzle {
+        let (index, puzzle) = self;
+        // The id defaults to the index of the puzzle, so it is only written if it differs.
+        let id = if *puzzle.id() == format!("{index}") {
+            None
+        } else {
+            Some(puzzle.id().clone())
+        };
+
+        Puzzle {
+            id,
+            name: puzzle.name().to_string(),
+            description: puzzle.description().clone(),
+            difficulty: puzzle.difficulty().export(predefined),
+            tiles: puzzle
+                .tiles()
+                .iter()
+                .enumerate()
+                .map(|tile| tile.export(predefined))
+                .collect(),
+            board: puzzle.board_config().export(predefined),
+            additional_info: puzzle
+                .additional_info()
+                .as_ref()
+                .map(|info| info.clone().into_iter().collect()),
+        }
+    }
+}
+
+impl Exportable<Option<PuzzleDifficulty>> for &Option<PuzzleDifficultyConfig> {
+    fn export(self, _: &Predefined) -> Option<PuzzleDifficulty> {
+        self.map(|difficulty| match difficulty {
+            PuzzleDifficultyConfig::Easy => PuzzleDifficulty::Easy,
+            PuzzleDifficultyConfig::Medium => PuzzleDifficulty::Medium,
+            PuzzleDifficultyConfig::Hard => PuzzleDifficulty::Hard,
+            PuzzleDifficultyConfig::Expert => PuzzleDifficulty::Expert,
+        })
+    }
+}
+
+/// Exports a tile at the given index in the tiles of its puzzle.
+/// The index determines the default color of the tile.
+impl Exportable<Tile> for (usize, &TileConfig) {
+    fn export(self, predefined: &Predefined) -> Tile {
+        let (index, tile) = self;
+        let has_default_color = tile.color() == ColorConfig::default_with_index(index);
+
+        let predefined_name = predefined.tile_names().into_iter().find(|&name| {
+            (index, TileLayout::Ref(name.to_string()))
+                .convert(predefined, &mut Custom::default())
+                .is_ok_and(|base| base == tile.base())
+        });
+        let layout = match predefined_name {
+            Some(name) => {
+                let reference = Tile::Ref(name.to_string());
+                let is_exact_match = (index, reference.clone())
+                    .convert(predefined, &mut Custom::default())
+                    .is_ok_and(|tiles| tiles == [tile.clone()]);
+                if is_exact_match {
+                    return reference;
+                }
+                TileLayout::Ref(name.to_string())
+            }
+            None => TileLayout::Custom(to_json_rows(tile.base(), |&cell| cell as i8)),
+        };
+
+        if has_default_color {
+            Tile::Layout(layout)
+        } else {
+            Tile::Custom {
+                layout,
+                color: Some(Color::Hex(tile.color().to_hex_string())),
+                count: None,
+            }
+        }
+    }
+}
+
+impl Exportable<Board> for &BoardConfig {
+    fn export(self, predefined: &Predefined) -> Board {
+        let (width, height) = self.layout().dim();
+        let dimension_name = format!("{width}x{height}");
+        let predefined_name = predefined
+            .board_names()
+            .into_iter()
+            .chain([dimension_name.as_str()])
+            .find(|&name| {
+                Board::Ref(name.to_string())
+                    .convert(predefined, &mut Custom::default())
+                    .is_ok_and(|board| board == *self)
+            });
+        if let Some(name) = predefined_name {
+            return Board::Ref(name.to_string());
+        }
+
+        match self {
+            BoardConfig::Simple { layout } => Board::SimpleBoard {
+                layout: to_json_rows(layout, |&on_board| if on_board { 0 } else { 1 }),
+            },
+            BoardConfig::Area {
+                layout,
+                area_indices,
+                display_values,
+                value_order,
+                area_configs,
+                target_template,
+            } => {
+                // Cells, which are not on the board, must not belong to an area.
+                let area_layout = Array2::from_shape_fn(area_indices.dim(), |(x, y)| {
+                    let area_index = area_indices[[x, y]];
+                    if layout[[x, y]] {
+                        area_index
+                    } else {
+                        area_index.min(-1)
+                    }
+                });
+                Board::AreaBoard {
+                    area_layout: to_json_rows(&area_layout, |&area_index| area_index),
+                    values: to_json_rows(display_values, String::clone),
+                    value_order: to_json_rows(value_order, |&order| order),
+                    areas: area_configs
+                        .iter()
+                        .map(|area| area.export(predefined))
+                        .collect(),
+                    target_template: target_template.as_str().to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl Exportable<Area> for &AreaConfig {
+    fn export(self, predefined: &Predefined) -> Area {
+        let formatter = match self.formatter() {
+            AreaValueFormatter::Plain => AreaFormatter::Plain,
+            AreaValueFormatter::Nth => AreaFormatter::Nth,
+            AreaValueFormatter::PrefixSuffix { prefix, suffix } => AreaFormatter::PrefixSuffix {
+                prefix: prefix.clone(),
+                suffix: suffix.clone(),
+            },
+        };
+
+        Area {
+            name: self.name().to_string(),
+            formatter,
+            default_factory: self.default_factory().export(predefined),
+        }
+    }
+}
+
+impl Exportable<DefaultFactory> for &DefaultValueFactory {
+    fn export(self, _: &Predefined) -> DefaultFactory {
+        match self {
+            DefaultValueFactory::Fixed(value) => DefaultFactory::Fixed {
+                value: value.clone(),
+            },
+            DefaultValueFactory::CurrentDay => DefaultFactory::CurrentDay,
+            DefaultValueFactory::CurrentMonthShort => DefaultFactory::CurrentMonthShort,
+            DefaultValueFactory::CurrentYear2FirstDigit => DefaultFactory::CurrentYear2FirstDigit,
+            DefaultValueFactory::CurrentYear2SecondDigit => DefaultFactory::CurrentYear2SecondDigit,
+            DefaultValueFactory::CurrentYear4FirstDigit => DefaultFactory::CurrentYear4FirstDigit,
+            DefaultValueFactory::CurrentYear4SecondDigit => DefaultFactory::CurrentYear4SecondDigit,
+        }
+    }
+}
+
+/// Converts an array of the config to the rows written in the JSON file.
+/// The arrays of the config are indexed by [x, y], so the array is transposed.
+fn to_json_rows<T, R>(array: &Array2<T>, map: impl Fn(&T) -> R) -> Vec<Vec<R>> {
+    array
+        .t()
+        .rows()
+        .into_iter()
+        .map(|row| row.iter().map(&map).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{create_json_loader, DefaultValueFactory};
+    use serde_json::Value;
+    use std::fs;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../puzzled/resources");
+
+    const PREDEFINED: &str = r#"
+    {
+        "tiles": {
+            "I3": [[1, 1, 1]],
+            "L3": [[1, 0], [1, 1]]
+        },
+        "boards": {}
+    }
+    "#;
+
+    #[test]
+    fn test_round_trip_core_collections() {
+        let predefined = fs::read_to_string(format!("{RESOURCES}/predefined.json")).unwrap();
+        let loader = create_json_loader(&predefined, env!("CARGO_PKG_VERSION")).unwrap();
+
+        let mut paths: Vec<_> = fs::read_dir(format!("{RESOURCES}/puzzles"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let json = fs::read_to_string(&path).unwrap();
+            let collection = loader.load_puzzle_collection(&json).unwrap();
+
+            let exported = loader.export_puzzle_collection(&collection);
+            let reloaded = loader.load_puzzle_collection(&exported);
+            assert_eq!(reloaded.as_ref().ok(), Some(&collection), "{path:?}");
+            assert_eq!(
+                loader.export_puzzle_collection(&reloaded.unwrap()),
+                exported
+            );
+        }
+    }
+
+    #[test]
+    fn test_export_uses_references() {
+        let loader = create_json_loader(PREDEFINED, "0.1.0").unwrap();
+        let json = r##"
+        {
+          "puzzled": "0.1.0",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "custom_tiles": {
+            "line": [[1, 1, 1]]
+          },
+          "puzzles": [
+            {
+              "name": "Puzzle",
+              "tiles": [
+                "line",
+                { "layout": "L3", "color": "#ff000080" },
+                [[1, 1], [1, 1]]
+              ],
+              "board": { "layout": [[0, 0, 0], [0, 0, 0], [0, 1, 1]] }
+            },
+            {
+              "id": "full",
+              "name": "Full Board",
+              "tiles": ["L3", "L3"],
+              "board": { "layout": [[0, 0, 0], [0, 0, 0]] }
+            }
+          ]
+        }
+        "##;
+        let collection = loader.load_puzzle_collection(json).unwrap();
+
+        let exported = loader.export_puzzle_collection(&collection);
+        let value: Value = serde_json::from_str(&exported).unwrap();
+        assert_eq!(value["puzzled"], "0.1.0");
+        assert_eq!(value["allow_board_rotation"], false);
+        assert!(value.get("custom_tiles").is_none());
+
+        let puzzle = &value["puzzles"][0];
+        assert!(puzzle.get("id").is_none());
+        assert_eq!(
+            puzzle["tiles"],
+            serde_json::json!([
+                "I3",
+                { "layout": "L3", "color": "#ff000080" },
+                [[1, 1], [1, 1]]
+            ])
+        );
+        assert_eq!(
+            puzzle["board"],
+            serde_json::json!({ "layout": [[0, 0, 0], [0, 0, 0], [0, 1, 1]] })
+        );
+
+        let puzzle = &value["puzzles"][1];
+        assert_eq!(puzzle["id"], "full");
+        assert_eq!(puzzle["tiles"], serde_json::json!(["L3", "L3"]));
+        // The board was rotated to landscape when it was loaded.
+        assert_eq!(puzzle["board"], "3x2");
+
+        let reloaded = loader.load_puzzle_collection(&exported).unwrap();
+        assert_eq!(reloaded, collection);
+    }
+
+    #[test]
+    fn test_export_area_board() {
+        let loader = create_json_loader(PREDEFINED, "0.1.0").unwrap();
+        let json = r#"
+        {
+          "puzzled": "0.1.0",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "puzzles": [
+            {
+              "name": "Area",
+              "tiles": ["I3"],
+              "board": {
+                "area_layout": [[0, 0, -1], [1, 1, 1]],
+                "values": [["A", "B", ""], ["1", "2", "3"]],
+                "value_order": [[0, 1, 0], [0, 1, 2]],
+                "areas": [
+                  {
+                    "name": "Letter",
+                    "formatter": { "type": "Plain" },
+                    "default_factory": { "type": "Fixed", "value": "B" }
+                  },
+                  {
+                    "name": "Day",
+                    "formatter": { "type": "Nth" },
+                    "default_factory": { "type": "CurrentDay" }
+                  }
+                ],
+                "target_template": "{0} {1}"
+              }
+            }
+          ]
+        }
+        "#;
+        let collection = loader.load_puzzle_collection(json).unwrap();
+
+        let exported = loader.export_puzzle_collection(&collection);
+        let value: Value = serde_json::from_str(&exported).unwrap();
+        let board = &value["puzzles"][0]["board"];
+        assert_eq!(
+            board["area_layout"],
+            serde_json::json!([[0, 0, -1], [1, 1, 1]])
+        );
+        assert_eq!(
+            board["areas"][1]["default_factory"],
+            serde_json::json!({ "type": "CurrentDay" })
+        );
+
+        let reloaded = loader.load_puzzle_collection(&exported).unwrap();
+        assert_eq!(reloaded, collection);
+        let area_configs = match reloaded.puzzles()[0].board_config() {
+            crate::BoardConfig::Area { area_configs, .. } => area_configs.clone(),
+            _ => panic!("expected an area board"),
+        };
+        assert_eq!(
+            area_configs[0].default_factory(),
+            &DefaultValueFactory::Fixed("B".to_string())
+        );
+        assert_eq!(
+            area_configs[1].default_factory(),
+            &DefaultValueFactory::CurrentDay
+        );
+    }
+}
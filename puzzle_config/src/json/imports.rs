@@ -0,0 +1,425 @@
+use crate::json::model::{Board, PuzzleCollection, Tile, TileLayout};
+use crate::json::predefined::{ConfigStore, Custom, Predefined};
+use crate::ReadError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The custom tiles and boards an installed collection exports to the collections importing it.
+struct ImportedCollection {
+    imports: Vec<String>,
+    exports: ConfigStore,
+}
+
+/// The installed collections, whose custom tiles and boards can be imported by their id.
+#[derive(Default)]
+pub(crate) struct ImportRegistry {
+    collections: HashMap<String, ImportedCollection>,
+}
+
+impl ImportRegistry {
+    /// Registers the custom tiles and boards of the collection.
+    /// A collection registered before with the same id is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection`: The collection with a valid id. The puzzles are not needed.
+    pub(crate) fn register(&mut self, collection: PuzzleCollection<Value, Tile, Board>) {
+        let mut exports = ConfigStore::default();
+        for (name, tile) in collection.custom_tiles.unwrap_or_default() {
+            exports.add_tile(name, tile);
+        }
+        for (name, board) in collection.custom_boards.unwrap_or_default() {
+            exports.add_board(name, board);
+        }
+
+        let imported = ImportedCollection {
+            imports: collection.imports.unwrap_or_default(),
+            exports,
+        };
+        self.collections.insert(collection.id, imported);
+    }
+
+    /// Removes the collection with the given id, so it can not be imported anymore.
+    /// Nothing happens, if no collection with the id is registered.
+    pub(crate) fn unregister(&mut self, collection_id: &str) {
+        self.collections.remove(collection_id);
+    }
+
+    /// Creates the store of the custom tiles and boards imported by a collection.
+    ///
+    /// The imports are layered in the order they are listed. Each imported collection provides
+    /// only its own custom tiles and boards, the ones it imports itself are not passed on. The
+    /// custom tiles and boards of the importing collection are added on top of the returned
+    /// store.
+    ///
+    /// # Arguments
+    ///
+    /// * `predefined`: The predefined tiles and boards.
+    /// * `collection_id`: The id of the importing collection.
+    /// * `imports`: The ids of the imported collections.
+    ///
+    /// returns: Result<Custom, ReadError>: An error, if an imported collection or one of its
+    /// own imports is not registered, or a collection imports itself.
+    pub(crate) fn resolve(
+        &self,
+        predefined: &Predefined,
+        collection_id: &str,
+        imports: &[String],
+    ) -> Result<Custom, ReadError> {
+        let mut custom = Custom::default();
+        let mut chain = vec![collection_id.to_string()];
+        for import in imports {
+            let exports = self.exports(predefined, import, &mut chain)?;
+            custom.add_layer(&exports);
+        }
+        Ok(custom)
+    }
+
+    /// Returns the own custom tiles and boards of a collection.
+    ///
+    /// They may refer to the tiles and boards the collection imports itself. These references
+    /// are replaced by what they refer to, so the exports do not depend on the imports of the
+    /// collection anymore. The chain contains the ids of the collections importing the current
+    /// one, to find cycles.
+    fn exports(
+        &self,
+        predefined: &Predefined,
+        collection_id: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<ConfigStore, ReadError> {
+        if let Some(start) = chain.iter().position(|id| id == collection_id) {
+            let mut collection_ids = chain[start..].to_vec();
+            collection_ids.push(collection_id.to_string());
+            return Err(ReadError::CyclicImport { collection_ids });
+        }
+        let imported =
+            self.collections
+                .get(collection_id)
+                .ok_or_else(|| ReadError::UnknownImport {
+                    collection_id: collection_id.to_string(),
+                })?;
+
+        let mut scope = Custom::default();
+        scope.add_layer(&imported.exports);
+        chain.push(collection_id.to_string());
+        for import in &imported.imports {
+            let exports = self.exports(predefined, import, chain)?;
+            scope.add_layer(&exports);
+        }
+        chain.pop();
+
+        let mut exports = ConfigStore::default();
+        for name in imported.exports.tile_names() {
+            let tile = imported.exports.get_tile(name).unwrap();
+            exports.add_tile(name.to_string(), inline_tile(tile, predefined, &scope));
+        }
+        for name in imported.exports.board_names() {
+            let board = imported.exports.get_board(name).unwrap();
+            exports.add_board(name.to_string(), inline_board(board, &scope));
+        }
+        Ok(exports)
+    }
+}
+
+/// Replaces the references to custom tiles in the tile by the tiles they refer to in the scope.
+/// References to predefined tiles are kept, because they are found the same way in every
+/// collection.
+fn inline_tile(tile: Tile, predefined: &Predefined, scope: &Custom) -> Tile {
+    match tile {
+        Tile::Ref(name) if predefined.get_tile(&name).is_none() => match scope.get_tile(&name) {
+            Some(tile) => inline_tile(tile, predefined, scope),
+            None => Tile::Ref(name),
+        },
+        Tile::Ref(name) => Tile::Ref(name),
+        Tile::Layout(layout) => Tile::Layout(inline_layout(layout, predefined, scope)),
+        Tile::Custom {
+            layout,
+            color,
+            count,
+        } => Tile::Custom {
+            layout: inline_layout(layout, predefined, scope),
+            color,
+            count,
+        },
+    }
+}
+
+/// Replaces a reference to a custom tile by the layout of the tile it refers to in the scope.
+/// Like when reading a layout, custom tiles take precedence over predefined ones.
+fn inline_layout(layout: TileLayout, predefined: &Predefined, scope: &Custom) -> TileLayout {
+    match layout {
+        TileLayout::Ref(name) => match scope.get_tile(&name) {
+            Some(tile) => match inline_tile(tile, predefined, scope) {
+                Tile::Ref(name) => match predefined.get_tile(&name) {
+                    Some(tile) => layout_of(tile),
+                    None => TileLayout::Ref(name),
+                },
+                tile => layout_of(tile),
+            },
+            None => TileLayout::Ref(name),
+        },
+        layout => layout,
+    }
+}
+
+/// Returns the layout of a tile, which is not a reference.
+fn layout_of(tile: Tile) -> TileLayout {
+    match tile {
+        Tile::Ref(name) => TileLayout::Ref(name),
+        Tile::Layout(layout) | Tile::Custom { layout, .. } => layout,
+    }
+}
+
+/// Replaces a reference to a custom board by the board it refers to in the scope.
+fn inline_board(board: Board, scope: &Custom) -> Board {
+    match board {
+        Board::Ref(name) => match scope.get_board(&name) {
+            Some(board) => inline_board(board, scope),
+            None => Board::Ref(name),
+        },
+        board => board,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{create_json_loader, JsonLoader, LintKind, ReadError};
+    use ndarray::arr2;
+
+    const PREDEFINED: &str = r#"{ "tiles": { "I3": [[1, 1, 1]] }, "boards": {} }"#;
+
+    const SHAPES: &str = r##"
+    {
+      "puzzled": "0.4.0",
+      "name": "Shapes",
+      "author": "Test Author",
+      "id": "de.til7701.Puzzled.shapes",
+      "imports": ["de.til7701.Puzzled.basics"],
+      "custom_tiles": {
+        "corner": [[1, 0], [1, 1]],
+        "big-line": { "layout": "line", "color": "#ff0000" }
+      },
+      "custom_boards": {
+        "square": { "layout": [[0, 0], [0, 0]] }
+      },
+      "puzzles": []
+    }
+    "##;
+
+    const BASICS: &str = r#"
+    {
+      "puzzled": "0.4.0",
+      "name": "Basics",
+      "author": "Test Author",
+      "id": "de.til7701.Puzzled.basics",
+      "custom_tiles": {
+        "line": [[1, 1, 1, 1]],
+        "corner": [[1, 1], [1, 1]]
+      },
+      "puzzles": []
+    }
+    "#;
+
+    fn collection_importing(imports: &str, tiles: &str, board: &str) -> String {
+        format!(
+            r#"
+            {{
+              "puzzled": "0.4.0",
+              "name": "Test",
+              "author": "Test Author",
+              "id": "de.til7701.Puzzled.test",
+              "imports": {imports},
+              "puzzles": [{{ "name": "Puzzle", "tiles": {tiles}, "board": {board} }}]
+            }}
+            "#
+        )
+    }
+
+    fn create_loader() -> JsonLoader {
+        let mut loader = create_json_loader(PREDEFINED, "0.4.0").unwrap();
+        loader.register_collection(SHAPES).unwrap();
+        loader.register_collection(BASICS).unwrap();
+        loader
+    }
+
+    #[test]
+    fn test_load_with_imports() {
+        let loader = create_loader();
+        let json = collection_importing(
+            r#"["de.til7701.Puzzled.shapes"]"#,
+            r#"["corner", "big-line", "I3"]"#,
+            r#""square""#,
+        );
+
+        let collection = loader.load_puzzle_collection(&json).unwrap();
+
+        let puzzle = &collection.puzzles()[0];
+        let bases: Vec<_> = puzzle.tiles().iter().map(|tile| tile.base()).collect();
+        // The corner of the shapes collection hides the one of the basics collection.
+        assert_eq!(
+            bases[0],
+            arr2(&[[true, false], [true, true]]).reversed_axes()
+        );
+        // The shapes collection refers to the line of the basics collection it imports.
+        assert_eq!(bases[1], arr2(&[[true, true, true, true]]).reversed_axes());
+        assert_eq!(puzzle.tiles()[1].color().to_hex_string(), "#ff0000");
+        assert_eq!(bases[2], arr2(&[[true, true, true]]).reversed_axes());
+        assert_eq!(puzzle.board_config().layout().dim(), (2, 2));
+    }
+
+    #[test]
+    fn test_imports_are_not_passed_on() {
+        let loader = create_loader();
+        let json = collection_importing(
+            r#"["de.til7701.Puzzled.shapes"]"#,
+            r#"["line"]"#,
+            r#""4x1""#,
+        );
+
+        let result = loader.load_puzzle_collection(&json);
+
+        assert_eq!(
+            result.err(),
+            Some(ReadError::UnknownPredefinedTile {
+                name: "line".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_imported_tiles_refer_to_their_own_imports() {
+        let loader = create_loader();
+        let json = r#"
+        {
+          "puzzled": "0.4.0",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "imports": ["de.til7701.Puzzled.shapes"],
+          "custom_tiles": { "line": [[1, 1]] },
+          "puzzles": [{ "name": "Puzzle", "tiles": ["big-line", "line"], "board": "3x2" }]
+        }
+        "#;
+
+        let collection = loader.load_puzzle_collection(json).unwrap();
+
+        let tiles = collection.puzzles()[0].tiles();
+        // The big line keeps referring to the line of the basics collection.
+        assert_eq!(
+            tiles[0].base(),
+            arr2(&[[true, true, true, true]]).reversed_axes()
+        );
+        assert_eq!(tiles[1].base(), arr2(&[[true, true]]).reversed_axes());
+    }
+
+    #[test]
+    fn test_unregister_collection() {
+        let mut loader = create_loader();
+        let json = collection_importing(
+            r#"["de.til7701.Puzzled.basics"]"#,
+            r#"["line"]"#,
+            r#""4x1""#,
+        );
+        assert!(loader.load_puzzle_collection(&json).is_ok());
+
+        loader.unregister_collection("de.til7701.Puzzled.basics");
+
+        assert_eq!(
+            loader.load_puzzle_collection(&json).err(),
+            Some(ReadError::UnknownImport {
+                collection_id: "de.til7701.Puzzled.basics".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_own_custom_tiles_hide_imports() {
+        let loader = create_loader();
+        let json = r#"
+        {
+          "puzzled": "0.4.0",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "imports": ["de.til7701.Puzzled.basics"],
+          "custom_tiles": { "line": [[1, 1]] },
+          "puzzles": [{ "name": "Puzzle", "tiles": ["line"], "board": "2x1" }]
+        }
+        "#;
+
+        let collection = loader.load_puzzle_collection(json).unwrap();
+
+        let base = collection.puzzles()[0].tiles()[0].base();
+        assert_eq!(base, arr2(&[[true, true]]).reversed_axes());
+    }
+
+    #[test]
+    fn test_unknown_import() {
+        let loader = create_loader();
+        let json =
+            collection_importing(r#"["de.til7701.Puzzled.missing"]"#, r#"["I3"]"#, r#""3x1""#);
+
+        let result = loader.load_puzzle_collection(&json);
+
+        assert_eq!(
+            result.err(),
+            Some(ReadError::UnknownImport {
+                collection_id: "de.til7701.Puzzled.missing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_cyclic_import() {
+        let mut loader = create_loader();
+        let basics = BASICS.replace(
+            r#""custom_tiles""#,
+            r#""imports": ["de.til7701.Puzzled.shapes"], "custom_tiles""#,
+        );
+        loader.register_collection(&basics).unwrap();
+        let json =
+            collection_importing(r#"["de.til7701.Puzzled.shapes"]"#, r#"["I3"]"#, r#""3x1""#);
+
+        let result = loader.load_puzzle_collection(&json);
+
+        assert_eq!(
+            result.err(),
+            Some(ReadError::CyclicImport {
+                collection_ids: vec![
+                    "de.til7701.Puzzled.shapes".to_string(),
+                    "de.til7701.Puzzled.basics".to_string(),
+                    "de.til7701.Puzzled.shapes".to_string(),
+                ]
+            })
+        );
+
+        let result = loader.load_puzzle_collection(SHAPES);
+        assert!(matches!(result, Err(ReadError::CyclicImport { .. })));
+    }
+
+    #[test]
+    fn test_lint_imports() {
+        let loader = create_loader();
+        let json = collection_importing(
+            r#"["de.til7701.Puzzled.shapes"]"#,
+            r#"["big-line"]"#,
+            r#""3x1""#,
+        );
+        let issues = loader.lint_collection(&json);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0].kind(),
+            LintKind::TileAreaMismatch { .. }
+        ));
+
+        let json =
+            collection_importing(r#"["de.til7701.Puzzled.missing"]"#, r#"["I3"]"#, r#""3x1""#);
+        let issues = loader.lint_collection(&json);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path(), "/imports");
+        assert!(matches!(
+            issues[0].kind(),
+            LintKind::Error(ReadError::UnknownImport { .. })
+        ));
+    }
+}
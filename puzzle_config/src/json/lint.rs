@@ -0,0 +1,681 @@
+use crate::json::converter::Convertable;
+use crate::json::model::{Board, DefaultFactory, Puzzle, PuzzleCollection, Tile, TileLayout};
+use crate::json::predefined::{Custom, Predefined};
+use crate::json::JsonLoader;
+use crate::{validation, DefaultValueFactory, ReadError, PUZZLED_VERSION_FIELD};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use time::Month;
+
+/// How severe a problem found by the linter is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintSeverity {
+    /// The collection can not be loaded.
+    Error,
+    /// The collection can be loaded, but it likely contains a mistake.
+    Warning,
+}
+
+/// A problem found by the linter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintKind {
+    /// An error, which prevents the collection from being loaded.
+    Error(ReadError),
+    /// The tiles of a puzzle cover a different number of cells than the board has to be filled
+    /// with. For a board with areas, one cell of each area stays empty.
+    TileAreaMismatch {
+        tiles_area: usize,
+        board_area: usize,
+    },
+    /// More than one puzzle has the same id. Puzzles without an id use their index as id.
+    DuplicatePuzzleId { id: String },
+    /// A tile of the `custom_tiles` section is not used by any puzzle.
+    UnusedCustomTile { name: String },
+    /// A board of the `custom_boards` section is not used by any puzzle.
+    UnusedCustomBoard { name: String },
+    /// A value the default factory of an area may produce is not a value of the area on the
+    /// board, so no default target can be selected.
+    DefaultValueNotInArea { area: String, value: String },
+}
+
+impl LintKind {
+    /// Returns whether the problem prevents the collection from being loaded.
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            LintKind::Error(_) => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+/// A problem found by the linter and where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// The JSON pointer to the value causing the problem, e.g. `/puzzles/2/tiles/0`.
+    path: String,
+    /// The name of the puzzle the value belongs to, if any.
+    puzzle_name: Option<String>,
+    kind: LintKind,
+}
+
+impl LintIssue {
+    /// Returns the JSON pointer to the value causing the problem.
+    /// It is empty, if the problem concerns the whole document.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the name of the puzzle the problem belongs to, if any.
+    pub fn puzzle_name(&self) -> Option<&str> {
+        self.puzzle_name.as_deref()
+    }
+
+    pub fn kind(&self) -> &LintKind {
+        &self.kind
+    }
+
+    pub fn severity(&self) -> LintSeverity {
+        self.kind.severity()
+    }
+}
+
+/// Check the collection and report every problem found.
+///
+/// The collection, each custom tile and board, each puzzle and each of their tiles and boards
+/// are read on their own, so a broken value does not hide the problems of the others.
+pub(crate) fn lint_collection(loader: &JsonLoader, json_str: &str) -> Vec<LintIssue> {
+    let mut linter = Linter::new(&loader.predefined);
+    let mut value: Value = match serde_json::from_str(json_str) {
+        Ok(value) => value,
+        Err(e) => {
+            linter.error(String::new(), None, ReadError::JsonError(e.to_string()));
+            return linter.issues;
+        }
+    };
+
+    // The pointers of the issues refer to the collection upgraded to the current format.
+    if let Err(e) = loader.migrate(&mut value) {
+        linter.error(pointer("", PUZZLED_VERSION_FIELD), None, e);
+    }
+    if let Some(collection) =
+        linter.parse::<PuzzleCollection<Value, Value, Value>>(&value, String::new(), None)
+    {
+        let imports = collection.imports.as_deref().unwrap_or_default();
+        match loader
+            .imports
+            .resolve(&loader.predefined, &collection.id, imports)
+        {
+            Ok(custom) => linter.custom = custom,
+            Err(e) => linter.error(pointer("", "imports"), None, e),
+        }
+        if let Err(e) = validation::validate_collection_id(collection.id) {
+            linter.error(pointer("", "id"), None, e);
+        }
+    }
+
+    let empty = Map::new();
+    let object = value.as_object().unwrap_or(&empty);
+    let custom_tiles = object
+        .get("custom_tiles")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let custom_boards = object
+        .get("custom_boards")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let puzzles = object
+        .get("puzzles")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    linter.lint_custom_tiles(custom_tiles);
+    linter.lint_custom_boards(custom_boards);
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        linter.lint_puzzle(i, puzzle, &mut ids);
+    }
+
+    for name in custom_tiles.keys() {
+        if !linter.used_tiles.contains(name) {
+            let kind = LintKind::UnusedCustomTile { name: name.clone() };
+            linter.report(pointer("/custom_tiles", name), None, kind);
+        }
+    }
+    for name in custom_boards.keys() {
+        if !linter.used_boards.contains(name) {
+            let kind = LintKind::UnusedCustomBoard { name: name.clone() };
+            linter.report(pointer("/custom_boards", name), None, kind);
+        }
+    }
+    linter.issues
+}
+
+/// Appends the key to the JSON pointer, escaping it as described in RFC 6901.
+fn pointer(parent: &str, key: &str) -> String {
+    format!("{parent}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Returns the name of the predefined or custom tile the tile refers to, if any.
+fn referenced_tile(tile: &Tile) -> Option<&str> {
+    match tile {
+        Tile::Ref(name)
+        | Tile::Layout(TileLayout::Ref(name))
+        | Tile::Custom {
+            layout: TileLayout::Ref(name),
+            ..
+        } => Some(name),
+        _ => None,
+    }
+}
+
+/// Returns every value the default factory may produce.
+/// The values depending on the year are only known for the current year.
+fn default_values(factory: &DefaultFactory) -> Vec<String> {
+    match factory {
+        DefaultFactory::CurrentDay => (1..=31).map(|day| day.to_string()).collect(),
+        DefaultFactory::CurrentMonthShort => {
+            let mut month = Month::January;
+            let mut values = Vec::new();
+            for _ in 0..12 {
+                values.push(month.to_string()[0..3].to_string());
+                month = month.next();
+            }
+            values
+        }
+        _ => factory
+            .clone()
+            .convert(&Predefined::default(), &mut Custom::default())
+            .map(|factory: DefaultValueFactory| factory.produce())
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn count_cells<'a>(cells: impl IntoIterator<Item = &'a bool>) -> usize {
+    cells.into_iter().filter(|&&cell| cell).count()
+}
+
+struct Linter<'a> {
+    predefined: &'a Predefined,
+    custom: Custom,
+    /// Custom tiles and boards, which could not be read. Their errors are reported once at
+    /// their definition and not again for every puzzle using them.
+    invalid_tiles: HashSet<String>,
+    invalid_boards: HashSet<String>,
+    used_tiles: HashSet<String>,
+    used_boards: HashSet<String>,
+    issues: Vec<LintIssue>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(predefined: &'a Predefined) -> Self {
+        Linter {
+            predefined,
+            custom: Custom::default(),
+            invalid_tiles: HashSet::new(),
+            invalid_boards: HashSet::new(),
+            used_tiles: HashSet::new(),
+            used_boards: HashSet::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, path: String, puzzle_name: Option<&str>, kind: LintKind) {
+        self.issues.push(LintIssue {
+            path,
+            puzzle_name: puzzle_name.map(str::to_string),
+            kind,
+        });
+    }
+
+    fn error(&mut self, path: String, puzzle_name: Option<&str>, error: ReadError) {
+        self.report(path, puzzle_name, LintKind::Error(error));
+    }
+
+    /// Reads the value as the given model type and reports an error, if it does not match.
+    fn parse<T: DeserializeOwned>(
+        &mut self,
+        value: &Value,
+        path: String,
+        puzzle_name: Option<&str>,
+    ) -> Option<T> {
+        match serde_json::from_value(value.clone()) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                self.error(path, puzzle_name, ReadError::JsonError(e.to_string()));
+                None
+            }
+        }
+    }
+
+    fn lint_custom_tiles(&mut self, tiles: &Map<String, Value>) {
+        // All tiles are added first, since custom tiles may refer to each other.
+        let mut parsed = Vec::new();
+        for (name, value) in tiles {
+            let path = pointer("/custom_tiles", name);
+            match self.parse::<Tile>(value, path.clone(), None) {
+                Some(tile) => {
+                    if let Some(reference) = referenced_tile(&tile) {
+                        self.used_tiles.insert(reference.to_string());
+                    }
+                    self.custom.add_tile(name.clone(), tile.clone());
+                    parsed.push((name, path, tile));
+                }
+                None => {
+                    self.invalid_tiles.insert(name.clone());
+                }
+            }
+        }
+
+        for (name, path, tile) in parsed {
+            if let Err(e) = (0, tile).convert(self.predefined, &mut self.custom) {
+                self.error(path, None, e);
+                self.invalid_tiles.insert(name.clone());
+            }
+        }
+    }
+
+    fn lint_custom_boards(&mut self, boards: &Map<String, Value>) {
+        for (name, value) in boards {
+            let path = pointer("/custom_boards", name);
+            let Some(board) = self.parse::<Board>(value, path.clone(), None) else {
+                self.invalid_boards.insert(name.clone());
+                continue;
+            };
+            self.custom.add_board(name.clone(), board.clone());
+            self.lint_areas(&path, None, &board);
+            if let Err(e) = board.convert(self.predefined, &mut self.custom) {
+                self.error(path, None, e);
+                self.invalid_boards.insert(name.clone());
+            }
+        }
+    }
+
+    fn lint_puzzle(&mut self, index: usize, value: &Value, ids: &mut HashMap<String, usize>) {
+        let path = format!("/puzzles/{index}");
+        let name = value.get("name").and_then(Value::as_str);
+
+        if let Some(puzzle) = self.parse::<Puzzle<Value, Value>>(value, path.clone(), name) {
+            let id = puzzle.id.unwrap_or_else(|| format!("{index}"));
+            if ids.insert(id.clone(), index).is_some() {
+                let id_path = if value.get("id").is_some() {
+                    pointer(&path, "id")
+                } else {
+                    path.clone()
+                };
+                self.report(id_path, name, LintKind::DuplicatePuzzleId { id });
+            }
+        }
+
+        let tiles_area = match value.get("tiles").and_then(Value::as_array) {
+            Some(tiles) => self.lint_puzzle_tiles(&path, name, tiles),
+            None => None,
+        };
+        let board_area = match value.get("board") {
+            Some(board) => self.lint_puzzle_board(&path, name, board),
+            None => None,
+        };
+        if let (Some(tiles_area), Some(board_area)) = (tiles_area, board_area)
+            && tiles_area != board_area
+        {
+            let kind = LintKind::TileAreaMismatch {
+                tiles_area,
+                board_area,
+            };
+            self.report(path, name, kind);
+        }
+    }
+
+    /// Checks the tiles of a puzzle and returns the number of cells they cover, if all of them
+    /// are valid.
+    fn lint_puzzle_tiles(
+        &mut self,
+        puzzle_path: &str,
+        puzzle_name: Option<&str>,
+        tiles: &[Value],
+    ) -> Option<usize> {
+        let mut area = Some(0);
+        for (j, value) in tiles.iter().enumerate() {
+            let path = format!("{puzzle_path}/tiles/{j}");
+            let Some(tile) = self.parse::<Tile>(value, path.clone(), puzzle_name) else {
+                area = None;
+                continue;
+            };
+            if let Some(reference) = referenced_tile(&tile) {
+                self.used_tiles.insert(reference.to_string());
+                if self.invalid_tiles.contains(reference) {
+                    area = None;
+                    continue;
+                }
+            }
+            match (j, tile).convert(self.predefined, &mut self.custom) {
+                Ok(tile_configs) => {
+                    let tile_area: usize = tile_configs
+                        .iter()
+                        .map(|tile_config| count_cells(tile_config.base()))
+                        .sum();
+                    area = area.map(|area| area + tile_area);
+                }
+                Err(e) => {
+                    self.error(path, puzzle_name, e);
+                    area = None;
+                }
+            }
+        }
+        area
+    }
+
+    /// Checks the board of a puzzle and returns the number of cells the tiles have to cover,
+    /// if the board is valid.
+    fn lint_puzzle_board(
+        &mut self,
+        puzzle_path: &str,
+        puzzle_name: Option<&str>,
+        value: &Value,
+    ) -> Option<usize> {
+        let path = pointer(puzzle_path, "board");
+        let board = self.parse::<Board>(value, path.clone(), puzzle_name)?;
+        if let Board::Ref(name) = &board {
+            self.used_boards.insert(name.clone());
+            if self.invalid_boards.contains(name) {
+                return None;
+            }
+        }
+
+        self.lint_areas(&path, puzzle_name, &board);
+        match board.convert(self.predefined, &mut self.custom) {
+            Ok(board_config) => {
+                let cells = count_cells(board_config.layout());
+                Some(cells.saturating_sub(board_config.area_count()))
+            }
+            Err(ReadError::UnknownCustomBoard { board_name, .. }) => {
+                let e = ReadError::UnknownCustomBoard {
+                    puzzle_name: puzzle_name.unwrap_or_default().to_string(),
+                    board_name,
+                };
+                self.error(path, puzzle_name, e);
+                None
+            }
+            Err(e) => {
+                self.error(path, puzzle_name, e);
+                None
+            }
+        }
+    }
+
+    /// Checks that every default value of the areas of the board is a value of its area.
+    fn lint_areas(&mut self, board_path: &str, puzzle_name: Option<&str>, board: &Board) {
+        let Board::AreaBoard {
+            area_layout,
+            values,
+            areas,
+            ..
+        } = board
+        else {
+            return;
+        };
+
+        for (k, area) in areas.iter().enumerate() {
+            let area_values: HashSet<&str> = area_layout
+                .iter()
+                .enumerate()
+                .flat_map(|(i, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|&(_, &area_index)| area_index == k as i32)
+                        .map(move |(j, _)| (i, j))
+                })
+                .filter_map(|(i, j)| values.get(i).and_then(|row| row.get(j)))
+                .map(String::as_str)
+                .collect();
+
+            for value in default_values(&area.default_factory) {
+                if !area_values.contains(value.as_str()) {
+                    let path = format!("{board_path}/areas/{k}/default_factory");
+                    let kind = LintKind::DefaultValueNotInArea {
+                        area: area.name.clone(),
+                        value,
+                    };
+                    self.report(path, puzzle_name, kind);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_json_loader;
+    use std::fs;
+
+    const PREDEFINED: &str = r#"
+    {
+        "tiles": {
+            "L3": [
+                [1, 0],
+                [1, 1]
+            ]
+        },
+        "boards": {}
+    }
+    "#;
+
+    fn lint(json_str: &str) -> Vec<LintIssue> {
+        let json_loader = create_json_loader(PREDEFINED, "0.1.0").unwrap();
+        json_loader.lint_collection(json_str)
+    }
+
+    fn issue(path: &str, puzzle_name: Option<&str>, kind: LintKind) -> LintIssue {
+        LintIssue {
+            path: path.to_string(),
+            puzzle_name: puzzle_name.map(str::to_string),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_lint_valid_collection() {
+        let issues = lint(
+            r#"
+            {
+                "puzzled": "0.1.0",
+                "name": "Test",
+                "author": "Test Author",
+                "id": "de.til7701.Puzzled.test",
+                "puzzles": [
+                    { "name": "Simple", "tiles": ["L3", "L3"], "board": "2x3" }
+                ]
+            }
+            "#,
+        );
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn test_lint_invalid_json() {
+        let issues = lint("{");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path(), "");
+        assert_eq!(issues[0].severity(), LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_lint_reports_all_problems() {
+        let issues = lint(
+            r#"
+            {
+                "puzzled": "0.2.0",
+                "name": "Test",
+                "author": "Test Author",
+                "id": "invalid id",
+                "custom_tiles": {
+                    "unused/tile": [[1]],
+                    "empty": []
+                },
+                "custom_boards": {
+                    "unused": { "layout": [[0]] }
+                },
+                "puzzles": [
+                    { "name": "A", "id": "a", "tiles": ["L3", [[1], []]], "board": "2x3" },
+                    { "name": "B", "id": "a", "tiles": ["unknown", "empty"], "board": "missing" },
+                    { "name": "C", "tiles": ["L3"], "board": "2x3" }
+                ]
+            }
+            "#,
+        );
+        assert_eq!(
+            issues,
+            vec![
+                issue(
+                    "/puzzled",
+                    None,
+                    LintKind::Error(ReadError::UnsupportedVersion)
+                ),
+                issue(
+                    "/id",
+                    None,
+                    LintKind::Error(ReadError::InvalidCollectionId("invalid id".to_string()))
+                ),
+                issue(
+                    "/custom_tiles/empty",
+                    None,
+                    LintKind::Error(ReadError::TileWidthOrHeightCannotBeZero)
+                ),
+                issue(
+                    "/puzzles/0/tiles/1",
+                    Some("A"),
+                    LintKind::Error(ReadError::TileWidthOrHeightCannotBeZero)
+                ),
+                issue(
+                    "/puzzles/1/id",
+                    Some("B"),
+                    LintKind::DuplicatePuzzleId {
+                        id: "a".to_string()
+                    }
+                ),
+                issue(
+                    "/puzzles/1/tiles/0",
+                    Some("B"),
+                    LintKind::Error(ReadError::UnknownPredefinedTile {
+                        name: "unknown".to_string()
+                    })
+                ),
+                issue(
+                    "/puzzles/1/board",
+                    Some("B"),
+                    LintKind::Error(ReadError::UnknownCustomBoard {
+                        puzzle_name: "B".to_string(),
+                        board_name: "missing".to_string()
+                    })
+                ),
+                issue(
+                    "/puzzles/2",
+                    Some("C"),
+                    LintKind::TileAreaMismatch {
+                        tiles_area: 3,
+                        board_area: 6
+                    }
+                ),
+                issue(
+                    "/custom_tiles/unused~1tile",
+                    None,
+                    LintKind::UnusedCustomTile {
+                        name: "unused/tile".to_string()
+                    }
+                ),
+                issue(
+                    "/custom_boards/unused",
+                    None,
+                    LintKind::UnusedCustomBoard {
+                        name: "unused".to_string()
+                    }
+                ),
+            ]
+        );
+        assert!(issues
+            .iter()
+            .all(|issue| issue.kind().severity() == issue.severity()));
+    }
+
+    #[test]
+    fn test_lint_area_default_values() {
+        let issues = lint(
+            r#"
+            {
+                "puzzled": "0.1.0",
+                "name": "Test",
+                "author": "Test Author",
+                "id": "de.til7701.Puzzled.test",
+                "puzzles": [
+                    {
+                        "name": "Areas",
+                        "tiles": [[[1, 1]]],
+                        "board": {
+                            "area_layout": [[0, 0], [1, 1]],
+                            "values": [["a", "b"], ["Jan", "Feb"]],
+                            "value_order": [[0, 1], [0, 1]],
+                            "areas": [
+                                {
+                                    "name": "Letter",
+                                    "formatter": { "type": "Plain" },
+                                    "default_factory": { "type": "Fixed", "value": "c" }
+                                },
+                                {
+                                    "name": "Month",
+                                    "formatter": { "type": "Plain" },
+                                    "default_factory": { "type": "CurrentMonthShort" }
+                                }
+                            ],
+                            "target_template": "{0} {1}"
+                        }
+                    }
+                ]
+            }
+            "#,
+        );
+        let values: Vec<(&str, &LintKind)> = issues
+            .iter()
+            .map(|issue| (issue.path(), issue.kind()))
+            .collect();
+        assert_eq!(values.len(), 1 + 10);
+        assert_eq!(
+            values[0],
+            (
+                "/puzzles/0/board/areas/0/default_factory",
+                &LintKind::DefaultValueNotInArea {
+                    area: "Letter".to_string(),
+                    value: "c".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            values[1],
+            (
+                "/puzzles/0/board/areas/1/default_factory",
+                &LintKind::DefaultValueNotInArea {
+                    area: "Month".to_string(),
+                    value: "Mar".to_string()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_lint_core_collections() {
+        let predefined_json_str =
+            fs::read_to_string("../puzzled/resources/predefined.json").unwrap();
+        let json_loader = create_json_loader(&predefined_json_str, "0.4.0").unwrap();
+        for entry in fs::read_dir("../puzzled/resources/puzzles").unwrap() {
+            let json_str = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let errors: Vec<LintIssue> = json_loader
+                .lint_collection(&json_str)
+                .into_iter()
+                .filter(|issue| issue.severity() == LintSeverity::Error)
+                .collect();
+            assert_eq!(errors, vec![]);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{ReadError, PUZZLED_VERSION_FIELD};
+use semver::Version;
+use serde_json::{Map, Value};
+
+/// A step of the migration pipeline.
+/// It upgrades a collection written for an older puzzled version to the format introduced in
+/// `version`.
+pub(crate) struct Migration {
+    /// The puzzled version, which introduced the format this step migrates to.
+    pub(crate) version: Version,
+    /// Rewrites the collection object to the format of `version`.
+    pub(crate) migrate: fn(&mut Map<String, Value>) -> Result<(), ReadError>,
+}
+
+/// Returns the migrations of the collection format, ordered by version.
+///
+/// Every change of the format up to now only added optional fields, so collections of older
+/// versions can be read as they are and no migration is needed yet. When a field is renamed or
+/// its meaning changes, add a step here, which rewrites older collections to the new format.
+pub(crate) fn migrations() -> Vec<Migration> {
+    Vec::new()
+}
+
+/// Upgrades the collection step by step from its declared version to the target version.
+///
+/// Every migration with a version newer than the declared version and not newer than the target
+/// version is applied in order. Afterward, the collection declares the target version.
+///
+/// # Arguments
+///
+/// * `value`: The JSON value of the collection.
+/// * `declared_version`: The puzzled version the collection was written for.
+/// * `target_version`: The puzzled version to upgrade the collection to.
+/// * `migrations`: The migration steps ordered by version.
+///
+/// returns: Result<(), ReadError>
+pub(crate) fn migrate(
+    value: &mut Value,
+    declared_version: &Version,
+    target_version: &Version,
+    migrations: &[Migration],
+) -> Result<(), ReadError> {
+    let object = value.as_object_mut().ok_or(ReadError::MissingVersion)?;
+    for migration in migrations {
+        if *declared_version < migration.version && migration.version <= *target_version {
+            (migration.migrate)(object)?;
+        }
+    }
+
+    if declared_version < target_version {
+        object.insert(
+            PUZZLED_VERSION_FIELD.to_string(),
+            Value::String(target_version.to_string()),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_json_loader;
+    use serde_json::json;
+    use std::fs;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../puzzled/resources");
+
+    const PREDEFINED: &str = r#"{ "tiles": { "I3": [[1, 1, 1]] }, "boards": {} }"#;
+
+    /// Renames the field `title` of each puzzle to `name`.
+    fn rename_title(collection: &mut Map<String, Value>) -> Result<(), ReadError> {
+        let puzzles = collection
+            .get_mut("puzzles")
+            .and_then(Value::as_array_mut)
+            .ok_or_else(|| ReadError::JsonError("puzzles must be an array".to_string()))?;
+        for puzzle in puzzles.iter_mut().filter_map(Value::as_object_mut) {
+            if let Some(title) = puzzle.remove("title") {
+                puzzle.insert("name".to_string(), title);
+            }
+        }
+        Ok(())
+    }
+
+    fn append_to_description(collection: &mut Map<String, Value>) -> Result<(), ReadError> {
+        let description = collection
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let description = format!("{description}!");
+        collection.insert("description".to_string(), Value::String(description));
+        Ok(())
+    }
+
+    fn fail(_: &mut Map<String, Value>) -> Result<(), ReadError> {
+        Err(ReadError::JsonError("migration failed".to_string()))
+    }
+
+    fn version(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    fn test_migrations() -> Vec<Migration> {
+        vec![
+            Migration {
+                version: version("0.2.0"),
+                migrate: fail,
+            },
+            Migration {
+                version: version("0.3.0"),
+                migrate: rename_title,
+            },
+            Migration {
+                version: version("0.3.1"),
+                migrate: append_to_description,
+            },
+            Migration {
+                version: version("0.4.0"),
+                migrate: append_to_description,
+            },
+            Migration {
+                version: version("0.5.0"),
+                migrate: fail,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_migrate_applies_steps_in_range() {
+        let mut value = json!({
+            "puzzled": "0.2.0",
+            "description": "Old",
+            "puzzles": [{ "title": "First" }]
+        });
+
+        let result = migrate(
+            &mut value,
+            &version("0.2.0"),
+            &version("0.4.0"),
+            &test_migrations(),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(
+            value,
+            json!({
+                "puzzled": "0.4.0",
+                "description": "Old!!",
+                "puzzles": [{ "name": "First" }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrate_current_version() {
+        let mut value = json!({ "puzzled": "0.4.0", "description": "Current" });
+
+        let result = migrate(
+            &mut value,
+            &version("0.4.0"),
+            &version("0.4.0"),
+            &test_migrations(),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(
+            value,
+            json!({ "puzzled": "0.4.0", "description": "Current" })
+        );
+    }
+
+    #[test]
+    fn test_migrate_error() {
+        let mut value = json!({ "puzzled": "0.4.0" });
+
+        let result = migrate(
+            &mut value,
+            &version("0.4.0"),
+            &version("0.5.0"),
+            &test_migrations(),
+        );
+
+        assert!(matches!(result, Err(ReadError::JsonError(_))));
+    }
+
+    #[test]
+    fn test_upgrade_collection() {
+        let mut loader = create_json_loader(PREDEFINED, "0.4.0").unwrap();
+        loader.migrations = test_migrations();
+        let json = r#"
+        {
+          "puzzled": "0.2.1",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "puzzles": [{ "title": "Puzzle", "tiles": ["I3"], "board": "3x1" }]
+        }
+        "#;
+
+        let upgraded = loader.upgrade_collection(json).unwrap();
+
+        assert!(upgraded.starts_with("{\n  \"puzzled\": \"0.4.0\",\n  \"name\": \"Test\","));
+        let collection = loader.load_puzzle_collection(json).unwrap();
+        assert_eq!(collection.puzzles()[0].name(), "Puzzle");
+        assert_eq!(collection.description(), &Some("!!".to_string()));
+        assert_eq!(
+            loader.load_puzzle_collection(&upgraded).unwrap(),
+            collection
+        );
+    }
+
+    #[test]
+    fn test_upgrade_collection_file() {
+        let loader = create_json_loader(PREDEFINED, "0.4.0").unwrap();
+        let path =
+            std::env::temp_dir().join(format!("puzzle_config_upgrade_{}.json", std::process::id()));
+        let json = r#"
+        {
+          "puzzled": "0.2.0",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "puzzles": []
+        }
+        "#;
+        fs::write(&path, json).unwrap();
+
+        let result = loader.upgrade_collection_file(&path);
+        let upgraded = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        let value: Value = serde_json::from_str(&upgraded).unwrap();
+        assert_eq!(value["puzzled"], "0.4.0");
+        assert_eq!(value["name"], "Test");
+    }
+
+    #[test]
+    fn test_upgrade_core_collections() {
+        let predefined = fs::read_to_string(format!("{RESOURCES}/predefined.json")).unwrap();
+        let loader = create_json_loader(&predefined, env!("CARGO_PKG_VERSION")).unwrap();
+
+        for entry in fs::read_dir(format!("{RESOURCES}/puzzles")).unwrap() {
+            let path = entry.unwrap().path();
+            let json = fs::read_to_string(&path).unwrap();
+
+            let upgraded = loader.upgrade_collection(&json).unwrap();
+
+            let collection = loader.load_puzzle_collection(&json).unwrap();
+            let reloaded = loader.load_puzzle_collection(&upgraded);
+            assert_eq!(reloaded.ok(), Some(collection), "{path:?}");
+        }
+    }
+}
@@ -1,19 +1,39 @@
 use crate::json::converter::Convertable;
-use crate::json::model::PuzzleCollection;
-use crate::json::predefined::{Custom, Predefined};
-use crate::{PuzzleConfigCollection, ReadError, PUZZLED_VERSION_FIELD};
+use crate::json::exporter::Exportable;
+use crate::json::imports::ImportRegistry;
+use crate::json::migration::Migration;
+use crate::json::model::{Board, PuzzleCollection, Tile};
+use crate::json::predefined::Predefined;
+use crate::{validation, PuzzleConfigCollection, ReadError, PUZZLED_VERSION_FIELD};
 use semver::{Version, VersionReq};
 use serde_json::Value;
+use std::fs;
+use std::path::Path;
 
 mod converter;
+mod exporter;
+mod imports;
+mod lint;
+mod migration;
 mod model;
 mod predefined;
+#[cfg(feature = "schema")]
+mod schema;
+
+pub use lint::{LintIssue, LintKind, LintSeverity};
+#[cfg(feature = "schema")]
+pub use schema::collection_schema;
 
 /// Loader for puzzle configuration from JSON strings.
 /// Instances can be reused to load multiple collections.
 pub struct JsonLoader {
     predefined: Predefined,
+    puzzled_version: Version,
     version_req: VersionReq,
+    migrations: Vec<Migration>,
+    imports: ImportRegistry,
+    #[cfg(feature = "schema")]
+    schema_validator: Option<jsonschema::Validator>,
 }
 
 impl JsonLoader {
@@ -27,19 +47,165 @@ impl JsonLoader {
         Self {
             predefined,
             version_req: VersionReq::parse(format!("<={}", puzzled_version).as_str()).unwrap(),
+            puzzled_version: Version::parse(&puzzled_version).unwrap(),
+            migrations: migration::migrations(),
+            imports: ImportRegistry::default(),
+            #[cfg(feature = "schema")]
+            schema_validator: None,
         }
     }
 
+    /// Enable or disable checking collections against the JSON Schema of the collection format.
+    ///
+    /// The schema is checked after upgrading a collection to the current format and before
+    /// reading it. A violation is reported with the JSON pointer of the offending value, which
+    /// is more precise than the errors of the JSON parser. Validation is disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled`: Whether collections are checked against the schema.
+    #[cfg(feature = "schema")]
+    pub fn set_schema_validation(&mut self, enabled: bool) {
+        self.schema_validator =
+            enabled.then(|| schema::create_validator(&self.puzzled_version.to_string()));
+    }
+
     /// Load a puzzle configuration collection from a JSON string.
+    /// Collections written for an older puzzled version are upgraded to the current format first.
     /// Returns an error if the JSON is invalid or the version is unsupported.
     pub fn load_puzzle_collection(
         &self,
         json_str: &str,
     ) -> Result<PuzzleConfigCollection, ReadError> {
-        let value: Value =
+        let mut value: Value =
+            serde_json::from_str(json_str).map_err(|e| ReadError::JsonError(e.to_string()))?;
+
+        self.migrate(&mut value)?;
+        self.load(value)
+    }
+
+    /// Register an installed collection, so other collections can import its custom tiles and
+    /// boards by its id.
+    ///
+    /// A collection lists the ids of the collections it uses in its `imports` field. Register
+    /// all installed collections before loading the collections importing them. The imports are
+    /// resolved when a collection is loaded, so the collections can be registered in any order.
+    /// A collection registered before with the same id is replaced. Importing a collection
+    /// provides only its own custom tiles and boards, not the ones it imports itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_str`: The JSON string of the installed collection.
+    ///
+    /// returns: Result<(), ReadError>: An error, if the custom tiles and boards of the
+    /// collection can not be read.
+    pub fn register_collection(&mut self, json_str: &str) -> Result<(), ReadError> {
+        let mut value: Value =
+            serde_json::from_str(json_str).map_err(|e| ReadError::JsonError(e.to_string()))?;
+        self.migrate(&mut value)?;
+
+        let mut collection = serde_json::from_value::<PuzzleCollection<Value, Tile, Board>>(value)
+            .map_err(|e| ReadError::JsonError(e.to_string()))?;
+        collection.id = validation::validate_collection_id(collection.id)?;
+        self.imports.register(collection);
+        Ok(())
+    }
+
+    /// Unregister an installed collection, so other collections can not import it anymore.
+    /// Nothing happens, if no collection with the id is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_id`: The id of the removed collection.
+    pub fn unregister_collection(&mut self, collection_id: &str) {
+        self.imports.unregister(collection_id);
+    }
+
+    /// Upgrade a collection to the format of the puzzled version of this loader.
+    ///
+    /// The collection is migrated step by step from the version it declares and is marked with
+    /// the puzzled version of this loader afterward. Unlike `export_puzzle_collection`, the
+    /// structure of the collection is kept, e.g. the custom tiles and the order of the fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_str`: The JSON string of the collection.
+    ///
+    /// returns: Result<String, ReadError>: The JSON string of the upgraded collection or an
+    /// error, if the collection can not be loaded.
+    pub fn upgrade_collection(&self, json_str: &str) -> Result<String, ReadError> {
+        let mut value: Value =
             serde_json::from_str(json_str).map_err(|e| ReadError::JsonError(e.to_string()))?;
 
-        let version: Result<i32, ReadError> = match &value {
+        self.migrate(&mut value)?;
+        // Only write collections, which can be loaded after the upgrade.
+        self.load(value.clone())?;
+        Ok(serde_json::to_string_pretty(&value).expect("Failed to serialize the collection"))
+    }
+
+    /// Upgrade the collection file at the given path in place.
+    /// See `upgrade_collection` for details. The file is not changed, if the upgrade fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path of the collection file.
+    ///
+    /// returns: Result<(), ReadError>
+    pub fn upgrade_collection_file(&self, path: &Path) -> Result<(), ReadError> {
+        let json_str =
+            fs::read_to_string(path).map_err(|e| ReadError::FileReadError(e.to_string()))?;
+        let upgraded = self.upgrade_collection(&json_str)?;
+        fs::write(path, upgraded + "\n").map_err(|e| ReadError::FileReadError(e.to_string()))
+    }
+
+    /// Check all puzzles, tiles and boards of a collection and report every problem found.
+    ///
+    /// Unlike `load_puzzle_collection`, this does not stop at the first error. Each issue
+    /// contains the JSON pointer to the value causing it and the name of the puzzle it belongs
+    /// to. Besides the errors, which prevent the collection from being loaded, warnings are
+    /// reported for mistakes like puzzles whose tiles do not cover the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_str`: The JSON string of the collection.
+    ///
+    /// returns: Vec<LintIssue>: Empty, if no problems were found.
+    pub fn lint_collection(&self, json_str: &str) -> Vec<LintIssue> {
+        lint::lint_collection(self, json_str)
+    }
+
+    /// Write a puzzle configuration collection as a JSON string, which can be loaded again.
+    ///
+    /// Tiles and boards matching a predefined tile or board are written as a reference to it.
+    /// The boards are written as they are in the config, so board rotation is disabled in the
+    /// written collection. The collection is marked with the puzzled version of this loader.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection`: The collection to write.
+    ///
+    /// returns: String
+    pub fn export_puzzle_collection(&self, collection: &PuzzleConfigCollection) -> String {
+        let mut exported: PuzzleCollection = collection.export(&self.predefined);
+        exported.puzzled_version = Some(self.puzzled_version.to_string());
+        serde_json::to_string_pretty(&exported).expect("Failed to serialize the collection")
+    }
+
+    /// Check the puzzled version of the collection and upgrade it to the format of the puzzled
+    /// version of this loader.
+    fn migrate(&self, value: &mut Value) -> Result<(), ReadError> {
+        let declared_version = self.check_version(value)?;
+        migration::migrate(
+            value,
+            &declared_version,
+            &self.puzzled_version,
+            &self.migrations,
+        )
+    }
+
+    /// Check the puzzled version of the collection and return it.
+    fn check_version(&self, value: &Value) -> Result<Version, ReadError> {
+        match value {
             Value::Object(object) => {
                 let version_value = object.get(PUZZLED_VERSION_FIELD);
                 match version_value {
@@ -47,7 +213,7 @@ impl JsonLoader {
                         let collection_version = Version::parse(s)
                             .map_err(|e| ReadError::InvalidVersion(e.to_string()))?;
                         if self.version_req.matches(&collection_version) {
-                            Ok(1)
+                            Ok(collection_version)
                         } else {
                             Err(ReadError::UnsupportedVersion)
                         }
@@ -56,18 +222,23 @@ impl JsonLoader {
                 }
             }
             _ => Err(ReadError::MissingVersion),
-        };
-        if version? == 1 {
-            self.load(value)
-        } else {
-            Err(ReadError::UnsupportedVersion)
         }
     }
 
     fn load(&self, json_data: Value) -> Result<PuzzleConfigCollection, ReadError> {
+        #[cfg(feature = "schema")]
+        if let Some(validator) = &self.schema_validator {
+            schema::validate(validator, &json_data)?;
+        }
         let result = serde_json::from_value::<PuzzleCollection>(json_data);
         match result {
-            Ok(collection) => collection.convert(&self.predefined, &mut Custom::default()),
+            Ok(collection) => {
+                let imports = collection.imports.as_deref().unwrap_or_default();
+                let mut custom = self
+                    .imports
+                    .resolve(&self.predefined, &collection.id, imports)?;
+                collection.convert(&self.predefined, &mut custom)
+            }
             Err(e) => Err(ReadError::JsonError(e.to_string())),
         }
     }
@@ -1,30 +1,50 @@
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroU32;
 
-#[derive(Deserialize)]
-pub struct PuzzleCollection {
+/// A puzzle collection as written in the JSON file.
+///
+/// The types of the puzzles, tiles and boards are generic, so the linter can read them as plain
+/// JSON values and check each of them on its own.
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PuzzleCollection<P = Puzzle, T = Tile, B = Board> {
+    /// The version of Puzzled the collection is made for.
+    /// Older versions of Puzzled refuse to load the collection.
+    #[serde(rename = "puzzled", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(required))]
+    pub puzzled_version: Option<String>,
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub author: String,
     pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
     #[serde(default = "default_true")]
     pub allow_board_rotation: bool,
     #[serde(default)]
     pub progression: Progression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub preview: Option<Preview>,
+    /// The ids of installed collections, whose custom tiles and boards are used in this
+    /// collection. The tiles and boards an imported collection imports itself are not included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Vec<String>>,
     /// Custom tiles to override or extend predefined tiles.
-    pub custom_tiles: Option<HashMap<String, Tile>>,
-    pub custom_boards: Option<HashMap<String, Board>>,
-    pub puzzles: Vec<Puzzle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_tiles: Option<HashMap<String, T>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_boards: Option<HashMap<String, B>>,
+    pub puzzles: Vec<P>,
 }
 
 fn default_true() -> bool {
     true
 }
 
-#[derive(Deserialize, Default)]
+#[derive(Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Progression {
     #[default]
@@ -32,7 +52,8 @@ pub enum Progression {
     Sequential,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Preview {
     #[serde(default = "default_true")]
     pub show_board: bool,
@@ -44,22 +65,28 @@ pub struct Preview {
     pub show_tile_count: bool,
 }
 
-#[derive(Deserialize)]
-pub struct Puzzle {
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Puzzle<T = Tile, B = Board> {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub difficulty: Option<PuzzleDifficulty>,
     /// The tiles to use in this puzzle. Can reference predefined tiles, custom tiles or define
     /// them inline.
-    pub tiles: Vec<Tile>,
-    pub board: Board,
+    pub tiles: Vec<T>,
+    pub board: B,
     /// Additional metadata for the puzzle.
     /// This is shown in the Puzzle Info dialog and may contain solution statistics or other info.
-    pub additional_info: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_info: Option<BTreeMap<String, String>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum PuzzleDifficulty {
     Easy,
     Medium,
@@ -67,7 +94,8 @@ pub enum PuzzleDifficulty {
     Expert,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Tile {
     /// Can either be predefined in the application or defined in the `custom_tiles` section.
@@ -75,12 +103,15 @@ pub enum Tile {
     Layout(TileLayout),
     Custom {
         layout: TileLayout,
+        #[serde(skip_serializing_if = "Option::is_none")]
         color: Option<Color>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         count: Option<NonZeroU32>,
     },
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum TileLayout {
     /// Can either be predefined in the application or defined in the `custom_tiles` section.
@@ -88,13 +119,15 @@ pub enum TileLayout {
     Custom(Vec<Vec<i8>>),
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Color {
     Hex(String),
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Board {
     Ref(String),
@@ -110,7 +143,8 @@ pub enum Board {
     },
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Area {
     pub name: String,
     pub formatter: AreaFormatter,
@@ -118,7 +152,8 @@ pub struct Area {
     pub default_factory: DefaultFactory,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum AreaFormatter {
     Plain,
@@ -130,7 +165,8 @@ pub enum AreaFormatter {
     },
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum DefaultFactory {
     Fixed {
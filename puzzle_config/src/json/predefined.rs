@@ -28,4 +28,37 @@ impl ConfigStore {
     pub fn get_board(&self, name: &str) -> Option<Board> {
         self.boards.get(name).cloned()
     }
+
+    /// Adds the tiles and boards of the given store, which are not in this store yet.
+    /// So the given store is a layer below this store.
+    ///
+    /// # Arguments
+    ///
+    /// * `lower`: The store to add the tiles and boards from.
+    pub fn add_layer(&mut self, lower: &ConfigStore) {
+        for (name, tile) in &lower.tiles {
+            self.tiles
+                .entry(name.clone())
+                .or_insert_with(|| tile.clone());
+        }
+        for (name, board) in &lower.boards {
+            self.boards
+                .entry(name.clone())
+                .or_insert_with(|| board.clone());
+        }
+    }
+
+    /// Returns the names of all tiles in the store, sorted by name.
+    pub fn tile_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.tiles.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns the names of all boards in the store, sorted by name.
+    pub fn board_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.boards.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
 }
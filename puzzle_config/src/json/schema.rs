@@ -0,0 +1,123 @@
+use crate::json::model::PuzzleCollection;
+use crate::ReadError;
+use jsonschema::Validator;
+use serde_json::Value;
+
+/// Generate the JSON Schema of the puzzle collection format.
+///
+/// The schema is generated from the types the collections are read into, so it accepts the same
+/// collections as the loader, apart from the checks made while converting them, like references
+/// to unknown tiles.
+///
+/// # Arguments
+///
+/// * `puzzled_version`: The puzzled version the schema is generated for.
+///
+/// returns: Value
+pub fn collection_schema(puzzled_version: &str) -> Value {
+    let mut schema = schemars::schema_for!(PuzzleCollection);
+    schema.insert("title".to_string(), "Puzzled Puzzle Collection".into());
+    schema.insert(
+        "description".to_string(),
+        format!("A puzzle collection for Puzzled {puzzled_version}.").into(),
+    );
+    schema.to_value()
+}
+
+/// Create a validator checking collections against the schema of the collection format.
+pub(crate) fn create_validator(puzzled_version: &str) -> Validator {
+    jsonschema::validator_for(&collection_schema(puzzled_version))
+        .expect("The generated collection schema is invalid")
+}
+
+/// Check the collection against the schema and return the first violation.
+pub(crate) fn validate(validator: &Validator, value: &Value) -> Result<(), ReadError> {
+    validator
+        .validate(value)
+        .map_err(|error| ReadError::SchemaViolation {
+            pointer: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_json_loader;
+    use std::fs;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../puzzled/resources");
+
+    /// The schema shipped for the current version.
+    /// Run the tests with `UPDATE_SCHEMA=1` to write it after changing the format.
+    const SCHEMA_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../docs/schema/puzzle-collection-",
+        env!("CARGO_PKG_VERSION"),
+        ".schema.json"
+    );
+
+    #[test]
+    fn test_schema_file_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&collection_schema(env!("CARGO_PKG_VERSION")))
+            .unwrap()
+            + "\n";
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            fs::write(SCHEMA_FILE, &schema).unwrap();
+        }
+        let shipped = fs::read_to_string(SCHEMA_FILE).unwrap_or_default();
+        assert!(
+            shipped == schema,
+            "{SCHEMA_FILE} is outdated, run the tests with UPDATE_SCHEMA=1"
+        );
+    }
+
+    #[test]
+    fn test_core_collections_match_schema() {
+        let validator = create_validator(env!("CARGO_PKG_VERSION"));
+        for entry in fs::read_dir(format!("{RESOURCES}/puzzles")).unwrap() {
+            let path = entry.unwrap().path();
+            let value: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            assert_eq!(validate(&validator, &value), Ok(()), "{path:?}");
+        }
+    }
+
+    #[test]
+    fn test_load_with_schema_validation() {
+        let predefined = r#"{ "tiles": { "I3": [[1, 1, 1]] }, "boards": {} }"#;
+        let mut loader = create_json_loader(predefined, "0.4.0").unwrap();
+        let json = r#"
+        {
+          "puzzled": "0.4.0",
+          "name": "Test",
+          "author": "Test Author",
+          "id": "de.til7701.Puzzled.test",
+          "puzzles": [
+            {
+              "name": "Puzzle",
+              "tiles": ["I3", { "layout": [[1]], "count": 0 }],
+              "board": "2x2"
+            }
+          ]
+        }
+        "#;
+        assert!(matches!(
+            loader.load_puzzle_collection(json),
+            Err(ReadError::JsonError(_))
+        ));
+
+        loader.set_schema_validation(true);
+        match loader.load_puzzle_collection(json) {
+            Err(ReadError::SchemaViolation { pointer, .. }) => {
+                assert_eq!(pointer, "/puzzles/0/tiles/1");
+            }
+            result => panic!("expected a schema violation, got {result:?}"),
+        }
+
+        loader.set_schema_validation(false);
+        assert!(matches!(
+            loader.load_puzzle_collection(json),
+            Err(ReadError::JsonError(_))
+        ));
+    }
+}
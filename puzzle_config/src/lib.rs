@@ -5,6 +5,7 @@ mod validation;
 
 pub use config::area::AreaConfig;
 pub use config::area::AreaValueFormatter;
+pub use config::area::DefaultValueFactory;
 pub use config::board::BoardConfig;
 pub use config::collection::PuzzleConfigCollection;
 pub use config::color::ColorConfig;
@@ -16,7 +17,10 @@ pub use config::puzzle::PuzzleId;
 pub use config::target::{Target, TargetIndex, TargetTemplate};
 pub use config::tile::TileConfig;
 pub use error::ReadError;
+#[cfg(feature = "schema")]
+pub use json::collection_schema;
 pub use json::JsonLoader;
+pub use json::{LintIssue, LintKind, LintSeverity};
 
 const PUZZLED_VERSION_FIELD: &str = "puzzled";
 
@@ -0,0 +1,157 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use puzzle_config::{BoardConfig, JsonLoader, PuzzleConfig, PuzzleConfigCollection, TargetIndex};
+use puzzle_solver::blocking::{count_all_solutions, solve_all_filling};
+use puzzle_solver::board::Board;
+use puzzle_solver::cancel::NeverCancel;
+use puzzle_solver::options::{SolutionSelection, SolverOptions, SolverStrategy};
+use puzzle_solver::tile::Tile;
+use std::fs;
+use std::hint::black_box;
+
+/// The directory of the core collections shipped with the app.
+const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../puzzled/resources");
+
+/// The (collection, puzzle id) pairs of the puzzles searched for their first solution.
+///
+/// The hexomino puzzles of the core collections take minutes to solve, so the largest puzzle is
+/// the rectangle of all pentominoes.
+const FIRST_SOLUTION: [(&str, &str); 6] = [
+    ("trominoes", "b"),
+    ("tetrominoes", "a"),
+    ("pentominoes", "3x10A"),
+    ("pentominoes", "6x10"),
+    ("puzzle_a_day", "m"),
+    ("puzzle_a_day", "1"),
+];
+
+/// The (collection, puzzle id) pairs of the puzzles, whose solutions are all enumerated.
+///
+/// Enumerating searches the whole search tree, so only small puzzles are used.
+const ENUMERATION: [(&str, &str); 5] = [
+    ("trominoes", "c"),
+    ("tetrominoes", "a"),
+    ("pentominoes", "3x10A"),
+    ("puzzle_a_day", "m"),
+    ("puzzle_a_day", "1"),
+];
+
+/// A puzzle prepared for the solver.
+struct Fixture {
+    name: String,
+    board: Board,
+    tiles: Vec<Tile>,
+}
+
+fn load_collection(loader: &JsonLoader, collection: &str) -> PuzzleConfigCollection {
+    let json = fs::read_to_string(format!("{RESOURCES}/puzzles/{collection}.json")).unwrap();
+    loader.load_puzzle_collection(&json).unwrap()
+}
+
+/// Loads the given puzzles from the core collections.
+///
+/// The board of a puzzle with areas is prepared for its default target, e.g. the first day of
+/// the year for Puzzle-a-Day.
+fn load_fixtures(puzzles: &[(&str, &str)]) -> Vec<Fixture> {
+    let predefined = fs::read_to_string(format!("{RESOURCES}/predefined.json")).unwrap();
+    let loader = puzzle_config::create_json_loader(&predefined, env!("CARGO_PKG_VERSION")).unwrap();
+
+    puzzles
+        .iter()
+        .map(|&(collection_name, puzzle_id)| {
+            let collection = load_collection(&loader, collection_name);
+            let puzzle = collection
+                .puzzles()
+                .iter()
+                .find(|puzzle| puzzle.id() == puzzle_id)
+                .unwrap_or_else(|| panic!("no puzzle {puzzle_id} in {collection_name}"));
+            Fixture {
+                name: format!("{collection_name}/{puzzle_id}"),
+                board: create_board(puzzle),
+                tiles: puzzle
+                    .tiles()
+                    .iter()
+                    .map(|tile_config| Tile::new(tile_config.base().clone()))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn create_board(puzzle: &PuzzleConfig) -> Board {
+    let board_config = puzzle.board_config();
+    let mut board: Board = board_config.layout().map(|on_board| !on_board).into();
+    if let BoardConfig::Area { .. } = board_config {
+        let target = board_config.default_target().unwrap();
+        for TargetIndex(x, y) in target.indices {
+            board[[x, y]] = true;
+        }
+    }
+    board
+}
+
+fn bench_first_solution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("first_solution");
+    group.sample_size(10);
+    // The first solution in search order does not depend on the scheduling of the threads.
+    let options = SolverOptions {
+        selection: SolutionSelection::FirstInOrder,
+        ..Default::default()
+    };
+
+    for fixture in load_fixtures(&FIRST_SOLUTION) {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(&fixture.name),
+            &fixture,
+            |b, fixture| {
+                b.iter(|| {
+                    let result = solve_all_filling(
+                        fixture.board.clone(),
+                        &fixture.tiles,
+                        &options,
+                        &NeverCancel,
+                    );
+                    black_box(result.unwrap())
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_enumeration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("enumeration");
+    group.sample_size(10);
+    let strategies = [
+        SolverStrategy::Backtracking,
+        SolverStrategy::MostConstrainedCell,
+        SolverStrategy::DancingLinks,
+    ];
+
+    for fixture in load_fixtures(&ENUMERATION) {
+        for strategy in strategies {
+            let options = SolverOptions {
+                strategy,
+                ..Default::default()
+            };
+            group.bench_with_input(
+                BenchmarkId::new(format!("{strategy:?}"), &fixture.name),
+                &fixture,
+                |b, fixture| {
+                    b.iter(|| {
+                        let result = count_all_solutions(
+                            fixture.board.clone(),
+                            &fixture.tiles,
+                            &options,
+                            &NeverCancel,
+                        );
+                        black_box(result.unwrap())
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_first_solution, bench_enumeration);
+criterion_main!(benches);
@@ -0,0 +1,354 @@
+//! A human-readable text format for boards and solutions.
+//!
+//! Each line of the text is one row of the board array, the same way the array is written with
+//! `ndarray::arr2`. A filled cell is written as `#` and an empty cell as `.`. In a solution, the
+//! cells covered by a placement are written with the letter of its tile index: `A` to `Z` for the
+//! indices 0 to 25 and `a` to `z` for the indices 26 to 51.
+//!
+//! ```text
+//! #AAB
+//! .ACB
+//! ```
+
+use crate::board::Board;
+use crate::result::{Solution, TilePlacement};
+use crate::tile::Transform;
+use ndarray::Array2;
+use std::collections::BTreeMap;
+
+const FILLED: char = '#';
+const EMPTY: char = '.';
+
+/// The reason why a board or a solution can not be converted from or to text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsciiError {
+    /// A line of the text has a different length than the first line.
+    RaggedLine {
+        /// The index of the line, ignoring blank lines.
+        line: usize,
+    },
+    /// A character is neither a cell of the board nor the letter of a tile.
+    UnknownCharacter {
+        /// The unknown character.
+        character: char,
+        /// The index of the line, ignoring blank lines.
+        line: usize,
+        /// The index of the character in the line.
+        column: usize,
+    },
+    /// A tile index has no letter, because it is larger than 51.
+    TileIndexTooLarge {
+        /// The index of the tile in the tiles passed to the solver.
+        tile_index: usize,
+    },
+    /// A placement lies outside the board or overlaps a filled cell or another placement.
+    InvalidPlacement {
+        /// The index of the tile in the tiles passed to the solver.
+        tile_index: usize,
+    },
+}
+
+/// Writes the board as text with one line per row.
+///
+/// # Arguments
+///
+/// * `board`: The board to write.
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// use puzzle_solver::ascii::format_board;
+/// use puzzle_solver::board::Board;
+///
+/// let mut board = Board::new((2, 3));
+/// board[[0, 2]] = true;
+/// assert_eq!(format_board(&board), "..#\n...\n");
+/// ```
+pub fn format_board(board: &Board) -> String {
+    let grid = board
+        .get_array()
+        .map(|&filled| if filled { FILLED } else { EMPTY });
+    format_grid(&grid)
+}
+
+/// Reads a board from text with one line per row.
+///
+/// Blank lines and whitespace around the lines are ignored.
+///
+/// # Arguments
+///
+/// * `text`: The text containing only `#` and `.` cells.
+///
+/// returns: Result<Board, AsciiError>
+///
+/// # Examples
+///
+/// ```
+/// use puzzle_solver::ascii::parse_board;
+///
+/// let board = parse_board("..#\n...").unwrap();
+/// assert_eq!(board.get_array().dim(), (2, 3));
+/// assert!(board[[0, 2]]);
+/// ```
+pub fn parse_board(text: &str) -> Result<Board, AsciiError> {
+    let grid = parse_grid(text)?;
+    for ((line, column), &character) in grid.indexed_iter() {
+        if character != FILLED && character != EMPTY {
+            return Err(AsciiError::UnknownCharacter {
+                character,
+                line,
+                column,
+            });
+        }
+    }
+    Ok(Board::from(grid.map(|&character| character == FILLED)))
+}
+
+/// Writes the solution on the board as text with one line per row.
+///
+/// # Arguments
+///
+/// * `board`: The board the solution was searched for.
+/// * `solution`: The solution to write.
+///
+/// returns: Result<String, AsciiError>: An error, if a tile index has no letter or a placement
+/// does not fit on the board.
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::ascii::format_solution;
+/// use puzzle_solver::blocking::solve_all_filling;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::cancel::NeverCancel;
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::tile::Tile;
+///
+/// let board = Board::new((1, 3));
+/// let tiles = vec![Tile::new(arr2(&[[true, true, true]]))];
+/// let solution = solve_all_filling(board.clone(), &tiles, &SolverOptions::default(), &NeverCancel);
+/// assert_eq!(format_solution(&board, &solution.unwrap()).unwrap(), "AAA\n");
+/// ```
+pub fn format_solution(board: &Board, solution: &Solution) -> Result<String, AsciiError> {
+    let mut grid = board
+        .get_array()
+        .map(|&filled| if filled { FILLED } else { EMPTY });
+    for placement in solution.placements() {
+        let tile_index = placement.tile_index();
+        let letter = letter_of(tile_index).ok_or(AsciiError::TileIndexTooLarge { tile_index })?;
+        let (x, y) = placement.position();
+        for ((dx, dy), _) in placement
+            .rotation()
+            .indexed_iter()
+            .filter(|(_, cell)| **cell)
+        {
+            match grid.get_mut((x + dx, y + dy)) {
+                Some(cell) if *cell == EMPTY => *cell = letter,
+                _ => return Err(AsciiError::InvalidPlacement { tile_index }),
+            }
+        }
+    }
+    Ok(format_grid(&grid))
+}
+
+/// Reads a board and a solution on it from text with one line per row.
+///
+/// The cells covered by the placements are empty on the returned board. The placed tiles are
+/// not known, so the base of each placement is the shape covered by its letter and the
+/// transform is the identity.
+///
+/// # Arguments
+///
+/// * `text`: The text containing `#` and `.` cells and the letters of the placed tiles.
+///
+/// returns: Result<(Board, Solution), AsciiError>
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::ascii::parse_solution;
+///
+/// let (board, solution) = parse_solution("#AA\nBBA").unwrap();
+/// assert!(board[[0, 0]]);
+/// assert_eq!(solution.placements().len(), 2);
+/// assert_eq!(solution.placements()[0].rotation(), &arr2(&[[true, true], [false, true]]));
+/// assert_eq!(solution.placements()[0].position(), (0, 1));
+/// ```
+pub fn parse_solution(text: &str) -> Result<(Board, Solution), AsciiError> {
+    let grid = parse_grid(text)?;
+    let mut cells_by_tile: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+    for ((line, column), &character) in grid.indexed_iter() {
+        if character == FILLED || character == EMPTY {
+            continue;
+        }
+        let tile_index = tile_index_of(character).ok_or(AsciiError::UnknownCharacter {
+            character,
+            line,
+            column,
+        })?;
+        cells_by_tile
+            .entry(tile_index)
+            .or_default()
+            .push((line, column));
+    }
+
+    let placements = cells_by_tile
+        .into_iter()
+        .map(|(tile_index, cells)| placement_of(tile_index, &cells))
+        .collect();
+    let board = Board::from(grid.map(|&character| character == FILLED));
+    Ok((board, Solution::new(placements)))
+}
+
+/// Creates the placement covering exactly the given cells.
+fn placement_of(tile_index: usize, cells: &[(usize, usize)]) -> TilePlacement {
+    let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let max_x = cells.iter().map(|&(x, _)| x).max().unwrap_or(0);
+    let max_y = cells.iter().map(|&(_, y)| y).max().unwrap_or(0);
+
+    let mut base = Array2::default((max_x - min_x + 1, max_y - min_y + 1));
+    for &(x, y) in cells {
+        base[[x - min_x, y - min_y]] = true;
+    }
+    TilePlacement::placed(tile_index, base, Transform::default(), (min_x, min_y))
+}
+
+/// Splits the text into a grid of characters, ignoring blank lines and surrounding whitespace.
+fn parse_grid(text: &str) -> Result<Array2<char>, AsciiError> {
+    let lines: Vec<Vec<char>> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.chars().collect())
+        .collect();
+    let width = lines.first().map_or(0, Vec::len);
+    if let Some(line) = lines.iter().position(|line| line.len() != width) {
+        return Err(AsciiError::RaggedLine { line });
+    }
+
+    let height = lines.len();
+    let cells: Vec<char> = lines.into_iter().flatten().collect();
+    Ok(Array2::from_shape_vec((height, width), cells).expect("all lines have the same length"))
+}
+
+fn format_grid(grid: &Array2<char>) -> String {
+    let mut text = String::new();
+    for row in grid.rows() {
+        text.extend(row.iter());
+        text.push('\n');
+    }
+    text
+}
+
+fn letter_of(tile_index: usize) -> Option<char> {
+    match tile_index {
+        0..26 => Some((b'A' + tile_index as u8) as char),
+        26..52 => Some((b'a' + (tile_index - 26) as u8) as char),
+        _ => None,
+    }
+}
+
+fn tile_index_of(letter: char) -> Option<usize> {
+    match letter {
+        'A'..='Z' => Some(letter as usize - 'A' as usize),
+        'a'..='z' => Some(letter as usize - 'a' as usize + 26),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_board_round_trip() {
+        let text = "#..#\n....\n##..\n";
+        let board = parse_board(text).unwrap();
+        assert_eq!(board.get_array().dim(), (3, 4));
+        assert!(board[[2, 1]]);
+        assert!(!board[[2, 2]]);
+        assert_eq!(format_board(&board), text);
+    }
+
+    #[test]
+    fn test_parse_board_ignores_whitespace() {
+        let board = parse_board(
+            "
+            #.
+            .#
+            ",
+        )
+        .unwrap();
+        assert_eq!(board.get_array(), &arr2(&[[true, false], [false, true]]));
+    }
+
+    #[test]
+    fn test_parse_board_errors() {
+        assert_eq!(
+            parse_board("..\n...").err(),
+            Some(AsciiError::RaggedLine { line: 1 })
+        );
+        assert_eq!(
+            parse_board("..\n.A").err(),
+            Some(AsciiError::UnknownCharacter {
+                character: 'A',
+                line: 1,
+                column: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_solution_round_trip() {
+        let text = "#AAB\n.ACB\nzzCC\n";
+        let (board, solution) = parse_solution(text).unwrap();
+        assert_eq!(
+            board.get_array(),
+            &arr2(&[
+                [true, false, false, false],
+                [false, false, false, false],
+                [false, false, false, false]
+            ])
+        );
+        let indices: Vec<usize> = solution
+            .placements()
+            .iter()
+            .map(|p| p.tile_index())
+            .collect();
+        assert_eq!(indices, vec![0, 1, 2, 51]);
+        assert_eq!(solution.placements()[3].position(), (2, 0));
+        assert_eq!(format_solution(&board, &solution).unwrap(), text);
+    }
+
+    #[test]
+    fn test_format_solution_errors() {
+        let board = parse_board("#.").unwrap();
+        let solution = Solution::new(vec![TilePlacement::placed(
+            52,
+            arr2(&[[true]]),
+            Transform::default(),
+            (0, 1),
+        )]);
+        assert_eq!(
+            format_solution(&board, &solution).err(),
+            Some(AsciiError::TileIndexTooLarge { tile_index: 52 })
+        );
+
+        let solution = Solution::new(vec![TilePlacement::placed(
+            0,
+            arr2(&[[true, true]]),
+            Transform::default(),
+            (0, 0),
+        )]);
+        assert_eq!(
+            format_solution(&board, &solution).err(),
+            Some(AsciiError::InvalidPlacement { tile_index: 0 })
+        );
+    }
+}
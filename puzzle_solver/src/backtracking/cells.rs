@@ -0,0 +1,483 @@
+use crate::backtracking::UNUSED;
+use crate::backtracking::positioned::PositionedTile;
+use crate::backtracking::pruner::{Pruner, RemainingTiles};
+use crate::bitmask::Bitmask;
+use crate::branches::{self, BranchStop, Deepest};
+use crate::cancel::Cancellation;
+use crate::options::{SolutionSelection, SolveMode};
+use crate::statistics::SearchCounters;
+use log::debug;
+use std::sync::Arc;
+
+/// How the next cell to fill is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellChoice {
+    /// The first empty cell of the board.
+    FirstEmpty,
+    /// The empty cell with the fewest placements, which still fit on the board.
+    MostConstrained,
+}
+
+/// Searches a solution by filling one cell of the board after another.
+///
+/// For every cell only the placements covering it are tried, so a cell, which can not be
+/// covered anymore, ends the branch immediately.
+///
+/// returns: Result<Vec<usize>, Vec<usize>>: The index of the placement for each tile, if a
+/// solution was found, otherwise the deepest partial placements reached.
+pub fn solve_filling<const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    pruner: Pruner<N>,
+    choice: CellChoice,
+    selection: SolutionSelection,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Result<Vec<usize>, Vec<usize>> {
+    if board_bitmask.all_relevant_bits_set() {
+        return Ok(Vec::new());
+    }
+
+    let shared = CellShared::new(
+        board_bitmask,
+        positioned_tiles,
+        pruner,
+        choice,
+        counters,
+        None,
+    );
+    let solvers = prepare_solvers(board_bitmask, &shared);
+    shared.counters.set_branches_total(solvers.len());
+
+    let result = branches::search_branches(solvers, selection, cancel, |mut solver, stop| {
+        solver.solve(&shared, stop)
+    });
+    debug!("Solver finished.");
+    result
+}
+
+/// Searches the whole search tree by filling one cell after another and calls `on_solution`
+/// for every solution found.
+///
+/// The solutions are passed as the index of the placement for each tile.
+/// The order of the solutions is not defined and `on_solution` may be called from several
+/// threads.
+///
+/// returns: Result<(), Vec<usize>>: Ok if the whole search tree was searched, the deepest
+/// partial placements reached if it was cancelled.
+pub fn enumerate_filling<const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    pruner: Pruner<N>,
+    choice: CellChoice,
+    counters: Arc<SearchCounters>,
+    on_solution: &(dyn Fn(Vec<usize>) + Sync),
+    cancel: &dyn Cancellation,
+) -> Result<(), Vec<usize>> {
+    if board_bitmask.all_relevant_bits_set() {
+        on_solution(Vec::new());
+        return Ok(());
+    }
+
+    let shared = CellShared::new(
+        board_bitmask,
+        positioned_tiles,
+        pruner,
+        choice,
+        counters,
+        Some(on_solution),
+    );
+    let solvers = prepare_solvers(board_bitmask, &shared);
+    shared.counters.set_branches_total(solvers.len());
+
+    let result = branches::enumerate_branches(solvers, cancel, |mut solver, stop| {
+        solver.solve(&shared, stop).err().unwrap_or_default()
+    });
+    debug!("All solvers finished.");
+    result
+}
+
+/// Creates one solver for every way to fill the first chosen cell.
+fn prepare_solvers<const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    shared: &CellShared<'_, N>,
+) -> Vec<CellSolver<N>> {
+    let root = CellSolver::new(board_bitmask, shared);
+    let Some(cell) = root.choose_cell(shared) else {
+        return vec![root];
+    };
+
+    let mut solvers = Vec::new();
+    for &(class, placement_index) in &shared.cell_placements[cell] {
+        let placement = &shared.positioned_tiles[class].bitmasks()[placement_index];
+        if !board_bitmask.and_is_zero(placement) {
+            continue;
+        }
+        let mut board_with_placement = board_bitmask.clone();
+        board_with_placement.xor(board_bitmask, placement);
+        let remaining = root.remaining_after(class, shared);
+        if shared
+            .pruner
+            .prune_with_remaining(&board_with_placement, &remaining)
+        {
+            shared.counters.add_prunes(1);
+            continue;
+        }
+        let mut solver = root.clone();
+        solver.place(class, placement_index, board_with_placement, shared);
+        solvers.push(solver);
+    }
+
+    if root.holes_left > 0 {
+        let mut solver = root.clone();
+        solver.leave_empty(cell);
+        solvers.push(solver);
+    }
+
+    solvers
+}
+
+/// Shared data for the CellSolver.
+struct CellShared<'a, const N: usize> {
+    positioned_tiles: Vec<PositionedTile<N>>,
+    /// For each cell, the placements covering it as (tile class, placement index).
+    ///
+    /// Identical tiles share one class, which is the index of the first copy.
+    cell_placements: Vec<Vec<(usize, usize)>>,
+    /// For each tile class, the indices of all its copies in the order they are used.
+    copies: Vec<Vec<usize>>,
+    mode: SolveMode,
+    choice: CellChoice,
+    pruner: Pruner<N>,
+    counters: Arc<SearchCounters>,
+    /// If set, all solutions are passed to this function and the search continues after a
+    /// solution was found.
+    on_solution: Option<&'a (dyn Fn(Vec<usize>) + Sync)>,
+}
+
+impl<'a, const N: usize> CellShared<'a, N> {
+    fn new(
+        board_bitmask: &Bitmask<N>,
+        positioned_tiles: &[PositionedTile<N>],
+        pruner: Pruner<N>,
+        choice: CellChoice,
+        counters: Arc<SearchCounters>,
+        on_solution: Option<&'a (dyn Fn(Vec<usize>) + Sync)>,
+    ) -> Self {
+        let mut copies: Vec<Vec<usize>> = vec![Vec::new(); positioned_tiles.len()];
+        let mut classes: Vec<usize> = Vec::with_capacity(positioned_tiles.len());
+        for (tile_index, positioned_tile) in positioned_tiles.iter().enumerate() {
+            let class = match positioned_tile.previous_copy() {
+                Some(previous_copy) => classes[previous_copy],
+                None => tile_index,
+            };
+            classes.push(class);
+            copies[class].push(tile_index);
+        }
+
+        let mut cell_placements: Vec<Vec<(usize, usize)>> =
+            vec![Vec::new(); board_bitmask.relevant_bits()];
+        for (class, members) in copies.iter().enumerate() {
+            if members.is_empty() {
+                continue;
+            }
+            for (placement_index, bitmask) in positioned_tiles[class].bitmasks().iter().enumerate()
+            {
+                for (cell, placements) in cell_placements.iter_mut().enumerate() {
+                    if bitmask.get_bit(cell) {
+                        placements.push((class, placement_index));
+                    }
+                }
+            }
+        }
+
+        CellShared {
+            positioned_tiles: positioned_tiles.to_vec(),
+            cell_placements,
+            copies,
+            mode: pruner.mode(),
+            choice,
+            pruner,
+            counters,
+            on_solution,
+        }
+    }
+}
+
+/// Solver filling the board cell by cell using recursive backtracking.
+#[derive(Clone)]
+struct CellSolver<const N: usize> {
+    /// The board after each placed tile or empty cell, the last one is the current board.
+    board_bitmasks: Vec<Bitmask<N>>,
+    used_tile_indices: Vec<usize>,
+    /// For each tile class, the number of copies, which are placed.
+    used_copies: Vec<usize>,
+    placed_tiles: usize,
+    /// The number of cells, which may still be left empty.
+    holes_left: usize,
+    tmp_bitmask: Bitmask<N>,
+    stop_counter: u32,
+    /// Set when the branch should stop, so the search returns without visiting more nodes.
+    stopped: bool,
+    deepest: Deepest,
+    /// The nodes visited since the statistics were last added to the shared counters.
+    nodes_visited: u64,
+    /// The prunes since the statistics were last added to the shared counters.
+    prunes: u64,
+}
+
+impl<const N: usize> CellSolver<N> {
+    fn new(board_bitmask: &Bitmask<N>, shared: &CellShared<N>) -> Self {
+        let num_tiles = shared.positioned_tiles.len();
+        CellSolver {
+            board_bitmasks: vec![board_bitmask.clone()],
+            used_tile_indices: vec![UNUSED; num_tiles],
+            used_copies: vec![0; num_tiles],
+            placed_tiles: 0,
+            holes_left: match shared.mode {
+                SolveMode::PlaceAllTiles => shared.pruner.max_holes(),
+                _ => 0,
+            },
+            tmp_bitmask: Bitmask::new(board_bitmask.relevant_bits()),
+            stop_counter: 0,
+            stopped: false,
+            deepest: Deepest::default(),
+            nodes_visited: 0,
+            prunes: 0,
+        }
+    }
+
+    /// The entry point for the CellSolver to start solving the puzzle.
+    ///
+    /// This function will only return, if a solution is found, or it is proven that no solution
+    /// exists.
+    /// When enumerating all solutions, it only returns after the whole search tree was searched.
+    ///
+    /// returns: Result<Vec<usize>, Deepest>: The placements if a solution is found, the deepest
+    /// placements reached otherwise.
+    fn solve(&mut self, shared: &CellShared<N>, stop: &BranchStop) -> Result<Vec<usize>, Deepest> {
+        let solved = self.solve_recursive(shared, stop);
+        self.flush_statistics(shared);
+        if !solved && !stop.should_stop() {
+            shared.counters.branch_exhausted();
+        }
+        if solved {
+            Ok(self.sorted_placements(shared))
+        } else {
+            Err(std::mem::take(&mut self.deepest))
+        }
+    }
+
+    /// The main recursive solver function.
+    ///
+    /// It chooses the next empty cell and tries every placement covering it, which fits on the
+    /// board. If cells may stay empty, it also tries to leave the cell empty.
+    /// From time to time it checks, whether the branch should stop.
+    ///
+    /// returns: bool: true if a solution was found.
+    fn solve_recursive(&mut self, shared: &CellShared<N>, stop: &BranchStop) -> bool {
+        if self.stopped {
+            return false;
+        }
+        self.stop_counter += 1;
+        self.nodes_visited += 1;
+        if self.stop_counter & 0xff == 0 {
+            self.flush_statistics(shared);
+            if stop.should_stop() {
+                self.stopped = true;
+                return false;
+            }
+        }
+        self.deepest
+            .update(self.placed_tiles, || self.used_tile_indices.clone());
+
+        if self.placed_tiles == shared.positioned_tiles.len() {
+            return self.submit_solution(shared);
+        }
+        let Some(cell) = self.choose_cell(shared) else {
+            return self.submit_solution(shared);
+        };
+
+        for &(class, placement_index) in &shared.cell_placements[cell] {
+            if self.used_copies[class] == shared.copies[class].len() {
+                continue;
+            }
+            let board = self.board_bitmasks.last().unwrap();
+            let placement = &shared.positioned_tiles[class].bitmasks()[placement_index];
+            if !board.and_is_zero(placement) {
+                continue;
+            }
+            self.tmp_bitmask.xor(board, placement);
+            let remaining = self.remaining_after(class, shared);
+            if shared
+                .pruner
+                .prune_with_remaining(&self.tmp_bitmask, &remaining)
+            {
+                self.prunes += 1;
+                continue;
+            }
+
+            let board_with_placement = self.tmp_bitmask.clone();
+            self.place(class, placement_index, board_with_placement, shared);
+            if self.solve_recursive(shared, stop) {
+                return true;
+            }
+            self.remove(class, shared);
+        }
+
+        if self.holes_left > 0 {
+            self.leave_empty(cell);
+            if self.solve_recursive(shared, stop) {
+                return true;
+            }
+            self.board_bitmasks.pop();
+            self.holes_left += 1;
+        }
+
+        false
+    }
+
+    /// Returns the next cell to fill, or None if the board is full.
+    ///
+    /// A cell without any fitting placement is returned immediately, because the branch can
+    /// not be completed anymore, unless the cell is left empty.
+    fn choose_cell(&self, shared: &CellShared<N>) -> Option<usize> {
+        let board = self.board_bitmasks.last().unwrap();
+        match shared.choice {
+            CellChoice::FirstEmpty => board.first_zero_bit(),
+            CellChoice::MostConstrained => {
+                let mut best: Option<(usize, usize)> = None;
+                for cell in 0..board.relevant_bits() {
+                    if board.get_bit(cell) {
+                        continue;
+                    }
+                    let limit = best.map_or(usize::MAX, |(_, count)| count);
+                    let count = self.count_candidates(cell, limit, shared);
+                    if count == 0 {
+                        return Some(cell);
+                    }
+                    if count < limit {
+                        best = Some((cell, count));
+                    }
+                }
+                best.map(|(cell, _)| cell)
+            }
+        }
+    }
+
+    /// Counts the placements covering the cell, which fit on the current board.
+    ///
+    /// Counting stops at the limit, because the cell is not chosen anyway.
+    fn count_candidates(&self, cell: usize, limit: usize, shared: &CellShared<N>) -> usize {
+        let board = self.board_bitmasks.last().unwrap();
+        let mut count = 0;
+        for &(class, placement_index) in &shared.cell_placements[cell] {
+            if self.used_copies[class] < shared.copies[class].len()
+                && board.and_is_zero(&shared.positioned_tiles[class].bitmasks()[placement_index])
+            {
+                count += 1;
+                if count >= limit {
+                    break;
+                }
+            }
+        }
+        count
+    }
+
+    /// Summarizes the tiles not placed yet after placing the next copy of the tile class.
+    ///
+    /// This is only computed, if the pruner has global stages using it.
+    fn remaining_after(&self, class: usize, shared: &CellShared<N>) -> RemainingTiles {
+        if !shared.pruner.has_global_stages() {
+            return RemainingTiles::default();
+        }
+        let next_copy = shared.copies[class][self.used_copies[class]];
+        shared
+            .pruner
+            .remaining(|i| i != next_copy && self.used_tile_indices[i] == UNUSED)
+    }
+
+    /// Places the first unused copy of the tile class, so copies are never swapped.
+    fn place(
+        &mut self,
+        class: usize,
+        placement_index: usize,
+        board_with_placement: Bitmask<N>,
+        shared: &CellShared<N>,
+    ) {
+        let tile_index = shared.copies[class][self.used_copies[class]];
+        self.used_tile_indices[tile_index] = placement_index;
+        self.used_copies[class] += 1;
+        self.placed_tiles += 1;
+        self.board_bitmasks.push(board_with_placement);
+    }
+
+    /// Removes the last placed copy of the tile class.
+    fn remove(&mut self, class: usize, shared: &CellShared<N>) {
+        self.used_copies[class] -= 1;
+        let tile_index = shared.copies[class][self.used_copies[class]];
+        self.used_tile_indices[tile_index] = UNUSED;
+        self.placed_tiles -= 1;
+        self.board_bitmasks.pop();
+    }
+
+    /// Marks the cell as filled without placing a tile on it.
+    fn leave_empty(&mut self, cell: usize) {
+        let mut board = self.board_bitmasks.last().unwrap().clone();
+        board.set_bit(cell);
+        self.board_bitmasks.push(board);
+        self.holes_left -= 1;
+    }
+
+    /// Adds the locally counted statistics to the shared counters.
+    fn flush_statistics(&mut self, shared: &CellShared<N>) {
+        shared.counters.add_nodes_visited(self.nodes_visited);
+        shared.counters.add_prunes(self.prunes);
+        self.nodes_visited = 0;
+        self.prunes = 0;
+    }
+
+    /// Returns the placements with the copies of each tile in increasing placement order, like
+    /// the other searches return them.
+    fn sorted_placements(&self, shared: &CellShared<N>) -> Vec<usize> {
+        let mut placements = self.used_tile_indices.clone();
+        for members in shared.copies.iter().filter(|members| members.len() > 1) {
+            let mut sorted: Vec<usize> = members.iter().map(|&i| placements[i]).collect();
+            sorted.sort();
+            for (&tile_index, placement) in members.iter().zip(sorted) {
+                placements[tile_index] = placement;
+            }
+        }
+        placements
+    }
+
+    /// Determines if the current board state represents a complete solution.
+    ///
+    /// If the current board is a correct solution, it returns true.
+    /// Otherwise, it returns false.
+    /// When enumerating all solutions, a correct solution is passed to `on_solution` and
+    /// false is returned to continue the search.
+    fn submit_solution(&self, shared: &CellShared<N>) -> bool {
+        let all_placed = self.placed_tiles == shared.positioned_tiles.len();
+        let solved = match shared.mode {
+            SolveMode::FillWithAllTiles => {
+                all_placed && self.board_bitmasks.last().unwrap().all_relevant_bits_set()
+            }
+            SolveMode::FillWithSubset => {
+                self.board_bitmasks.last().unwrap().all_relevant_bits_set()
+            }
+            SolveMode::PlaceAllTiles => all_placed,
+        };
+        if solved {
+            debug!(
+                "Solution found with tile placements: {:?}",
+                self.used_tile_indices
+            );
+            if let Some(on_solution) = shared.on_solution {
+                on_solution(self.sorted_placements(shared));
+                return false;
+            }
+        }
+        solved
+    }
+}
@@ -1,74 +1,95 @@
+use crate::backtracking::UNUSED;
 use crate::backtracking::positioned::PositionedTile;
-use crate::backtracking::pruner::Pruner;
+use crate::backtracking::pruner::{Pruner, RemainingTiles};
 use crate::bitmask::Bitmask;
+use crate::branches::{self, BranchStop, Deepest};
+use crate::cancel::Cancellation;
+use crate::options::{SolutionSelection, SolveMode};
+use crate::statistics::SearchCounters;
 use log::debug;
 use std::sync::Arc;
-use tokio::task::JoinSet;
-use tokio_util::sync::CancellationToken;
 
-pub async fn solve_filling(
+pub fn solve_filling<const N: usize>(
     board_width: i32,
-    board_bitmask: &Bitmask,
-    positioned_tiles: &[PositionedTile],
-    pruner: Pruner,
-    cancel_token: CancellationToken,
-) -> Option<Vec<usize>> {
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    pruner: Pruner<N>,
+    selection: SolutionSelection,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Result<Vec<usize>, Vec<usize>> {
     if board_bitmask.all_relevant_bits_set() {
-        return Some(Vec::new());
+        return Ok(Vec::new());
     }
 
-    let solvers: Vec<AllFillingSolver> = prepare_solvers(board_bitmask, positioned_tiles, &pruner);
-    let shared = Arc::new(AllFillingShared {
+    let solvers: Vec<AllFillingSolver<N>> =
+        prepare_solvers(board_bitmask, positioned_tiles, &pruner, &counters);
+    counters.set_branches_total(solvers.len());
+    let shared = AllFillingShared {
         board_width,
-        positioned_tiles: positioned_tiles.to_vec(),
+        positioned_tiles,
+        mode: pruner.mode(),
+        remaining: remaining_after_each_tile(&pruner, positioned_tiles.len()),
         pruner,
-        cancel_token: cancel_token.clone(),
-    });
-    let mut set: JoinSet<Option<Vec<usize>>> = JoinSet::new();
-
-    let result: Option<Vec<usize>> = {
-        for mut solver in solvers.into_iter() {
-            set.spawn({
-                let shared = shared.clone();
-                async move { solver.solve(&shared).await }
-            });
-        }
-        tokio::select! {
-            _ = cancel_token.cancelled() => {
-                debug!("Cancellation requested, aborting all solver tasks.");
-                None
-            }
-            res = await_completion(&mut set) => {
-                debug!("Solver Finished, aborting remaining solver tasks.");
-                res
-            }
-        }
+        counters,
+        on_solution: None,
     };
-    set.abort_all();
+
+    let result = branches::search_branches(solvers, selection, cancel, |mut solver, stop| {
+        solver.solve(&shared, stop)
+    });
+    debug!("Solver finished.");
     result
 }
 
-async fn await_completion(set: &mut JoinSet<Option<Vec<usize>>>) -> Option<Vec<usize>> {
-    let mut result: Option<Vec<usize>> = None;
-    while let Some(res) = set.join_next().await {
-        match res {
-            Ok(r) => {
-                if r.is_some() {
-                    result = r;
-                    break;
-                }
-            }
-            Err(_) => {}
-        }
+/// Searches the whole search tree and calls `on_solution` for every solution found.
+///
+/// The solutions are passed as the index of the placement for each tile.
+/// The order of the solutions is not defined and `on_solution` may be called from several
+/// threads.
+///
+/// returns: Result<(), Vec<usize>>: Ok if the whole search tree was searched, the deepest
+/// partial placements reached if it was cancelled.
+pub fn enumerate_filling<const N: usize>(
+    board_width: i32,
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    pruner: Pruner<N>,
+    counters: Arc<SearchCounters>,
+    on_solution: &(dyn Fn(Vec<usize>) + Sync),
+    cancel: &dyn Cancellation,
+) -> Result<(), Vec<usize>> {
+    if board_bitmask.all_relevant_bits_set() {
+        on_solution(Vec::new());
+        return Ok(());
     }
+
+    let solvers: Vec<AllFillingSolver<N>> =
+        prepare_solvers(board_bitmask, positioned_tiles, &pruner, &counters);
+    counters.set_branches_total(solvers.len());
+    let shared = AllFillingShared {
+        board_width,
+        positioned_tiles,
+        mode: pruner.mode(),
+        remaining: remaining_after_each_tile(&pruner, positioned_tiles.len()),
+        pruner,
+        counters,
+        on_solution: Some(on_solution),
+    };
+
+    let result = branches::enumerate_branches(solvers, cancel, |mut solver, stop| {
+        solver.solve(&shared, stop).err().unwrap_or_default()
+    });
+    debug!("All solvers finished.");
     result
 }
 
-fn prepare_solvers(
-    board_bitmask: &Bitmask,
-    positioned_tiles: &[PositionedTile],
-    pruner: &Pruner,
-) -> Vec<AllFillingSolver> {
+fn prepare_solvers<const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    pruner: &Pruner<N>,
+    counters: &SearchCounters,
+) -> Vec<AllFillingSolver<N>> {
     if positioned_tiles.is_empty() {
         return Vec::new();
     }
@@ -81,7 +102,8 @@ fn prepare_solvers(
             let mut board_with_placements = board_bitmask.clone();
             board_with_placements.xor(board_bitmask, placement);
 
-            if pruner.prune(&board_with_placements) {
+            if pruner.prune_with_remaining(&board_with_placements, &pruner.remaining(|i| i > 0)) {
+                counters.add_prunes(1);
                 continue;
             }
 
@@ -98,28 +120,62 @@ fn prepare_solvers(
         }
     }
 
+    if pruner.mode() == SolveMode::FillWithSubset {
+        solvers.push(AllFillingSolver::new(
+            board_bitmask,
+            &[UNUSED],
+            positioned_tiles.len(),
+        ));
+    }
+
     solvers
 }
 
+/// Summarizes the tiles not placed yet, when the tiles are placed in order.
+///
+/// returns: Vec<RemainingTiles>: For each tile index up to `num_tiles`, the tiles from that
+/// index on.
+fn remaining_after_each_tile<const N: usize>(
+    pruner: &Pruner<N>,
+    num_tiles: usize,
+) -> Vec<RemainingTiles> {
+    (0..=num_tiles)
+        .map(|first_tile| pruner.remaining(|i| i >= first_tile))
+        .collect()
+}
+
 /// Shared data for the AllFillingSolver.
-struct AllFillingShared {
+struct AllFillingShared<'a, const N: usize> {
     board_width: i32,
-    positioned_tiles: Vec<PositionedTile>,
-    pruner: Pruner,
-    cancel_token: CancellationToken,
+    positioned_tiles: &'a [PositionedTile<N>],
+    mode: SolveMode,
+    pruner: Pruner<N>,
+    /// The tiles not placed yet for each tile index, the tiles from that index on.
+    remaining: Vec<RemainingTiles>,
+    counters: Arc<SearchCounters>,
+    /// If set, all solutions are passed to this function and the search continues after a
+    /// solution was found.
+    on_solution: Option<&'a (dyn Fn(Vec<usize>) + Sync)>,
 }
 
 /// Solver for filling the board with all tiles using recursive backtracking.
-struct AllFillingSolver {
+struct AllFillingSolver<const N: usize> {
     start_tile_index: usize,
-    board_bitmasks: Vec<Bitmask>,
+    board_bitmasks: Vec<Bitmask<N>>,
     used_tile_indices: Vec<usize>,
-    tmp_bitmask: Bitmask,
-    yield_counter: u32,
+    tmp_bitmask: Bitmask<N>,
+    stop_counter: u32,
+    /// Set when the branch should stop, so the search returns without visiting more nodes.
+    stopped: bool,
+    deepest: Deepest,
+    /// The nodes visited since the statistics were last added to the shared counters.
+    nodes_visited: u64,
+    /// The prunes since the statistics were last added to the shared counters.
+    prunes: u64,
 }
 
-impl AllFillingSolver {
-    fn new(board_bitmasks: &Bitmask, used_tile_indices: &[usize], num_tiles: usize) -> Self {
+impl<const N: usize> AllFillingSolver<N> {
+    fn new(board_bitmasks: &Bitmask<N>, used_tile_indices: &[usize], num_tiles: usize) -> Self {
         let mut use_tile_indices_vec: Vec<usize> = Vec::with_capacity(num_tiles);
         for used_tile_index in used_tile_indices {
             use_tile_indices_vec.push(*used_tile_index);
@@ -132,7 +188,11 @@ impl AllFillingSolver {
             board_bitmasks: vec![board_bitmasks.clone(); num_tiles],
             used_tile_indices: use_tile_indices_vec,
             tmp_bitmask: Bitmask::new(board_bitmasks.relevant_bits()),
-            yield_counter: 0,
+            stop_counter: 0,
+            stopped: false,
+            deepest: Deepest::default(),
+            nodes_visited: 0,
+            prunes: 0,
         }
     }
 
@@ -140,14 +200,24 @@ impl AllFillingSolver {
     ///
     /// This function will only return, if a solution is found, or it is proven that no solution
     /// exists.
+    /// When enumerating all solutions, it only returns after the whole search tree was searched.
     ///
-    /// returns: bool: true if a solution is found, false otherwise.
-    async fn solve(&mut self, shared: &AllFillingShared) -> Option<Vec<usize>> {
-        let solved = self.solve_recursive(self.start_tile_index, shared).await;
+    /// returns: Result<Vec<usize>, Deepest>: The placements if a solution is found, the deepest
+    /// placements reached otherwise.
+    fn solve(
+        &mut self,
+        shared: &AllFillingShared<N>,
+        stop: &BranchStop,
+    ) -> Result<Vec<usize>, Deepest> {
+        let solved = self.solve_recursive(self.start_tile_index, shared, stop);
+        self.flush_statistics(shared);
+        if !solved && !stop.should_stop() {
+            shared.counters.branch_exhausted();
+        }
         if solved {
-            Some(self.used_tile_indices.clone())
+            Ok(self.used_tile_indices.clone())
         } else {
-            None
+            Err(std::mem::take(&mut self.deepest))
         }
     }
 
@@ -160,65 +230,125 @@ impl AllFillingSolver {
     /// If no valid placements are found for a tile, it backtracks and tries the next placement.
     /// If all placements are exhausted without finding a solution, it returns false.
     ///
-    /// From time to time it yields to the tokio runtime to allow cancellation.
+    /// From time to time it checks, whether the branch should stop.
     ///
     /// # Arguments
     ///
     /// * `tile_index`:
     ///
     /// returns: bool
-    async fn solve_recursive(&mut self, tile_index: usize, shared: &AllFillingShared) -> bool {
-        self.yield_counter += 1;
-        if self.yield_counter & 0xff == 0 {
-            tokio::task::yield_now().await;
-            if shared.cancel_token.is_cancelled() {
+    fn solve_recursive(
+        &mut self,
+        tile_index: usize,
+        shared: &AllFillingShared<N>,
+        stop: &BranchStop,
+    ) -> bool {
+        if self.stopped {
+            return false;
+        }
+        self.stop_counter += 1;
+        self.nodes_visited += 1;
+        if self.stop_counter & 0xff == 0 {
+            self.flush_statistics(shared);
+            if stop.should_stop() {
+                self.stopped = true;
                 return false;
             }
         }
+        self.deepest.update(tile_index, || {
+            let mut placements = self.used_tile_indices[..tile_index].to_vec();
+            placements.resize(self.used_tile_indices.len(), UNUSED);
+            placements
+        });
 
         // All tiles placed
         if tile_index >= shared.positioned_tiles.len() {
-            return self.submit_solution();
+            return self.submit_solution(tile_index, shared);
+        }
+        // Board filled without the remaining tiles
+        if shared.mode == SolveMode::FillWithSubset
+            && self.board_bitmasks[tile_index - 1].all_relevant_bits_set()
+        {
+            self.used_tile_indices[tile_index..].fill(UNUSED);
+            return self.submit_solution(tile_index, shared);
         }
 
+        // Identical tiles are placed in the order of their placements to avoid permutations
+        let first_placement = match shared.positioned_tiles[tile_index].previous_copy() {
+            Some(previous_copy) => self.used_tile_indices[previous_copy].saturating_add(1),
+            None => 0,
+        };
         let num_placements = shared.positioned_tiles[tile_index].bitmasks().len();
-        for i in 0..num_placements {
+        for i in first_placement..num_placements {
             let placement = &shared.positioned_tiles[tile_index].bitmasks()[i];
             if self.board_bitmasks[tile_index - 1].and_is_zero(&placement) {
                 self.tmp_bitmask
                     .xor(&self.board_bitmasks[tile_index - 1], &placement);
-                if shared.pruner.prune(&self.tmp_bitmask) {
+                if shared
+                    .pruner
+                    .prune_with_remaining(&self.tmp_bitmask, &shared.remaining[tile_index + 1])
+                {
+                    self.prunes += 1;
                     continue;
                 }
                 self.used_tile_indices[tile_index] = i;
                 self.board_bitmasks[tile_index] = self.tmp_bitmask.clone();
-                if Box::pin(async { self.solve_recursive(tile_index + 1, &shared).await }).await {
+                if self.solve_recursive(tile_index + 1, shared, stop) {
                     return true;
                 }
             }
         }
 
+        if shared.mode == SolveMode::FillWithSubset {
+            self.used_tile_indices[tile_index] = UNUSED;
+            self.board_bitmasks[tile_index] = self.board_bitmasks[tile_index - 1].clone();
+            if self.solve_recursive(tile_index + 1, shared, stop) {
+                return true;
+            }
+        }
+
         false
     }
 
+    /// Adds the locally counted statistics to the shared counters.
+    fn flush_statistics(&mut self, shared: &AllFillingShared<N>) {
+        shared.counters.add_nodes_visited(self.nodes_visited);
+        shared.counters.add_prunes(self.prunes);
+        self.nodes_visited = 0;
+        self.prunes = 0;
+    }
+
     /// Determines if the current board state represents a complete solution.
     ///
     /// If the current board is a correct solution, it returns true.
     /// Otherwise, it returns false.
-    fn submit_solution(&self) -> bool {
+    /// When enumerating all solutions, a correct solution is passed to `on_solution` and
+    /// false is returned to continue the search.
+    ///
+    /// # Arguments
+    ///
+    /// * `tile_index`: The index of the first tile, which was not considered.
+    ///
+    /// returns: bool
+    fn submit_solution(&self, tile_index: usize, shared: &AllFillingShared<N>) -> bool {
         debug!("Submitting solution...");
-        let board_filled = self.board_bitmasks.last().unwrap().all_relevant_bits_set();
-        if board_filled {
+        let solved = shared.mode == SolveMode::PlaceAllTiles
+            || self.board_bitmasks[tile_index - 1].all_relevant_bits_set();
+        if solved {
             debug!(
                 "Solution found with tile placements: {:?}",
                 self.used_tile_indices
             );
+            if let Some(on_solution) = shared.on_solution {
+                on_solution(self.used_tile_indices.clone());
+                return false;
+            }
         }
-        board_filled
+        solved
     }
 
     #[allow(dead_code)]
-    fn print_debug(&self, shared: &AllFillingShared) {
+    fn print_debug(&self, shared: &AllFillingShared<N>) {
         debug!("RecursiveSolver Debug Info:");
         debug!("Board Width: {}", shared.board_width);
         debug!("Start Tile Index: {}", self.start_tile_index);
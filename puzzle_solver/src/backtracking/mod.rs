@@ -1,67 +1,570 @@
 use crate::array_util;
+use crate::backtracking::cells::CellChoice;
 use crate::backtracking::positioned::PositionedTile;
 use crate::backtracking::pruner::Pruner;
+use crate::bitmask;
 use crate::bitmask::Bitmask;
 use crate::board::Board;
-use crate::result::{Solution, TilePlacement, UnsolvableReason};
+use crate::cancel::{Cancellation, LimitedCancellation};
+use crate::dlx;
+use crate::options::{SolutionSelection, SolveMode, SolverOptions, SolverStrategy};
+use crate::plausibility::check;
+use crate::result::{Solution, SolutionCount, TilePlacement, UnsolvableReason};
+use crate::statistics::SearchCounters;
+use crate::symmetry::BoardSymmetries;
 use crate::tile::Tile;
-use tokio_util::sync::CancellationToken;
+use log::debug;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
+pub(crate) mod cells;
 pub mod core;
-mod positioned;
-mod pruner;
+pub(crate) mod positioned;
+pub(crate) mod pruner;
 
-pub async fn solve_all_filling(
+/// The placement index of a tile, which is not used in a solution.
+///
+/// Only solutions searched with `SolveMode::FillWithSubset` contain unused tiles.
+pub(crate) const UNUSED: usize = usize::MAX;
+
+/// Evaluates `$call` with the constant `$n` set to the array length of the Bitmask used for a
+/// board with the given number of cells, so small boards use the fastest representation.
+/// Evaluates `$too_large` instead, if the board has more cells than `bitmask::MAX_BITS`.
+macro_rules! dispatch_words {
+    ($cells:expr, |$n:ident| $call:expr, $too_large:expr) => {
+        match bitmask::words_for_bits($cells) {
+            0..=1 => {
+                const $n: usize = 1;
+                $call
+            }
+            2 => {
+                const $n: usize = 2;
+                $call
+            }
+            3 => {
+                const $n: usize = 3;
+                $call
+            }
+            4 => {
+                const $n: usize = 4;
+                $call
+            }
+            5..=8 => {
+                const $n: usize = 8;
+                $call
+            }
+            9..=bitmask::MAX_WORDS => {
+                const $n: usize = bitmask::MAX_WORDS;
+                $call
+            }
+            _ => {
+                debug!("Board too large for bitmask representation.");
+                $too_large
+            }
+        }
+    };
+}
+
+/// The tiles prepared for the search on a board, which do not depend on the symmetries of the
+/// board.
+///
+/// The placements can be reused for boards, which only differ by more filled cells.
+struct SharedInput<const N: usize> {
+    /// The tiles sorted in the order they are placed.
+    tiles: Vec<Tile>,
+    /// The index of each sorted tile in the tiles passed to the solver.
+    tile_indices: Vec<usize>,
+    pruner: Pruner<N>,
+    /// All placements for each tile.
+    positioned_tiles: Vec<PositionedTile<N>>,
+}
+
+impl<const N: usize> SharedInput<N> {
+    /// Prepares the tiles for the search on the given board.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board to place the tiles on.
+    /// * `tiles`: The tiles to place on the board.
+    /// * `options`: The options of the search.
+    /// * `fillable_cells`: The bit indices of the cells, which may be filled later. Placements
+    ///   are not pruned for leaving them empty.
+    ///
+    /// returns: SharedInput
+    fn new(
+        board: &Board,
+        tiles: &[Tile],
+        options: &SolverOptions,
+        fillable_cells: &[usize],
+    ) -> Self {
+        let mut tile_indices: Vec<usize> = (0..tiles.len()).collect();
+        tile_indices.sort_by(|&a, &b| tiles[a].base.len().cmp(&tiles[b].base.len()).reverse());
+        let tiles: Vec<Tile> = tile_indices.iter().map(|&i| tiles[i].clone()).collect();
+
+        let pruner = Pruner::new(board, &tiles, options.mode, options.pruning);
+        let fillable_pruner = match fillable_cells {
+            [] => None,
+            cells => Some(pruner.with_filled_cells(board, cells)),
+        };
+        let placement_pruner = fillable_pruner.as_ref().unwrap_or(&pruner);
+
+        let mut positioned_tiles: Vec<PositionedTile<N>> = tiles
+            .iter()
+            .map(|tile| PositionedTile::new(tile, board, placement_pruner))
+            .collect();
+        positioned::link_identical_copies(&mut positioned_tiles);
+        if let SolutionSelection::Seeded(seed) = options.selection {
+            positioned::shuffle_placements(&mut positioned_tiles, seed);
+        }
+
+        SharedInput {
+            tiles,
+            tile_indices,
+            pruner,
+            positioned_tiles,
+        }
+    }
+
+    /// Creates the input for a board, which only differs from the board of this input by the
+    /// given filled cells.
+    ///
+    /// The placements covering a filled cell are removed, the other placements keep their order,
+    /// so identical tiles still share the same placements.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board with the filled cells.
+    /// * `filled_cells`: The bit indices of the cells, which are filled additionally. They have
+    ///   to be part of the fillable cells this input was created with.
+    ///
+    /// returns: SharedInput
+    fn with_filled_cells(&self, board: &Board, filled_cells: &[usize]) -> Self {
+        let pruner = self.pruner.with_filled_cells(board, filled_cells);
+        let mut filled = Bitmask::new(board.get_array().len());
+        for &index in filled_cells {
+            filled.set_bit(index);
+        }
+        let positioned_tiles = self
+            .positioned_tiles
+            .iter()
+            .map(|positioned_tile| {
+                positioned_tile.filter(|_, placement| {
+                    placement.and_is_zero(&filled) && !pruner.prune(placement)
+                })
+            })
+            .collect();
+
+        SharedInput {
+            tiles: self.tiles.clone(),
+            tile_indices: self.tile_indices.clone(),
+            pruner,
+            positioned_tiles,
+        }
+    }
+}
+
+/// The tiles prepared for the search on a board.
+///
+/// `N` is the number of elements of the bitmasks used to represent the board.
+struct SearchInput<const N: usize> {
+    /// The tiles sorted in the order they are placed.
+    tiles: Vec<Tile>,
+    /// The index of each sorted tile in the tiles passed to the solver.
+    tile_indices: Vec<usize>,
+    pruner: Pruner<N>,
+    board_bitmask: Bitmask<N>,
+    /// All placements for each tile.
+    positioned_tiles: Vec<PositionedTile<N>>,
+    symmetries: BoardSymmetries<N>,
+    /// The placements used in the search, if one tile could be restricted to one placement
+    /// per symmetry class.
+    restricted_tiles: Option<Vec<PositionedTile<N>>>,
+}
+
+impl<const N: usize> SearchInput<N> {
+    fn new(board: &Board, tiles: &[Tile], options: &SolverOptions) -> Self {
+        Self::from_shared(SharedInput::new(board, tiles, options, &[]), board)
+    }
+
+    fn from_shared(shared: SharedInput<N>, board: &Board) -> Self {
+        let SharedInput {
+            tiles,
+            tile_indices,
+            pruner,
+            positioned_tiles,
+        } = shared;
+        let board_bitmask = Bitmask::from(board.get_array());
+        let symmetries = BoardSymmetries::new(board, &positioned_tiles);
+        // A restricted tile may be left out of a solution, which would lose its symmetric
+        // solutions.
+        let restricted_tiles = match pruner.mode() {
+            SolveMode::FillWithSubset => None,
+            _ => symmetries.restrict_placements(&positioned_tiles),
+        };
+        if restricted_tiles.is_some() {
+            debug!("Restricted placements to one per symmetry class.");
+        }
+
+        SearchInput {
+            tiles,
+            tile_indices,
+            pruner,
+            board_bitmask,
+            positioned_tiles,
+            symmetries,
+            restricted_tiles,
+        }
+    }
+}
+
+/// The settings of a single solver run besides the board and the tiles.
+pub struct SearchContext<'a> {
+    pub options: SolverOptions,
+    /// The counters for the statistics of the run.
+    pub counters: Arc<SearchCounters>,
+    pub cancel: &'a dyn Cancellation,
+}
+
+/// Searches a solution filling the whole board.
+///
+/// The size of the bitmasks is chosen based on the size of the board, so small boards use the
+/// fastest representation.
+pub fn solve_all_filling(
     board: Board,
     tiles: &[Tile],
-    cancel_token: CancellationToken,
+    context: SearchContext,
 ) -> Result<Solution, UnsolvableReason> {
-    let mut tiles = tiles.to_vec();
-    tiles.sort_by(|a, b| a.base.len().cmp(&b.base.len()).reverse());
-    let tiles = tiles;
+    let cells = board.get_array().len();
+    dispatch_words!(
+        cells,
+        |N| solve_all_filling_with::<N>(board, tiles, context),
+        Err(UnsolvableReason::BoardTooLarge { cells })
+    )
+}
 
-    let pruner = Pruner::new_for_filling(&board, &tiles);
+fn solve_all_filling_with<const N: usize>(
+    board: Board,
+    tiles: &[Tile],
+    context: SearchContext,
+) -> Result<Solution, UnsolvableReason> {
+    let SearchInput {
+        tiles,
+        tile_indices,
+        pruner,
+        board_bitmask,
+        positioned_tiles,
+        restricted_tiles,
+        ..
+    } = SearchInput::<N>::new(&board, tiles, &context.options);
+    let search_tiles = restricted_tiles.as_deref().unwrap_or(&positioned_tiles);
 
-    let board_bitmask = Bitmask::from(board.get_array());
-    let positioned_tiles: Vec<PositionedTile> = tiles
-        .iter()
-        .map(|tile| PositionedTile::new(tile, &board, &pruner))
-        .collect();
+    let SearchContext {
+        options,
+        counters,
+        cancel,
+    } = context;
+    let limited = LimitedCancellation::new(cancel, options.limits, &counters);
+    let result = match options.strategy {
+        SolverStrategy::Backtracking => core::solve_filling(
+            board.get_array().dim().0 as i32,
+            &board_bitmask,
+            search_tiles,
+            pruner,
+            options.selection,
+            counters.clone(),
+            &limited,
+        ),
+        SolverStrategy::DancingLinks => dlx::solve_filling(
+            &board_bitmask,
+            search_tiles,
+            options.mode,
+            options.selection,
+            counters.clone(),
+            &limited,
+        ),
+        SolverStrategy::FirstEmptyCell | SolverStrategy::MostConstrainedCell => {
+            cells::solve_filling(
+                &board_bitmask,
+                search_tiles,
+                pruner,
+                cell_choice(options.strategy),
+                options.selection,
+                counters.clone(),
+                &limited,
+            )
+        }
+    };
 
-    let result = core::solve_filling(
-        board.get_array().dim().0 as i32,
-        &board_bitmask,
-        &positioned_tiles,
-        pruner,
-        cancel_token,
+    let solution =
+        |placements| create_solution(placements, search_tiles, &tiles, &tile_indices, &board);
+    match result {
+        Ok(placements) => Ok(solution(placements)),
+        Err(_) if cancel.is_cancelled() => Err(UnsolvableReason::Cancelled),
+        Err(deepest) if limited.limit_reached() => Err(UnsolvableReason::LimitReached {
+            deepest: solution(deepest),
+        }),
+        Err(_) => Err(UnsolvableReason::NoFit),
+    }
+}
+
+/// Searches the whole search tree and calls `on_solution` for every solution.
+///
+/// Besides the solution, `on_solution` gets whether it is the first solution found of its class
+/// of solutions, which are equal up to a symmetry of the board.
+///
+/// If the placements could be restricted by the symmetries of the board, only one solution per
+/// symmetry class is searched and the symmetric solutions are created from it.
+pub fn enumerate_all_filling<F>(
+    board: Board,
+    tiles: &[Tile],
+    on_solution: F,
+    context: SearchContext,
+) -> Result<SolutionCount, UnsolvableReason>
+where
+    F: FnMut(Solution, bool) + Send,
+{
+    let cells = board.get_array().len();
+    dispatch_words!(
+        cells,
+        |N| enumerate_all_filling_with::<N, F>(board, tiles, on_solution, context),
+        Err(UnsolvableReason::BoardTooLarge { cells })
+    )
+}
+
+fn enumerate_all_filling_with<const N: usize, F>(
+    board: Board,
+    tiles: &[Tile],
+    on_solution: F,
+    context: SearchContext,
+) -> Result<SolutionCount, UnsolvableReason>
+where
+    F: FnMut(Solution, bool) + Send,
+{
+    let input = SearchInput::<N>::new(&board, tiles, &context.options);
+    enumerate_input(input, &board, on_solution, context)
+}
+
+/// Counts the solutions for each target separately. A target is a list of cells, which are
+/// filled on the board in addition to the filled cells of the board.
+///
+/// The placements of the tiles and the banned patterns of the pruner are only computed once for
+/// the board and reused for every target.
+/// The counters of the context count the targets as first level branches, so the progress
+/// reports how many targets are done.
+///
+/// If the operation is cancelled, the remaining targets are not searched and
+/// `UnsolvableReason::Cancelled` is returned for them.
+///
+/// # Arguments
+///
+/// * `board`: The board shared by all targets.
+/// * `targets`: The cells to fill for each target as [x, y] indices on the board.
+/// * `tiles`: The tiles to place on the board.
+/// * `context`: The settings of the search.
+///
+/// returns: Vec<Result<SolutionCount, UnsolvableReason>>: The result for each target in the
+/// order of the targets.
+pub fn count_all_filling_for_targets(
+    board: Board,
+    targets: &[Vec<[usize; 2]>],
+    tiles: &[Tile],
+    context: SearchContext,
+) -> Vec<Result<SolutionCount, UnsolvableReason>> {
+    let cells = board.get_array().len();
+    dispatch_words!(
+        cells,
+        |N| count_all_filling_for_targets_with::<N>(board, targets, tiles, context),
+        vec![Err(UnsolvableReason::BoardTooLarge { cells }); targets.len()]
     )
-    .await;
+}
+
+fn count_all_filling_for_targets_with<const N: usize>(
+    board: Board,
+    targets: &[Vec<[usize; 2]>],
+    tiles: &[Tile],
+    context: SearchContext,
+) -> Vec<Result<SolutionCount, UnsolvableReason>> {
+    let xs = board.get_array().dim().0;
+    // This index has to match the index in the Bitmask. See Bitmask::from(&Array2<bool>)
+    let empty_cell_indices = |target: &Vec<[usize; 2]>| -> Vec<usize> {
+        target
+            .iter()
+            .filter(|&&cell| !board[cell])
+            .map(|&[x, y]| y * xs + x)
+            .collect()
+    };
+    let mut fillable_cells: Vec<usize> = targets.iter().flat_map(empty_cell_indices).collect();
+    fillable_cells.sort_unstable();
+    fillable_cells.dedup();
+    let shared = SharedInput::<N>::new(&board, tiles, &context.options, &fillable_cells);
+
+    let SearchContext {
+        options,
+        counters,
+        cancel,
+    } = context;
+    counters.set_branches_total(targets.len());
+    let mut results = Vec::with_capacity(targets.len());
+    for target in targets {
+        if cancel.is_cancelled() {
+            results.push(Err(UnsolvableReason::Cancelled));
+            continue;
+        }
+
+        let mut target_board = board.clone();
+        for &cell in target {
+            target_board[cell] = true;
+        }
+        if let Err(reason) = check(&target_board, tiles, options.mode) {
+            debug!("Plausibility check failed for target {:?}.", target);
+            results.push(Err(reason));
+            counters.branch_exhausted();
+            continue;
+        }
+
+        let input = SearchInput::from_shared(
+            shared.with_filled_cells(&target_board, &empty_cell_indices(target)),
+            &target_board,
+        );
+        let target_counters = Arc::new(SearchCounters::new());
+        let target_context = SearchContext {
+            options: options.clone(),
+            counters: target_counters.clone(),
+            cancel,
+        };
+        let result = enumerate_input(input, &target_board, |_, _| {}, target_context);
+
+        let statistics = target_counters.snapshot();
+        counters.add_nodes_visited(statistics.nodes_visited());
+        counters.add_prunes(statistics.prunes());
+        if result.is_ok() {
+            counters.branch_exhausted();
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Searches the whole search tree of the prepared input and calls `on_solution` for every
+/// solution.
+fn enumerate_input<const N: usize, F>(
+    input: SearchInput<N>,
+    board: &Board,
+    on_solution: F,
+    context: SearchContext,
+) -> Result<SolutionCount, UnsolvableReason>
+where
+    F: FnMut(Solution, bool) + Send,
+{
+    let SearchInput {
+        tiles,
+        tile_indices,
+        pruner,
+        board_bitmask,
+        positioned_tiles,
+        symmetries,
+        restricted_tiles,
+    } = input;
+    let search_tiles = restricted_tiles.as_deref().unwrap_or(&positioned_tiles);
+
+    // The solvers may find solutions on several threads, so the solutions are counted and
+    // passed to `on_solution` one after another.
+    let collector = Mutex::new((0u64, HashSet::new(), on_solution));
+    let collect_solution = |placements: Vec<usize>| {
+        let solutions = match &restricted_tiles {
+            Some(restricted_tiles) => symmetries.symmetric_solutions(&placements, restricted_tiles),
+            None => vec![placements],
+        };
+        let key = symmetries.canonical_key(&solutions[0], &positioned_tiles);
+        let mut collector = collector.lock().unwrap();
+        let (total, unique_keys, on_solution) = &mut *collector;
+        let mut new_class = unique_keys.insert(key);
+        for placements in solutions {
+            *total += 1;
+            on_solution(
+                create_solution(placements, &positioned_tiles, &tiles, &tile_indices, board),
+                new_class,
+            );
+            new_class = false;
+        }
+    };
+
+    let SearchContext {
+        options,
+        counters,
+        cancel,
+    } = context;
+    let limited = LimitedCancellation::new(cancel, options.limits, &counters);
+    let result = match options.strategy {
+        SolverStrategy::Backtracking => core::enumerate_filling(
+            board.get_array().dim().0 as i32,
+            &board_bitmask,
+            search_tiles,
+            pruner,
+            counters.clone(),
+            &collect_solution,
+            &limited,
+        ),
+        SolverStrategy::DancingLinks => dlx::enumerate_filling(
+            &board_bitmask,
+            search_tiles,
+            options.mode,
+            counters.clone(),
+            &collect_solution,
+            &limited,
+        ),
+        SolverStrategy::FirstEmptyCell | SolverStrategy::MostConstrainedCell => {
+            cells::enumerate_filling(
+                &board_bitmask,
+                search_tiles,
+                pruner,
+                cell_choice(options.strategy),
+                counters.clone(),
+                &collect_solution,
+                &limited,
+            )
+        }
+    };
 
     match result {
-        Some(placements) => Ok(create_solution(
-            placements,
-            &positioned_tiles,
-            &tiles,
-            &board,
-        )),
-        None => Err(UnsolvableReason::NoFit),
+        Ok(()) => {
+            let (total, unique_keys, _) = collector.into_inner().unwrap();
+            Ok(SolutionCount::new(total, unique_keys.len() as u64))
+        }
+        Err(deepest) if limited.limit_reached() && !cancel.is_cancelled() => {
+            Err(UnsolvableReason::LimitReached {
+                deepest: create_solution(deepest, search_tiles, &tiles, &tile_indices, board),
+            })
+        }
+        Err(_) => Err(UnsolvableReason::Cancelled),
+    }
+}
+
+/// Returns how the cell based strategies choose the next cell to fill.
+fn cell_choice(strategy: SolverStrategy) -> CellChoice {
+    match strategy {
+        SolverStrategy::MostConstrainedCell => CellChoice::MostConstrained,
+        _ => CellChoice::FirstEmpty,
     }
 }
 
-fn create_solution(
+fn create_solution<const N: usize>(
     placements: Vec<usize>,
-    positioned_tiles: &[PositionedTile],
+    positioned_tiles: &[PositionedTile<N>],
     tiles: &[Tile],
+    tile_indices: &[usize],
     board: &Board,
 ) -> Solution {
     let tile_placements: Vec<TilePlacement> = placements
         .iter()
         .enumerate()
+        .filter(|(_, placement_index)| **placement_index != UNUSED)
         .map(|(tile_index, &placement_index)| {
             create_tile_placement(
                 placement_index,
                 &positioned_tiles[tile_index],
                 &tiles[tile_index],
+                tile_indices[tile_index],
                 board,
             )
         })
@@ -69,10 +572,11 @@ fn create_solution(
     Solution::new(tile_placements)
 }
 
-fn create_tile_placement(
+fn create_tile_placement<const N: usize>(
     placement_index: usize,
-    positioned_tile: &PositionedTile,
+    positioned_tile: &PositionedTile<N>,
     tile: &Tile,
+    tile_index: usize,
     board: &Board,
 ) -> TilePlacement {
     let bitmask_placement = &positioned_tile.bitmasks()[placement_index];
@@ -118,5 +622,6 @@ fn create_tile_placement(
         y_start
     };
 
-    TilePlacement::new(tile.base().clone(), rotation.clone(), (x, y))
+    let transform = positioned_tile.transform(placement_index);
+    TilePlacement::new(tile_index, tile.base().clone(), rotation, transform, (x, y))
 }
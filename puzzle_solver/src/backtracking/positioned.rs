@@ -2,9 +2,9 @@ use crate::array_util;
 use crate::backtracking::pruner::Pruner;
 use crate::bitmask::Bitmask;
 use crate::board::Board;
-use crate::tile::Tile;
+use crate::tile::{Tile, Transform};
 use log::debug;
-use ndarray::Array2;
+use std::collections::{HashMap, HashSet};
 
 /// A tile with all its possible placements on the board represented as bitmasks.
 ///
@@ -12,11 +12,18 @@ use ndarray::Array2;
 /// If the cell is 1 in the bitmask, it means that the tile occupies that cell on the board.
 /// The board itself is not represented in the bitmask.
 #[derive(Clone)]
-pub struct PositionedTile {
-    bitmasks: Vec<Bitmask>,
+pub struct PositionedTile<const N: usize> {
+    bitmasks: Vec<Bitmask<N>>,
+    /// The transform of the tile's base used by each placement in `bitmasks`.
+    transforms: Vec<Transform>,
+    /// The index of the previous tile with the same placements, if any.
+    ///
+    /// Identical tiles share the same placements in the same order, so the search only has to
+    /// place them with increasing placement indices to avoid permutations of the copies.
+    previous_copy: Option<usize>,
 }
 
-impl PositionedTile {
+impl<const N: usize> PositionedTile<N> {
     /// Creates a new PositionedTile from a Tile and a Board.
     ///
     /// The resulting PositionedTile contains all possible placements of the Tile on the Board,
@@ -28,32 +35,93 @@ impl PositionedTile {
     /// * `board`: The Board on which the Tile will be placed.
     ///
     /// returns: PositionedTile
-    pub(crate) fn new(tile: &Tile, board: &Board, pruner: &Pruner) -> Self {
-        let all_placements: Vec<Array2<bool>> = tile
+    pub(crate) fn new(tile: &Tile, board: &Board, pruner: &Pruner<N>) -> Self {
+        let (bitmasks, transforms) = tile
             .all_rotations
             .iter()
-            .flat_map(|rotation| array_util::place_on_all_positions(board.get_array(), rotation))
-            .map(|array| {
-                let mut array = array.clone();
+            .zip(&tile.transforms)
+            .flat_map(|(rotation, &transform)| {
+                array_util::place_on_all_positions(board.get_array(), rotation)
+                    .into_iter()
+                    .map(move |array| (array, transform))
+            })
+            .map(|(mut array, transform)| {
                 array_util::remove_parent(board.get_array(), &mut array);
-                array
+                (Bitmask::from(&array), transform)
             })
-            .collect();
+            .filter(|(bitmask, _)| !pruner.prune(bitmask))
+            .unzip();
 
-        let bitmasks: Vec<Bitmask> = all_placements
-            .iter()
-            .map(|array| Bitmask::from(array))
-            .filter(|bitmask| !pruner.prune(bitmask))
-            .collect();
-
-        PositionedTile { bitmasks }
+        PositionedTile {
+            bitmasks,
+            transforms,
+            previous_copy: None,
+        }
     }
 
     /// Returns a reference to Bitmasks representing all possible placements of the Tile on the Board.
-    pub fn bitmasks(&self) -> &[Bitmask] {
+    pub fn bitmasks(&self) -> &[Bitmask<N>] {
         &self.bitmasks
     }
 
+    /// Returns the transform of the tile's base used by the placement with the given index.
+    pub fn transform(&self, placement_index: usize) -> Transform {
+        self.transforms[placement_index]
+    }
+
+    /// Creates a new PositionedTile, which only contains the placements matching the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate`: Called with the index and the bitmask of each placement.
+    ///
+    /// returns: PositionedTile
+    pub(crate) fn filter<P>(&self, mut predicate: P) -> Self
+    where
+        P: FnMut(usize, &Bitmask<N>) -> bool,
+    {
+        let (bitmasks, transforms) = self
+            .bitmasks
+            .iter()
+            .zip(&self.transforms)
+            .enumerate()
+            .filter(|(index, (bitmask, _))| predicate(*index, bitmask))
+            .map(|(_, (bitmask, &transform))| (bitmask.clone(), transform))
+            .unzip();
+        PositionedTile {
+            bitmasks,
+            transforms,
+            previous_copy: self.previous_copy,
+        }
+    }
+
+    /// Returns the index of the previous tile with the same placements, if any.
+    ///
+    /// The placement index of this tile has to be greater than the one of the previous copy.
+    /// If the previous copy is not used, this tile must not be used either.
+    pub fn previous_copy(&self) -> Option<usize> {
+        self.previous_copy
+    }
+
+    /// Takes over the placements of the previous copy in their order.
+    ///
+    /// The copy may have a different base, so every placement keeps the transform of this
+    /// tile's own base, which creates it.
+    fn copy_placements_of(&mut self, previous_copy: &PositionedTile<N>) {
+        let own_transforms: HashMap<&Bitmask<N>, Transform> = self
+            .bitmasks
+            .iter()
+            .zip(self.transforms.iter().copied())
+            .collect();
+        let transforms = previous_copy
+            .bitmasks
+            .iter()
+            .map(|bitmask| own_transforms[bitmask])
+            .collect();
+        self.bitmasks = previous_copy.bitmasks.clone();
+        self.transforms = transforms;
+    }
+
     #[allow(dead_code)]
     fn print_debug(&self, board_width: i32) {
         for bitmask in self.bitmasks.iter() {
@@ -62,6 +130,99 @@ impl PositionedTile {
     }
 }
 
+/// Links tiles with the same placements to their previous copy.
+///
+/// Every copy gets the placements of the first copy, so the placement indices of the copies
+/// refer to the same placements and can be compared. The placements of tiles with copies are
+/// sorted by their first occupied cell, so the copies are placed from one side of the board to
+/// the other.
+///
+/// # Arguments
+///
+/// * `positioned_tiles`: The tiles in the order they are placed.
+pub(crate) fn link_identical_copies<const N: usize>(positioned_tiles: &mut [PositionedTile<N>]) {
+    let placement_sets: Vec<HashSet<&Bitmask<N>>> = positioned_tiles
+        .iter()
+        .map(|positioned_tile| positioned_tile.bitmasks.iter().collect())
+        .collect();
+    let previous_copies: Vec<Option<usize>> = (0..placement_sets.len())
+        .map(|i| {
+            (0..i)
+                .rev()
+                .find(|&j| placement_sets[j] == placement_sets[i])
+        })
+        .collect();
+
+    for (i, previous_copy) in previous_copies.iter().enumerate() {
+        let has_copies = previous_copies.contains(&Some(i));
+        if previous_copy.is_none() && has_copies {
+            let positioned_tile = &mut positioned_tiles[i];
+            let mut placements: Vec<(Bitmask<N>, Transform)> = positioned_tile
+                .bitmasks
+                .drain(..)
+                .zip(positioned_tile.transforms.drain(..))
+                .collect();
+            placements.sort_by_cached_key(|(bitmask, _)| {
+                (0..bitmask.relevant_bits()).find(|&index| bitmask.get_bit(index))
+            });
+            (positioned_tile.bitmasks, positioned_tile.transforms) = placements.into_iter().unzip();
+        }
+        if let Some(previous_copy) = *previous_copy {
+            let (previous_tiles, tiles) = positioned_tiles.split_at_mut(i);
+            tiles[0].copy_placements_of(&previous_tiles[previous_copy]);
+            tiles[0].previous_copy = Some(previous_copy);
+        }
+    }
+}
+
+/// Shuffles the placements of the tiles with a pseudo random generator seeded with the given
+/// seed.
+///
+/// Copies of a tile get the same order of placements as the previous copy, so their placement
+/// indices can still be compared.
+///
+/// # Arguments
+///
+/// * `positioned_tiles`: The tiles in the order they are placed, already linked to their copies.
+/// * `seed`: The seed of the pseudo random generator.
+pub(crate) fn shuffle_placements<const N: usize>(
+    positioned_tiles: &mut [PositionedTile<N>],
+    seed: u64,
+) {
+    let mut random = SplitMix64(seed);
+    for i in 0..positioned_tiles.len() {
+        match positioned_tiles[i].previous_copy {
+            Some(previous_copy) => {
+                let (previous_tiles, tiles) = positioned_tiles.split_at_mut(i);
+                tiles[0].copy_placements_of(&previous_tiles[previous_copy]);
+            }
+            None => {
+                let positioned_tile = &mut positioned_tiles[i];
+                for j in (1..positioned_tile.bitmasks.len()).rev() {
+                    let k = (random.next() % (j as u64 + 1)) as usize;
+                    positioned_tile.bitmasks.swap(j, k);
+                    positioned_tile.transforms.swap(j, k);
+                }
+            }
+        }
+    }
+}
+
+/// A small pseudo random generator, whose output only depends on the seed.
+///
+/// It is implemented here, so the shuffled order is the same on every machine and version.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,7 +234,7 @@ mod tests {
         board[[0, 0]] = true;
         let tile = Tile::new(arr2(&[[true, true, true], [true, true, false]]));
 
-        let positioned_tile = PositionedTile::new(
+        let positioned_tile: PositionedTile<1> = PositionedTile::new(
             &tile,
             &board,
             &Pruner::new_for_filling(&board, &[tile.clone()]),
@@ -207,7 +368,7 @@ mod tests {
             [false, false, false],
         ]));
 
-        let positioned_tile = PositionedTile::new(
+        let positioned_tile: PositionedTile<1> = PositionedTile::new(
             &tile,
             &board,
             &Pruner::new_for_filling(&board, &[tile.clone()]),
@@ -224,7 +385,7 @@ mod tests {
         board[[1, 1]] = true;
         let tile = Tile::new(arr2(&[[true]]));
 
-        let positioned_tile = PositionedTile::new(
+        let positioned_tile: PositionedTile<1> = PositionedTile::new(
             &tile,
             &board,
             &Pruner::new_for_filling(&board, &[tile.clone()]),
@@ -237,11 +398,75 @@ mod tests {
         let board = Board::new((3, 3));
         let tile = Tile::new(arr2(&[[true, true], [true, true]]));
 
-        let positioned_tile = PositionedTile::new(
+        let positioned_tile: PositionedTile<1> = PositionedTile::new(
             &tile,
             &board,
             &Pruner::new_for_filling(&board, &[tile.clone()]),
         );
         assert_eq!(positioned_tile.bitmasks.len(), 4);
     }
+
+    #[test]
+    fn test_link_identical_copies() {
+        let board = Board::new((3, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[false, true], [true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+        ];
+        let pruner = Pruner::new_for_filling(&board, &tiles);
+        let mut positioned_tiles: Vec<PositionedTile<1>> = tiles
+            .iter()
+            .map(|tile| PositionedTile::new(tile, &board, &pruner))
+            .collect();
+
+        link_identical_copies(&mut positioned_tiles);
+
+        let previous_copies: Vec<Option<usize>> = positioned_tiles
+            .iter()
+            .map(|positioned_tile| positioned_tile.previous_copy())
+            .collect();
+        assert_eq!(previous_copies, vec![None, None, Some(0), Some(2)]);
+        assert!(positioned_tiles[2].bitmasks() == positioned_tiles[0].bitmasks());
+        assert!(positioned_tiles[3].bitmasks() == positioned_tiles[0].bitmasks());
+    }
+
+    #[test]
+    fn test_shuffle_placements() {
+        let board = Board::new((3, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+        ];
+        let pruner = Pruner::new_for_filling(&board, &tiles);
+        let create = || {
+            let mut positioned_tiles: Vec<PositionedTile<1>> = tiles
+                .iter()
+                .map(|tile| PositionedTile::new(tile, &board, &pruner))
+                .collect();
+            link_identical_copies(&mut positioned_tiles);
+            positioned_tiles
+        };
+
+        let original = create();
+        let mut shuffled = create();
+        shuffle_placements(&mut shuffled, 42);
+        let mut shuffled_again = create();
+        shuffle_placements(&mut shuffled_again, 42);
+
+        for (original, shuffled) in original.iter().zip(shuffled.iter()) {
+            let mut original_bitmasks = original.bitmasks().to_vec();
+            let mut shuffled_bitmasks = shuffled.bitmasks().to_vec();
+            original_bitmasks.sort_by_key(|bitmask| bitmask.to_string(3));
+            shuffled_bitmasks.sort_by_key(|bitmask| bitmask.to_string(3));
+            assert!(original_bitmasks == shuffled_bitmasks);
+        }
+        assert!(shuffled[0].bitmasks() != original[0].bitmasks());
+        assert!(shuffled[2].bitmasks() == shuffled[0].bitmasks());
+        for (shuffled, shuffled_again) in shuffled.iter().zip(shuffled_again.iter()) {
+            assert!(shuffled.bitmasks() == shuffled_again.bitmasks());
+        }
+    }
 }
@@ -5,22 +5,22 @@ use crate::tile::Tile;
 use ndarray::{arr2, Array2};
 use std::hash::Hash;
 
-#[derive(Hash, Eq, PartialEq, Debug)]
-pub struct BannedBitmask {
-    pattern: Bitmask,
-    area: Bitmask,
+#[derive(Hash, Eq, PartialEq, Debug, Clone)]
+pub struct BannedBitmask<const N: usize> {
+    pattern: Bitmask<N>,
+    area: Bitmask<N>,
 }
 
-impl BannedBitmask {
-    pub fn matches(&self, bitmask: &Bitmask) -> bool {
+impl<const N: usize> BannedBitmask<N> {
+    pub fn matches(&self, bitmask: &Bitmask<N>) -> bool {
         Bitmask::and_equals(bitmask, &self.area, &self.pattern)
     }
 }
 
-pub fn create_banned_bitmasks_for_filling(
+pub fn create_banned_bitmasks_for_filling<const N: usize>(
     board: &Board,
     tiles: &[Tile],
-) -> Vec<Vec<BannedBitmask>> {
+) -> Vec<Vec<BannedBitmask<N>>> {
     let min_tile_size = tiles
         .iter()
         .map(|tile| array_util::count_biggest_connected_area_of_cells_matching(tile.base(), true))
@@ -47,12 +47,12 @@ pub fn create_banned_bitmasks_for_filling(
     banned_bitmasks
 }
 
-fn create_banned_bitmasks_for_cell(
+fn create_banned_bitmasks_for_cell<const N: usize>(
     board: &Board,
     x: usize,
     y: usize,
     min_tile_size: usize,
-) -> Vec<BannedBitmask> {
+) -> Vec<BannedBitmask<N>> {
     let mut banned_bitmasks = Vec::new();
 
     if min_tile_size > 1 {
@@ -71,7 +71,7 @@ fn create_banned_bitmasks_for_cell(
     banned_bitmasks
 }
 
-fn banned_bitmasks_1(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
+fn banned_bitmasks_1<const N: usize>(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask<N>> {
     let pattern = arr2(&[
         [false, true, false],
         [true, false, true],
@@ -94,7 +94,7 @@ fn banned_bitmasks_1(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
 }
 
 #[allow(non_snake_case)]
-fn banned_bitmasks_D2(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
+fn banned_bitmasks_D2<const N: usize>(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask<N>> {
     let pattern = arr2(&[
         [false, true, true, false],
         [true, false, false, true],
@@ -134,7 +134,7 @@ fn banned_bitmasks_D2(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
 }
 
 #[allow(non_snake_case)]
-fn banned_bitmasks_L3(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
+fn banned_bitmasks_L3<const N: usize>(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask<N>> {
     let pattern = arr2(&[
         [false, true, true, false],
         [true, false, false, true],
@@ -206,7 +206,7 @@ fn banned_bitmasks_L3(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
 }
 
 #[allow(non_snake_case)]
-fn banned_bitmasks_I3(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
+fn banned_bitmasks_I3<const N: usize>(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask<N>> {
     let pattern = arr2(&[
         [false, true, true, true, false],
         [true, false, false, false, true],
@@ -248,7 +248,7 @@ fn banned_bitmasks_I3(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
 }
 
 #[allow(non_snake_case)]
-fn banned_bitmasks_O4(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
+fn banned_bitmasks_O4<const N: usize>(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask<N>> {
     let pattern = arr2(&[
         [false, true, true, false],
         [true, false, false, true],
@@ -276,13 +276,13 @@ fn banned_bitmasks_O4(board: &Board, x: usize, y: usize) -> Vec<BannedBitmask> {
     banned_bitmasks
 }
 
-fn create_banned_bitmask_for_pattern_at_if_possible(
+fn create_banned_bitmask_for_pattern_at_if_possible<const N: usize>(
     pattern: &Array2<bool>,
     area: &Array2<bool>,
     x: isize,
     y: isize,
     board: &Board,
-) -> Option<BannedBitmask> {
+) -> Option<BannedBitmask<N>> {
     for px in 0..pattern.dim().0 {
         for py in 0..pattern.dim().1 {
             let board_x = x + px as isize;
@@ -315,13 +315,13 @@ fn create_banned_bitmask_for_pattern_at_if_possible(
 /// * `board`: The board on which the pattern is placed.
 ///
 /// returns: BannedBitmask
-fn create_banned_bitmask_for_pattern_at(
+fn create_banned_bitmask_for_pattern_at<const N: usize>(
     pattern: &Array2<bool>,
     area: &Array2<bool>,
     x: isize,
     y: isize,
     board: &Board,
-) -> BannedBitmask {
+) -> BannedBitmask<N> {
     let mut board_array = board.get_array().clone();
     board_array.fill(false);
 
@@ -357,7 +357,8 @@ mod tests {
         ]);
         let board = Board::new((5, 5));
 
-        let banned_bitmask = create_banned_bitmask_for_pattern_at(&pattern, &area, 1, 1, &board);
+        let banned_bitmask: BannedBitmask<1> =
+            create_banned_bitmask_for_pattern_at(&pattern, &area, 1, 1, &board);
         let expected_pattern_board = arr2(&[
             [false, false, false, false, false],
             [false, false, true, false, false],
@@ -393,7 +394,8 @@ mod tests {
         ]);
         let board = Board::new((5, 5));
 
-        let banned_bitmask = create_banned_bitmask_for_pattern_at(&pattern, &area, -1, -1, &board);
+        let banned_bitmask: BannedBitmask<1> =
+            create_banned_bitmask_for_pattern_at(&pattern, &area, -1, -1, &board);
         let expected_pattern_board = arr2(&[
             [false, true, false, false, false],
             [true, false, false, false, false],
@@ -429,7 +431,8 @@ mod tests {
         ]);
         let board = Board::new((5, 5));
 
-        let banned_bitmask = create_banned_bitmask_for_pattern_at(&pattern, &area, 3, 3, &board);
+        let banned_bitmask: BannedBitmask<1> =
+            create_banned_bitmask_for_pattern_at(&pattern, &area, 3, 3, &board);
         let expected_pattern_board = arr2(&[
             [false, false, false, false, false],
             [false, false, false, false, false],
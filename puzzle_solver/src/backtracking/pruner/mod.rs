@@ -1,47 +1,294 @@
+use crate::backtracking::pruner::parity::CheckerboardParity;
+use crate::backtracking::pruner::regions::RegionSizes;
 use crate::bitmask::Bitmask;
 use crate::board::Board;
+use crate::options::{PrunerStages, SolveMode};
 use crate::tile::Tile;
 use banned::BannedBitmask;
+use std::sync::Arc;
 
 mod banned;
+mod parity;
+mod regions;
 
-pub struct Pruner {
+/// A global rule of the pruner, which looks at the whole board and the remaining tiles.
+pub(crate) trait PrunerStage<const N: usize>: Send + Sync {
+    /// Decides whether the board can not be completed with the remaining tiles anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board to analyze.
+    /// * `remaining`: The tiles, which are not placed yet.
+    /// * `max_holes`: The number of cells, which may stay empty in a solution.
+    ///
+    /// returns: bool: true if a solution is impossible.
+    fn prune(&self, board: &Bitmask<N>, remaining: &RemainingTiles, max_holes: usize) -> bool;
+}
+
+/// A summary of the tiles, which are not placed yet, used by the global pruner stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RemainingTiles {
+    /// The greatest common divisor of the areas of the tiles, 0 if there are no tiles.
+    area_gcd: usize,
+    /// The area of the smallest tile, `usize::MAX` if there are no tiles.
+    min_area: usize,
+    /// The sum of the differences between black and white cells of the tiles.
+    color_difference: usize,
+}
+
+impl Default for RemainingTiles {
+    /// A summary without any tiles.
+    fn default() -> Self {
+        RemainingTiles {
+            area_gcd: 0,
+            min_area: usize::MAX,
+            color_difference: 0,
+        }
+    }
+}
+
+impl RemainingTiles {
+    /// Creates a summary of the given tiles.
+    #[cfg(test)]
+    pub fn from_tiles(tiles: &[Tile]) -> Self {
+        tiles.iter().fold(Self::default(), |remaining, tile| {
+            remaining.with(&TileSummary::new(tile))
+        })
+    }
+
+    /// Returns the summary with the given tile added.
+    fn with(self, tile: &TileSummary) -> Self {
+        RemainingTiles {
+            area_gcd: gcd(self.area_gcd, tile.area),
+            min_area: self.min_area.min(tile.area),
+            color_difference: self.color_difference + tile.color_difference,
+        }
+    }
+
+    /// Returns the greatest common divisor of the areas of the tiles.
+    pub fn area_gcd(&self) -> usize {
+        self.area_gcd
+    }
+
+    /// Returns the area of the smallest tile.
+    pub fn min_area(&self) -> usize {
+        self.min_area
+    }
+
+    /// Returns the sum of the differences between black and white cells of the tiles.
+    pub fn color_difference(&self) -> usize {
+        self.color_difference
+    }
+}
+
+/// The properties of a single tile needed by the global pruner stages.
+#[derive(Debug, Clone, Copy)]
+struct TileSummary {
+    area: usize,
+    color_difference: usize,
+}
+
+impl TileSummary {
+    fn new(tile: &Tile) -> Self {
+        TileSummary {
+            area: tile.base().iter().filter(|&&cell| cell).count(),
+            color_difference: parity::color_difference(tile.base()),
+        }
+    }
+}
+
+/// Returns the number of cells, which may stay empty in a solution.
+fn max_holes(board: &Board, tile_summaries: &[TileSummary], mode: SolveMode) -> usize {
+    match mode {
+        SolveMode::FillWithAllTiles | SolveMode::FillWithSubset => 0,
+        SolveMode::PlaceAllTiles => {
+            let board_area = board.get_array().iter().filter(|&&cell| !cell).count();
+            let tiles_area: usize = tile_summaries.iter().map(|tile| tile.area).sum();
+            board_area.saturating_sub(tiles_area)
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[derive(Clone)]
+pub struct Pruner<const N: usize> {
     /// For each relevant bit on the board, a list of banned bitmasks.
     /// The bitmasks can be checked against an index of the current board state if it is
     /// empty.
-    banned_bitmasks: Vec<Vec<BannedBitmask>>,
+    banned_bitmasks: Vec<Vec<BannedBitmask<N>>>,
+    /// The enabled global stages, which need to know the remaining tiles.
+    stages: Vec<Arc<dyn PrunerStage<N>>>,
+    /// The summary of each tile in the order the tiles were passed to the pruner.
+    tile_summaries: Vec<TileSummary>,
+    mode: SolveMode,
+    /// The number of cells, which may stay empty in a solution.
+    max_holes: usize,
 }
 
-impl Pruner {
-    /// Creates a new Pruner for use while filling the board with tiles.
+impl<const N: usize> Pruner<N> {
+    /// Creates a new Pruner for use while filling the board with all tiles.
+    #[cfg(test)]
     pub fn new_for_filling(board: &Board, tiles: &[Tile]) -> Self {
-        let banned_bitmasks = banned::create_banned_bitmasks_for_filling(&board, &tiles);
+        Self::new(
+            board,
+            tiles,
+            SolveMode::FillWithAllTiles,
+            PrunerStages::default(),
+        )
+    }
+
+    /// Creates a new Pruner for the given solve mode.
+    ///
+    /// If not all cells have to be covered, cells which can not be filled anymore are not
+    /// pruned, as long as there are not more of them than cells left empty in a solution.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board the tiles are placed on.
+    /// * `tiles`: The tiles to place on the board.
+    /// * `mode`: Which tiles have to be placed and which cells have to be covered.
+    /// * `enabled_stages`: Which rules are used to prune boards.
+    ///
+    /// returns: Pruner
+    pub fn new(
+        board: &Board,
+        tiles: &[Tile],
+        mode: SolveMode,
+        enabled_stages: PrunerStages,
+    ) -> Self {
+        let banned_bitmasks = if enabled_stages.banned_patterns {
+            banned::create_banned_bitmasks_for_filling(board, tiles)
+        } else {
+            (0..board.get_array().len()).map(|_| Vec::new()).collect()
+        };
+        let mut stages: Vec<Arc<dyn PrunerStage<N>>> = Vec::new();
+        if enabled_stages.region_sizes {
+            stages.push(Arc::new(RegionSizes::new(board)));
+        }
+        if enabled_stages.checkerboard_parity {
+            stages.push(Arc::new(CheckerboardParity::new(board)));
+        }
+        let tile_summaries: Vec<TileSummary> = tiles.iter().map(TileSummary::new).collect();
+
+        Pruner {
+            banned_bitmasks,
+            stages,
+            max_holes: max_holes(board, &tile_summaries, mode),
+            tile_summaries,
+            mode,
+        }
+    }
+
+    /// Creates a pruner for a board, which only differs from the board of this pruner by the
+    /// given filled cells.
+    ///
+    /// The banned patterns of the filled cells are removed, so leaving them empty is not pruned
+    /// anymore. The patterns of the other cells are still valid and reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board with the filled cells.
+    /// * `filled_cells`: The bit indices of the cells, which are filled additionally.
+    ///
+    /// returns: Pruner
+    pub(crate) fn with_filled_cells(&self, board: &Board, filled_cells: &[usize]) -> Self {
+        let mut pruner = self.clone();
+        for &index in filled_cells {
+            pruner.banned_bitmasks[index].clear();
+        }
+        pruner.max_holes = max_holes(board, &self.tile_summaries, self.mode);
+        pruner
+    }
+
+    /// Returns the solve mode the pruner was created for.
+    pub fn mode(&self) -> SolveMode {
+        self.mode
+    }
+
+    /// Returns the number of cells, which may stay empty in a solution.
+    pub fn max_holes(&self) -> usize {
+        self.max_holes
+    }
+
+    /// Returns true, if global stages are enabled, which need the remaining tiles.
+    pub fn has_global_stages(&self) -> bool {
+        !self.stages.is_empty()
+    }
 
-        Pruner { banned_bitmasks }
+    /// Summarizes the tiles, whose index matches the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_remaining`: Called with the index of each tile in the tiles passed to the pruner.
+    ///
+    /// returns: RemainingTiles
+    pub(crate) fn remaining<P>(&self, is_remaining: P) -> RemainingTiles
+    where
+        P: Fn(usize) -> bool,
+    {
+        self.tile_summaries
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| is_remaining(*index))
+            .fold(RemainingTiles::default(), |remaining, (_, tile)| {
+                remaining.with(tile)
+            })
     }
 
     /// Analyzes the current board state and decides whether a solution is still possible.
     /// If a solution is determined to be impossible, it returns true.
     /// Otherwise, it returns false.
     ///
+    /// An empty cell matching a banned bitmask can not be filled anymore. A solution is
+    /// impossible, if there are more of these cells than cells allowed to stay empty.
+    /// Only the banned patterns are checked, see `prune_with_remaining` for the global stages.
+    ///
     /// # Arguments
     ///
     /// * `current_board`: The board to analyze.
     ///
     /// returns: bool
-    pub fn prune(&self, current_board: &Bitmask) -> bool {
+    pub fn prune(&self, current_board: &Bitmask<N>) -> bool {
         // TODO start from the first empty cell on the board and end at the last empty cell
+        let mut holes = 0;
         for index in 0..current_board.relevant_bits() {
-            if !current_board.get_bit(index) {
-                for banned in self.banned_bitmasks[index].iter() {
-                    if banned.matches(current_board) {
-                        return true;
-                    }
+            if !current_board.get_bit(index)
+                && self.banned_bitmasks[index]
+                    .iter()
+                    .any(|banned| banned.matches(current_board))
+            {
+                holes += 1;
+                if holes > self.max_holes {
+                    return true;
                 }
             }
         }
         false
     }
+
+    /// Same as `prune`, but additionally checks the enabled global stages with the remaining
+    /// tiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_board`: The board to analyze.
+    /// * `remaining`: The tiles, which are not placed yet.
+    ///
+    /// returns: bool
+    pub(crate) fn prune_with_remaining(
+        &self,
+        current_board: &Bitmask<N>,
+        remaining: &RemainingTiles,
+    ) -> bool {
+        self.prune(current_board)
+            || self
+                .stages
+                .iter()
+                .any(|stage| stage.prune(current_board, remaining, self.max_holes))
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +296,7 @@ mod tests {
     use crate::backtracking::pruner::Pruner;
     use crate::bitmask::Bitmask;
     use crate::board::Board;
+    use crate::options::{PrunerStages, SolveMode};
     use crate::tile::Tile;
     use ndarray::arr2;
 
@@ -65,7 +313,7 @@ mod tests {
             Tile::new(arr2(&[[true, true], [true, false]])),
         ];
 
-        let pruner = Pruner::new_for_filling(&board, &tiles);
+        let pruner: Pruner<1> = Pruner::new_for_filling(&board, &tiles);
         assert!(pruner.banned_bitmasks.len() > 0);
 
         // Assert prune
@@ -146,7 +394,7 @@ mod tests {
             Tile::new(arr2(&[[true, true, true], [true, true, true]])),
         ];
 
-        let pruner = Pruner::new_for_filling(&board, &tiles);
+        let pruner: Pruner<1> = Pruner::new_for_filling(&board, &tiles);
         assert!(pruner.banned_bitmasks.len() > 0);
 
         // Assert not prune
@@ -171,4 +419,107 @@ mod tests {
             [true, true, true, true],
         ]))));
     }
+
+    #[test]
+    fn test_pruner_place_all_tiles() {
+        let board = Board::new((3, 3));
+        let tiles = vec![Tile::new(arr2(&[[true, true], [true, true]]))];
+
+        let pruner: Pruner<1> = Pruner::new(
+            &board,
+            &tiles,
+            SolveMode::PlaceAllTiles,
+            PrunerStages::default(),
+        );
+        assert_eq!(pruner.mode(), SolveMode::PlaceAllTiles);
+
+        // Two isolated cells are allowed as holes
+        assert!(!pruner.prune(&Bitmask::from(&arr2(&[
+            [false, true, false],
+            [true, true, true],
+            [true, true, true],
+        ]))));
+
+        let pruner: Pruner<1> = Pruner::new_for_filling(&board, &tiles);
+        assert!(pruner.prune(&Bitmask::from(&arr2(&[
+            [false, true, false],
+            [true, true, true],
+            [true, true, true],
+        ]))));
+    }
+
+    #[test]
+    fn test_pruner_global_stages() {
+        let board = Board::new((2, 4));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+        let board_with_opposite_corners = Bitmask::from(&arr2(&[
+            [true, false, false, false],
+            [false, false, false, true],
+        ]));
+
+        let pruner: Pruner<1> = Pruner::new_for_filling(&board, &tiles);
+        assert!(!pruner.has_global_stages());
+        let remaining = pruner.remaining(|index| index > 0);
+        assert!(!pruner.prune_with_remaining(&board_with_opposite_corners, &remaining));
+
+        let stages = PrunerStages {
+            checkerboard_parity: true,
+            ..Default::default()
+        };
+        let pruner: Pruner<1> = Pruner::new(&board, &tiles, SolveMode::FillWithAllTiles, stages);
+        assert!(pruner.has_global_stages());
+        let remaining = pruner.remaining(|index| index > 0);
+        assert!(pruner.prune_with_remaining(&board_with_opposite_corners, &remaining));
+    }
+
+    #[test]
+    fn test_pruner_without_banned_patterns() {
+        let board = Board::new((1, 3));
+        let tiles = vec![Tile::new(arr2(&[[true, true]]))];
+        let board_with_single_cell = Bitmask::from(&arr2(&[[false, true, false]]));
+
+        let pruner: Pruner<1> = Pruner::new_for_filling(&board, &tiles);
+        assert!(pruner.prune(&board_with_single_cell));
+
+        let stages = PrunerStages {
+            banned_patterns: false,
+            ..Default::default()
+        };
+        let pruner: Pruner<1> = Pruner::new(&board, &tiles, SolveMode::FillWithAllTiles, stages);
+        assert!(!pruner.prune(&board_with_single_cell));
+    }
+
+    #[test]
+    fn test_pruner_with_filled_cells() {
+        let board = Board::new((1, 3));
+        let tiles = vec![Tile::new(arr2(&[[true, true]]))];
+        let placement = Bitmask::from(&arr2(&[[true, true, false]]));
+
+        let pruner: Pruner<1> = Pruner::new(
+            &board,
+            &tiles,
+            SolveMode::PlaceAllTiles,
+            PrunerStages::default(),
+        );
+        assert_eq!(pruner.max_holes(), 1);
+
+        let mut filled_board = board.clone();
+        filled_board[[0, 2]] = true;
+        let pruner = pruner.with_filled_cells(&filled_board, &[2]);
+        assert_eq!(pruner.max_holes(), 0);
+        assert!(!pruner.prune(&placement));
+
+        let pruner: Pruner<1> = Pruner::new_for_filling(&board, &tiles);
+        assert!(pruner.prune(&placement));
+        assert!(
+            !pruner
+                .with_filled_cells(&filled_board, &[2])
+                .prune(&placement)
+        );
+    }
 }
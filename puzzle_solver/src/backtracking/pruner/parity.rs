@@ -0,0 +1,117 @@
+use crate::backtracking::pruner::{PrunerStage, RemainingTiles};
+use crate::bitmask::Bitmask;
+use crate::board::Board;
+use ndarray::Array2;
+
+/// Prunes boards, whose empty cells can not be covered by the remaining tiles because of a
+/// checkerboard coloring.
+///
+/// A tile covers the same difference between black and white cells in every orientation and
+/// position, only the color with more cells changes. So the difference of the empty cells of
+/// the board can not be larger than the sum of the differences of the remaining tiles.
+pub struct CheckerboardParity<const N: usize> {
+    black_cells: Bitmask<N>,
+    white_cells: Bitmask<N>,
+}
+
+impl<const N: usize> CheckerboardParity<N> {
+    /// Creates a new CheckerboardParity stage for the given board.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board the tiles are placed on.
+    ///
+    /// returns: CheckerboardParity
+    pub fn new(board: &Board) -> Self {
+        let black_cells = checkerboard(board.get_array().dim());
+        let white_cells = black_cells.mapv(|black| !black);
+        CheckerboardParity {
+            black_cells: Bitmask::from(&black_cells),
+            white_cells: Bitmask::from(&white_cells),
+        }
+    }
+}
+
+impl<const N: usize> PrunerStage<N> for CheckerboardParity<N> {
+    fn prune(&self, board: &Bitmask<N>, remaining: &RemainingTiles, max_holes: usize) -> bool {
+        let empty_black = board.count_zeros_in(&self.black_cells);
+        let empty_white = board.count_zeros_in(&self.white_cells);
+        empty_black.abs_diff(empty_white) > remaining.color_difference() + max_holes
+    }
+}
+
+/// Returns the difference between the black and white cells of a layout in a checkerboard
+/// coloring.
+///
+/// # Arguments
+///
+/// * `layout`: The layout, where true marks the cells to count.
+///
+/// returns: usize
+pub fn color_difference(layout: &Array2<bool>) -> usize {
+    let black_cells = checkerboard(layout.dim());
+    let (black, white) = layout
+        .iter()
+        .zip(black_cells.iter())
+        .filter(|(cell, _)| **cell)
+        .fold((0usize, 0usize), |(black, white), (_, &is_black)| {
+            if is_black {
+                (black + 1, white)
+            } else {
+                (black, white + 1)
+            }
+        });
+    black.abs_diff(white)
+}
+
+/// Returns an array of the given dimensions, where true marks the black cells of a checkerboard.
+fn checkerboard(dim: (usize, usize)) -> Array2<bool> {
+    Array2::from_shape_fn(dim, |(x, y)| (x + y) % 2 == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_color_difference() {
+        assert_eq!(color_difference(&arr2(&[[true, true]])), 0);
+        assert_eq!(color_difference(&arr2(&[[true, true, true]])), 1);
+        assert_eq!(
+            color_difference(&arr2(&[[true, true, true], [false, true, false]])),
+            2
+        );
+    }
+
+    #[test]
+    fn test_checkerboard_parity() {
+        let board = Board::new((2, 4));
+        let stage: CheckerboardParity<1> = CheckerboardParity::new(&board);
+        let dominoes = RemainingTiles::from_tiles(&[
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ]);
+
+        let board_with_opposite_corners: Bitmask<1> = Bitmask::from(&arr2(&[
+            [true, false, false, false],
+            [false, false, false, true],
+        ]));
+        assert!(stage.prune(&board_with_opposite_corners, &dominoes, 0));
+        assert!(!stage.prune(&board_with_opposite_corners, &dominoes, 2));
+
+        let board_with_adjacent_corners: Bitmask<1> = Bitmask::from(&arr2(&[
+            [true, false, false, false],
+            [true, false, false, false],
+        ]));
+        assert!(!stage.prune(&board_with_adjacent_corners, &dominoes, 0));
+
+        let t_tiles = RemainingTiles::from_tiles(&[
+            Tile::new(arr2(&[[true, true, true], [false, true, false]])),
+            Tile::new(arr2(&[[true, true]])),
+        ]);
+        assert!(!stage.prune(&board_with_opposite_corners, &t_tiles, 0));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::backtracking::pruner::{PrunerStage, RemainingTiles};
+use crate::bitmask::Bitmask;
+use crate::board::Board;
+
+/// Prunes boards with isolated empty regions, which can not be sized by the remaining tiles.
+///
+/// Every tile covers a multiple of the greatest common divisor of the remaining tile areas, so
+/// a region has to be a multiple of it and at least as large as the smallest remaining tile.
+pub struct RegionSizes {
+    /// For each cell, the indices of its horizontal and vertical neighbours.
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl RegionSizes {
+    /// Creates a new RegionSizes stage for the given board.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board the tiles are placed on.
+    ///
+    /// returns: RegionSizes
+    pub fn new(board: &Board) -> Self {
+        let (xs, ys) = board.get_array().dim();
+        let neighbours = (0..xs * ys)
+            .map(|index| {
+                let (x, y) = (index % xs, index / xs);
+                let mut cell_neighbours = Vec::with_capacity(4);
+                if x > 0 {
+                    cell_neighbours.push(index - 1);
+                }
+                if x + 1 < xs {
+                    cell_neighbours.push(index + 1);
+                }
+                if y > 0 {
+                    cell_neighbours.push(index - xs);
+                }
+                if y + 1 < ys {
+                    cell_neighbours.push(index + xs);
+                }
+                cell_neighbours
+            })
+            .collect();
+        RegionSizes { neighbours }
+    }
+}
+
+impl<const N: usize> PrunerStage<N> for RegionSizes {
+    fn prune(&self, board: &Bitmask<N>, remaining: &RemainingTiles, max_holes: usize) -> bool {
+        let mut visited = board.clone();
+        let mut stack: Vec<usize> = Vec::new();
+        let mut holes = 0;
+        while let Some(start) = visited.first_zero_bit() {
+            visited.set_bit(start);
+            stack.push(start);
+            let mut size = 0;
+            while let Some(index) = stack.pop() {
+                size += 1;
+                for &neighbour in &self.neighbours[index] {
+                    if !visited.get_bit(neighbour) {
+                        visited.set_bit(neighbour);
+                        stack.push(neighbour);
+                    }
+                }
+            }
+
+            holes += if size < remaining.min_area() {
+                size
+            } else {
+                size % remaining.area_gcd()
+            };
+            if holes > max_holes {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_region_sizes() {
+        let board = Board::new((4, 3));
+        let stage = RegionSizes::new(&board);
+        let remaining = RemainingTiles::from_tiles(&[
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true], [true, true]])),
+        ]);
+
+        let board_with_odd_regions: Bitmask<1> = Bitmask::from(&arr2(&[
+            [false, false, false],
+            [true, true, true],
+            [false, false, true],
+            [false, true, true],
+        ]));
+        assert!(stage.prune(&board_with_odd_regions, &remaining, 0));
+        assert!(stage.prune(&board_with_odd_regions, &remaining, 1));
+        assert!(!stage.prune(&board_with_odd_regions, &remaining, 2));
+
+        let board_with_even_regions: Bitmask<1> = Bitmask::from(&arr2(&[
+            [false, false, true],
+            [true, true, true],
+            [false, false, true],
+            [false, false, true],
+        ]));
+        assert!(!stage.prune(&board_with_even_regions, &remaining, 0));
+    }
+
+    #[test]
+    fn test_region_sizes_smaller_than_tiles() {
+        let board = Board::new((3, 3));
+        let stage = RegionSizes::new(&board);
+        let remaining = RemainingTiles::from_tiles(&[
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+        ]);
+
+        let board_with_small_region: Bitmask<1> = Bitmask::from(&arr2(&[
+            [false, true, true],
+            [true, false, false],
+            [true, false, false],
+        ]));
+        assert!(stage.prune(&board_with_small_region, &remaining, 0));
+    }
+}
@@ -3,21 +3,32 @@ use std::ops::{BitAnd, BitOr, BitXor, Index};
 
 /// Must be the same as the bits in the primitive type used in the bitmask array.
 const BITS_IN_PRIMITIVE: usize = 128;
-/// Number of elements in the bitmask array. Adjust this to change the size of the bitmask.
-/// This should be kept small to allow for optimized operations. It may be increased, if
-/// more puzzles are added.
-const BITMASK_ARRAY_LENGTH: usize = 3;
 /// A mask with all bits set in a single primitive type.
 const FULL_PRIMITIVE_MASK: u128 = u128::MAX;
-
-/// Total number of bits in the Bitmask. This is not necessarily the number of relevant bits.
-const TOTAL_BITS: usize = BITMASK_ARRAY_LENGTH * BITS_IN_PRIMITIVE;
+/// The largest array length of a Bitmask the solver chooses at solve time.
+pub(crate) const MAX_WORDS: usize = 16;
+/// The maximum number of bits of a Bitmask the solver chooses at solve time.
+pub(crate) const MAX_BITS: usize = MAX_WORDS * BITS_IN_PRIMITIVE;
+
+/// Returns the number of elements in the bitmask array needed to hold the given number of bits.
+///
+/// The solver chooses the array length `N` of the Bitmask at solve time based on this value,
+/// so small boards use as few elements as possible.
+///
+/// # Arguments
+///
+/// * `bits`: Number of relevant bits, e.g. the number of cells on the board.
+///
+/// returns: usize
+pub(crate) const fn words_for_bits(bits: usize) -> usize {
+    bits.div_ceil(BITS_IN_PRIMITIVE)
+}
 
 /// A Bitmask represents a set of bits, allowing for efficient bitwise operations.
-/// The size is limited to `BITMASK_ARRAY_LENGTH * BITS_IN_PRIMITIVE` bits to hopefully
-/// allow for optimized operations.
+/// The size is limited to `N * BITS_IN_PRIMITIVE` bits. `N` is a compile time constant to
+/// allow for optimized operations, so the loops over the array can be unrolled.
 #[derive(Debug, Eq, PartialEq, Hash)]
-pub(crate) struct Bitmask {
+pub(crate) struct Bitmask<const N: usize> {
     /// Number of relevant bits in the bitmask.
     /// Those are the bits that are of interest, e.g., corresponding to the board size.
     /// The rest of the bits are still used in some operations.
@@ -25,12 +36,14 @@ pub(crate) struct Bitmask {
     relevant_bits: usize,
     /// The actual bits of the bitmask.
     /// This is an array to allow for bitmasks larger than the primitive type.
-    /// Operations should use `BITMASK_ARRAY_LENGTH` to determine the number of elements to process
-    /// and allow for compiler optimizations.
-    bits: [u128; BITMASK_ARRAY_LENGTH],
+    /// Operations should loop over all `N` elements to allow for compiler optimizations.
+    bits: [u128; N],
 }
 
-impl Bitmask {
+impl<const N: usize> Bitmask<N> {
+    /// Total number of bits in the Bitmask. This is not necessarily the number of relevant bits.
+    const TOTAL_BITS: usize = N * BITS_IN_PRIMITIVE;
+
     /// Constructs a new Bitmask with the given amount of relevant bits.
     /// Relevant bits start at the index 0 and go up to `length - 1`.
     /// All bits are initialized to zero.
@@ -45,15 +58,16 @@ impl Bitmask {
     ///
     /// returns: Bitmask
     pub(crate) fn new(length: usize) -> Self {
-        if length > TOTAL_BITS {
+        if length > Self::TOTAL_BITS {
             panic!(
                 "Bitmask length {} exceeds maximum of {}",
-                length, TOTAL_BITS
+                length,
+                Self::TOTAL_BITS
             );
         }
         Bitmask {
             relevant_bits: length,
-            bits: [0; BITMASK_ARRAY_LENGTH],
+            bits: [0; N],
         }
     }
 
@@ -112,31 +126,53 @@ impl Bitmask {
     ///
     /// returns: bool
     pub(crate) fn all_relevant_bits_set(&self) -> bool {
-        match BITMASK_ARRAY_LENGTH {
-            1 => {
-                let mask = (1 << self.relevant_bits) - 1;
-                self.bits[0] & mask == mask
+        let full_words = self.relevant_bits / BITS_IN_PRIMITIVE;
+        let remaining_bits = self.relevant_bits % BITS_IN_PRIMITIVE;
+
+        for i in 0..full_words {
+            if self.bits[i] != FULL_PRIMITIVE_MASK {
+                return false;
             }
-            _ => {
-                let full_words = self.relevant_bits / BITS_IN_PRIMITIVE;
-                let remaining_bits = self.relevant_bits % BITS_IN_PRIMITIVE;
-
-                for i in 0..full_words {
-                    if self.bits[i] != FULL_PRIMITIVE_MASK {
-                        return false;
-                    }
-                }
+        }
 
-                if remaining_bits > 0 {
-                    let mask = (1 << remaining_bits) - 1;
-                    if self.bits[full_words] & mask != mask {
-                        return false;
-                    }
-                }
+        if remaining_bits > 0 {
+            let mask = (1 << remaining_bits) - 1;
+            if self.bits[full_words] & mask != mask {
+                return false;
+            }
+        }
 
-                true
+        true
+    }
+
+    /// Returns the index of the first relevant bit, which is 0.
+    ///
+    /// # Arguments
+    ///
+    /// returns: Option<usize>: None if all relevant bits are set.
+    pub(crate) fn first_zero_bit(&self) -> Option<usize> {
+        for i in 0..N {
+            if self.bits[i] != FULL_PRIMITIVE_MASK {
+                let index = i * BITS_IN_PRIMITIVE + self.bits[i].trailing_ones() as usize;
+                return (index < self.relevant_bits).then_some(index);
             }
         }
+        None
+    }
+
+    /// Counts the bits, which are set in the mask, but 0 in self.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask`: Bitmask selecting the bits to count.
+    ///
+    /// returns: usize
+    pub(crate) fn count_zeros_in(&self, mask: &Bitmask<N>) -> usize {
+        let mut count = 0;
+        for i in 0..N {
+            count += (!self.bits[i] & mask.bits[i]).count_ones() as usize;
+        }
+        count
     }
 
     /// Performs a bitwise OR operation between two bitmasks and stores the result in self.
@@ -148,21 +184,11 @@ impl Bitmask {
     /// * `b`: Second bitmask.
     ///
     /// returns: ()
-    pub(crate) const fn or(&mut self, a: &Bitmask, b: &Bitmask) {
-        match BITMASK_ARRAY_LENGTH {
-            1 => {
-                self.bits[0] = a.bits[0] | b.bits[0];
-            }
-            2 => {
-                self.bits[0] = a.bits[0] | b.bits[0];
-                self.bits[1] = a.bits[1] | b.bits[1];
-            }
-            3 => {
-                self.bits[0] = a.bits[0] | b.bits[0];
-                self.bits[1] = a.bits[1] | b.bits[1];
-                self.bits[2] = a.bits[2] | b.bits[2];
-            }
-            _ => panic!(),
+    pub(crate) const fn or(&mut self, a: &Bitmask<N>, b: &Bitmask<N>) {
+        let mut i = 0;
+        while i < N {
+            self.bits[i] = a.bits[i] | b.bits[i];
+            i += 1;
         }
     }
 
@@ -175,21 +201,11 @@ impl Bitmask {
     /// * `b`: Second bitmask.
     ///
     /// returns: ()
-    pub(crate) const fn xor(&mut self, a: &Bitmask, b: &Bitmask) {
-        match BITMASK_ARRAY_LENGTH {
-            1 => {
-                self.bits[0] = a.bits[0] ^ b.bits[0];
-            }
-            2 => {
-                self.bits[0] = a.bits[0] ^ b.bits[0];
-                self.bits[1] = a.bits[1] ^ b.bits[1];
-            }
-            3 => {
-                self.bits[0] = a.bits[0] ^ b.bits[0];
-                self.bits[1] = a.bits[1] ^ b.bits[1];
-                self.bits[2] = a.bits[2] ^ b.bits[2];
-            }
-            _ => panic!(),
+    pub(crate) const fn xor(&mut self, a: &Bitmask<N>, b: &Bitmask<N>) {
+        let mut i = 0;
+        while i < N {
+            self.bits[i] = a.bits[i] ^ b.bits[i];
+            i += 1;
         }
     }
 
@@ -202,21 +218,11 @@ impl Bitmask {
     /// * `b`: Second bitmask.
     ///
     /// returns: ()
-    pub(crate) const fn and(&mut self, a: &Bitmask, b: &Bitmask) {
-        match BITMASK_ARRAY_LENGTH {
-            1 => {
-                self.bits[0] = a.bits[0] & b.bits[0];
-            }
-            2 => {
-                self.bits[0] = a.bits[0] & b.bits[0];
-                self.bits[1] = a.bits[1] & b.bits[1];
-            }
-            3 => {
-                self.bits[0] = a.bits[0] & b.bits[0];
-                self.bits[1] = a.bits[1] & b.bits[1];
-                self.bits[2] = a.bits[2] & b.bits[2];
-            }
-            _ => panic!(),
+    pub(crate) const fn and(&mut self, a: &Bitmask<N>, b: &Bitmask<N>) {
+        let mut i = 0;
+        while i < N {
+            self.bits[i] = a.bits[i] & b.bits[i];
+            i += 1;
         }
     }
 
@@ -230,17 +236,14 @@ impl Bitmask {
     /// * `other`: Other bitmask to AND with.
     ///
     /// returns: bool
-    pub(crate) const fn and_is_zero(&self, other: &Bitmask) -> bool {
-        match BITMASK_ARRAY_LENGTH {
-            1 => (self.bits[0] & other.bits[0]) == 0,
-            2 => (self.bits[0] & other.bits[0]) == 0 && (self.bits[1] & other.bits[1]) == 0,
-            3 => {
-                (self.bits[0] & other.bits[0]) == 0
-                    && (self.bits[1] & other.bits[1]) == 0
-                    && (self.bits[2] & other.bits[2]) == 0
-            }
-            _ => panic!(),
+    pub(crate) const fn and_is_zero(&self, other: &Bitmask<N>) -> bool {
+        let mut common = 0;
+        let mut i = 0;
+        while i < N {
+            common |= self.bits[i] & other.bits[i];
+            i += 1;
         }
+        common == 0
     }
 
     /// Checks if the bitwise AND between two bitmasks equals a third bitmask.
@@ -253,17 +256,14 @@ impl Bitmask {
     /// * `c`: Bitmask to compare the result against.
     ///
     /// returns: bool
-    pub(crate) const fn and_equals(a: &Bitmask, b: &Bitmask, c: &Bitmask) -> bool {
-        match BITMASK_ARRAY_LENGTH {
-            1 => (a.bits[0] & b.bits[0]) == c.bits[0],
-            2 => (a.bits[0] & b.bits[0]) == c.bits[0] && (a.bits[1] & b.bits[1]) == c.bits[1],
-            3 => {
-                (a.bits[0] & b.bits[0]) == c.bits[0]
-                    && (a.bits[1] & b.bits[1]) == c.bits[1]
-                    && (a.bits[2] & b.bits[2]) == c.bits[2]
-            }
-            _ => panic!(),
+    pub(crate) const fn and_equals(a: &Bitmask<N>, b: &Bitmask<N>, c: &Bitmask<N>) -> bool {
+        let mut difference = 0;
+        let mut i = 0;
+        while i < N {
+            difference |= (a.bits[i] & b.bits[i]) ^ c.bits[i];
+            i += 1;
         }
+        difference == 0
     }
 
     /// Converts the bitmask to a string representation.
@@ -289,10 +289,6 @@ impl Bitmask {
         output
     }
 
-    pub(crate) const fn max_bits() -> usize {
-        TOTAL_BITS
-    }
-
     pub(crate) fn to_array2(&self, rows: usize, cols: usize) -> Array2<bool> {
         let mut array = Array2::from_elem((rows, cols), false);
         for x in 0..cols {
@@ -307,8 +303,8 @@ impl Bitmask {
     }
 }
 
-impl BitOr for Bitmask {
-    type Output = Bitmask;
+impl<const N: usize> BitOr for Bitmask<N> {
+    type Output = Bitmask<N>;
 
     fn bitor(self, rhs: Self) -> Self::Output {
         let mut output = Bitmask::new(self.relevant_bits);
@@ -317,8 +313,8 @@ impl BitOr for Bitmask {
     }
 }
 
-impl BitXor for Bitmask {
-    type Output = Bitmask;
+impl<const N: usize> BitXor for Bitmask<N> {
+    type Output = Bitmask<N>;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
         let mut output = Bitmask::new(self.relevant_bits);
@@ -327,8 +323,8 @@ impl BitXor for Bitmask {
     }
 }
 
-impl BitAnd for Bitmask {
-    type Output = Bitmask;
+impl<const N: usize> BitAnd for Bitmask<N> {
+    type Output = Bitmask<N>;
 
     fn bitand(self, rhs: Self) -> Self::Output {
         let mut output = Bitmask::new(self.relevant_bits);
@@ -337,15 +333,15 @@ impl BitAnd for Bitmask {
     }
 }
 
-impl Default for Bitmask {
+impl<const N: usize> Default for Bitmask<N> {
     /// Creates a new Bitmask, where all bits are initialized to zero.
     /// The number of relevant bits is set to the maximum supported by the Bitmask.
     fn default() -> Self {
-        Self::new(TOTAL_BITS)
+        Self::new(Self::TOTAL_BITS)
     }
 }
 
-impl From<&Array2<bool>> for Bitmask {
+impl<const N: usize> From<&Array2<bool>> for Bitmask<N> {
     /// Creates a Bitmask from a 2D array of booleans.
     /// The relevant bits are determined by the number of elements in the array.
     /// Each cell in the array corresponds to a bit in the bitmask.
@@ -365,17 +361,16 @@ impl From<&Array2<bool>> for Bitmask {
     }
 }
 
-impl Clone for Bitmask {
+impl<const N: usize> Clone for Bitmask<N> {
     fn clone(&self) -> Self {
-        let mut new_bitmask = Bitmask::new(self.relevant_bits);
-        for i in 0..BITMASK_ARRAY_LENGTH {
-            new_bitmask.bits[i] = self.bits[i];
+        Bitmask {
+            relevant_bits: self.relevant_bits,
+            bits: self.bits,
         }
-        new_bitmask
     }
 }
 
-impl Index<usize> for Bitmask {
+impl<const N: usize> Index<usize> for Bitmask<N> {
     type Output = bool;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -394,6 +389,8 @@ mod tests {
     use super::*;
     use ndarray::arr2;
 
+    type Bitmask = super::Bitmask<3>;
+
     #[test]
     fn test_new() {
         let bitmask = Bitmask::new(10);
@@ -469,6 +466,42 @@ mod tests {
         assert_eq!(bitmask.all_relevant_bits_set(), false);
     }
 
+    #[test]
+    fn test_first_zero_bit() {
+        let mut bitmask = Bitmask::new(5);
+        assert_eq!(bitmask.first_zero_bit(), Some(0));
+
+        bitmask.set_bit(0);
+        bitmask.set_bit(1);
+        bitmask.set_bit(3);
+        assert_eq!(bitmask.first_zero_bit(), Some(2));
+
+        bitmask.set_bit(2);
+        bitmask.set_bit(4);
+        assert_eq!(bitmask.first_zero_bit(), None);
+    }
+
+    #[test]
+    fn test_first_zero_bit_multiple_words() {
+        let mut bitmask = Bitmask::new(200);
+        for i in 0..150 {
+            bitmask.set_bit(i);
+        }
+        assert_eq!(bitmask.first_zero_bit(), Some(150));
+    }
+
+    #[test]
+    fn test_count_zeros_in() {
+        let mut bitmask = Bitmask::new(6);
+        bitmask.set_bit(0);
+        bitmask.set_bit(3);
+        let mut mask = Bitmask::new(6);
+        mask.set_bit(0);
+        mask.set_bit(1);
+        mask.set_bit(2);
+        assert_eq!(bitmask.count_zeros_in(&mask), 2);
+    }
+
     #[test]
     fn test_or() {
         let mut a = Bitmask::new(10);
@@ -659,8 +692,8 @@ mod tests {
     #[test]
     fn test_default() {
         let bitmask = Bitmask::default();
-        assert_eq!(bitmask.relevant_bits(), TOTAL_BITS);
-        for i in 0..TOTAL_BITS {
+        assert_eq!(bitmask.relevant_bits(), Bitmask::TOTAL_BITS);
+        for i in 0..Bitmask::TOTAL_BITS {
             assert_eq!(bitmask[i], false);
         }
     }
@@ -733,6 +766,41 @@ mod tests {
         assert_eq!(bitmask[9], false);
     }
 
+    #[test]
+    fn test_words_for_bits() {
+        assert_eq!(words_for_bits(0), 0);
+        assert_eq!(words_for_bits(1), 1);
+        assert_eq!(words_for_bits(128), 1);
+        assert_eq!(words_for_bits(129), 2);
+        assert_eq!(words_for_bits(400), 4);
+    }
+
+    #[test]
+    fn test_all_relevant_bits_set_multiple_words() {
+        let mut bitmask = super::Bitmask::<4>::new(400);
+        for i in 0..400 {
+            bitmask.set_bit(i);
+        }
+        assert!(bitmask.all_relevant_bits_set());
+
+        bitmask.clear_bit(390);
+        assert!(!bitmask.all_relevant_bits_set());
+    }
+
+    #[test]
+    fn test_and_is_zero_multiple_words() {
+        let mut a = super::Bitmask::<4>::new(400);
+        a.set_bit(10);
+        a.set_bit(300);
+
+        let mut b = super::Bitmask::<4>::new(400);
+        b.set_bit(11);
+        assert!(a.and_is_zero(&b));
+
+        b.set_bit(300);
+        assert!(!a.and_is_zero(&b));
+    }
+
     #[test]
     fn test_to_array2() {
         let bitmask = Bitmask::from(&arr2(&[[true, false, true], [false, false, true]]));
@@ -0,0 +1,671 @@
+//! The solver API running the search on the calling thread.
+//!
+//! The functions block until the search is finished. With the `rayon` feature the branches of
+//! the search are searched in parallel on the rayon thread pool, otherwise the whole search runs
+//! on the calling thread. This API does not depend on an async runtime, so it can be used from
+//! plain threads and from WASM.
+//!
+//! The progress sender of the options is not used by this API.
+//!
+//! Like the async API, the solvers search boards with at most `board::MAX_BOARD_CELLS` cells
+//! and return `UnsolvableReason::BoardTooLarge` for larger boards.
+
+use crate::array_util::TrimSides;
+use crate::backtracking::{self, SearchContext};
+use crate::board::Board;
+use crate::cancel::Cancellation;
+use crate::hint::{self, Hint, HintPolicy};
+use crate::options::SolverOptions;
+use crate::plausibility::check;
+use crate::result::{
+    Solution, SolutionCount, SolverStatistics, TilePlacement, Uniqueness, UnsolvableReason,
+};
+use crate::statistics::SearchCounters;
+use crate::tile::Tile;
+use log::debug;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Tries to place all given tiles on the board, filling it completely.
+///
+/// This is the blocking version of `puzzle_solver::solve_all_filling`, see there for the
+/// details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<Solution, UnsolvableReason>
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::blocking::solve_all_filling;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::cancel::NeverCancel;
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::tile::Tile;
+///
+/// let mut board = Board::new((3, 4));
+/// board[[0, 0]] = true;
+/// let tiles = vec![
+///     Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+///     Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+/// ];
+///
+/// let result = solve_all_filling(board, &tiles, &SolverOptions::default(), &NeverCancel);
+/// assert!(result.is_ok());
+/// ```
+pub fn solve_all_filling(
+    board: Board,
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<Solution, UnsolvableReason> {
+    let (result, _) = solve_all_filling_with_statistics(board, tiles, options, cancel);
+    result
+}
+
+/// Same as `solve_all_filling`, but additionally returns the statistics of the search.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: (Result<Solution, UnsolvableReason>, SolverStatistics)
+pub fn solve_all_filling_with_statistics(
+    board: Board,
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> (Result<Solution, UnsolvableReason>, SolverStatistics) {
+    let counters = Arc::new(SearchCounters::new());
+    let result = solve_all_filling_counted(board, tiles, options, counters.clone(), cancel);
+    let statistics = counters.snapshot();
+    debug!("Solver statistics: {:?}", statistics);
+    (result, statistics)
+}
+
+/// Same as `solve_all_filling`, but the statistics are counted with the given counters.
+pub(crate) fn solve_all_filling_counted(
+    board: Board,
+    tiles: &[Tile],
+    options: &SolverOptions,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Result<Solution, UnsolvableReason> {
+    if let Err(reason) = check(&board, tiles, options.mode) {
+        debug!("Plausibility check failed.");
+        return Err(reason);
+    }
+
+    let mut board = board;
+    let trim_sides = board.trim();
+
+    let context = SearchContext {
+        options: options.clone(),
+        counters,
+        cancel,
+    };
+    match backtracking::solve_all_filling(board, tiles, context) {
+        Ok(solution) => Ok(untrim_solution(&solution, &trim_sides)),
+        Err(UnsolvableReason::LimitReached { deepest }) => Err(UnsolvableReason::LimitReached {
+            deepest: untrim_solution(&deepest, &trim_sides),
+        }),
+        Err(reason) => Err(reason),
+    }
+}
+
+/// Same as `solve_all_filling`, but some tiles are already placed on the board and can not be
+/// moved anymore.
+///
+/// This is the blocking version of `puzzle_solver::solve_with_locked`, see there for the
+/// details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `locked`: The placements of the tiles, which are already placed on the board.
+/// * `tiles`: The free tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<Solution, UnsolvableReason>: `UnsolvableReason::InvalidLockedPlacement` if
+/// a locked placement lies outside the board or overlaps a filled cell.
+pub fn solve_with_locked(
+    board: Board,
+    locked: &[TilePlacement],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<Solution, UnsolvableReason> {
+    let board = lock_placements(board, locked, None)?;
+    solve_all_filling(board, tiles, options, cancel)
+}
+
+/// Searches a locked placement, which prevents the puzzle from being solved.
+///
+/// This is the blocking version of `puzzle_solver::find_wrong_placement`, see there for the
+/// details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `locked`: The placements of the tiles, which are already placed on the board.
+/// * `locked_tiles`: The tiles of the locked placements, indexed by their tile index.
+/// * `tiles`: The free tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<Option<usize>, UnsolvableReason>: The index of the wrong placement in
+/// `locked`, None if the puzzle is solvable with all locked placements, or the reason why the
+/// puzzle is unsolvable if removing a single placement does not help.
+pub fn find_wrong_placement(
+    board: Board,
+    locked: &[TilePlacement],
+    locked_tiles: &[Tile],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<Option<usize>, UnsolvableReason> {
+    let reason = match solve_with_locked(board.clone(), locked, tiles, options, cancel) {
+        Ok(_) => return Ok(None),
+        Err(UnsolvableReason::InvalidLockedPlacement { placement_index }) => {
+            return Ok(Some(placement_index));
+        }
+        Err(reason @ (UnsolvableReason::Cancelled | UnsolvableReason::LimitReached { .. })) => {
+            return Err(reason);
+        }
+        Err(reason) => reason,
+    };
+
+    for (placement_index, placement) in locked.iter().enumerate() {
+        let freed_tile = locked_tiles
+            .get(placement.tile_index())
+            .ok_or(UnsolvableReason::InvalidLockedPlacement { placement_index })?;
+        let board = lock_placements(board.clone(), locked, Some(placement_index))?;
+        let mut tiles = tiles.to_vec();
+        tiles.push(freed_tile.clone());
+        match solve_all_filling(board, &tiles, options, cancel) {
+            Ok(_) => return Ok(Some(placement_index)),
+            Err(reason @ (UnsolvableReason::Cancelled | UnsolvableReason::LimitReached { .. })) => {
+                return Err(reason);
+            }
+            Err(_) => {}
+        }
+    }
+    Err(reason)
+}
+
+/// Searches a solution and returns one of its placements as a hint for the next move.
+///
+/// This is the blocking version of `puzzle_solver::find_hint`, see there for the details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `policy`: Which placement of the solution is suggested.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<Option<Hint>, UnsolvableReason>: None, if the board is already filled.
+pub fn find_hint(
+    board: Board,
+    tiles: &[Tile],
+    policy: HintPolicy,
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<Option<Hint>, UnsolvableReason> {
+    let solution = solve_all_filling(board.clone(), tiles, options, cancel)?;
+    Ok(hint::choose_hint(&board, tiles, &solution, policy))
+}
+
+/// Fills the cells covered by the locked placements on the board.
+///
+/// # Arguments
+///
+/// * `board`: The board to fill the cells on.
+/// * `locked`: The placements to fill the cells of.
+/// * `skipped`: The index of a placement, which is left out.
+///
+/// returns: Result<Board, UnsolvableReason>
+pub(crate) fn lock_placements(
+    mut board: Board,
+    locked: &[TilePlacement],
+    skipped: Option<usize>,
+) -> Result<Board, UnsolvableReason> {
+    for (placement_index, placement) in locked.iter().enumerate() {
+        if skipped == Some(placement_index) {
+            continue;
+        }
+        let (x, y) = placement.position();
+        for ((dx, dy), &cell) in placement.rotation().indexed_iter() {
+            if !cell {
+                continue;
+            }
+            let index = [x + dx, y + dy];
+            if board.get_array().get(index) != Some(&false) {
+                debug!("Locked placement {} is invalid.", placement_index);
+                return Err(UnsolvableReason::InvalidLockedPlacement { placement_index });
+            }
+            board[index] = true;
+        }
+    }
+    Ok(board)
+}
+
+/// Counts all solutions for placing all given tiles on the board, filling it completely.
+///
+/// This is the blocking version of `puzzle_solver::count_all_solutions`, see there for the
+/// details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<SolutionCount, UnsolvableReason>
+pub fn count_all_solutions(
+    board: Board,
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<SolutionCount, UnsolvableReason> {
+    let counters = Arc::new(SearchCounters::new());
+    enumerate_all_filling(board, tiles, |_, _| {}, options, counters, cancel)
+}
+
+/// Searches all solutions for placing all given tiles on the board, filling it completely.
+///
+/// Every solution is passed to `on_solution` as soon as it is found. The solutions are passed
+/// one after another, but not necessarily on the calling thread.
+/// This is the blocking version of `puzzle_solver::enumerate_solutions`, see there for the
+/// details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `on_solution`: Called for every solution found.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<SolutionCount, UnsolvableReason>
+pub fn enumerate_solutions<F>(
+    board: Board,
+    tiles: &[Tile],
+    mut on_solution: F,
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<SolutionCount, UnsolvableReason>
+where
+    F: FnMut(Solution) + Send,
+{
+    let counters = Arc::new(SearchCounters::new());
+    enumerate_all_filling(
+        board,
+        tiles,
+        |solution, _| on_solution(solution),
+        options,
+        counters,
+        cancel,
+    )
+}
+
+/// Counts the solutions for placing all given tiles on the board for each of the given targets.
+///
+/// This is the blocking version of `puzzle_solver::count_solutions_for_targets`, see there for
+/// the details.
+///
+/// # Arguments
+///
+/// * `board`: The board shared by all targets.
+/// * `targets`: The cells to fill for each target as (x, y) positions on the board.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Vec<Result<SolutionCount, UnsolvableReason>>: The result for each target in the
+/// order of the targets.
+pub fn count_solutions_for_targets(
+    board: Board,
+    targets: &[Vec<(usize, usize)>],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Vec<Result<SolutionCount, UnsolvableReason>> {
+    let counters = Arc::new(SearchCounters::new());
+    count_solutions_for_targets_counted(board, targets, tiles, options, counters, cancel)
+}
+
+/// Same as `count_solutions_for_targets`, but the statistics are counted with the given
+/// counters.
+pub(crate) fn count_solutions_for_targets_counted(
+    board: Board,
+    targets: &[Vec<(usize, usize)>],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Vec<Result<SolutionCount, UnsolvableReason>> {
+    let mut board = board;
+    let trim_sides = board.trim();
+    let (xs, ys) = board.get_array().dim();
+    let trimmed_targets: Vec<Vec<[usize; 2]>> = targets
+        .iter()
+        .map(|target| {
+            target
+                .iter()
+                .filter_map(|&(x, y)| {
+                    let x = x.checked_sub(trim_sides.lower_x).filter(|&x| x < xs)?;
+                    let y = y.checked_sub(trim_sides.lower_y).filter(|&y| y < ys)?;
+                    Some([x, y])
+                })
+                .collect()
+        })
+        .collect();
+
+    let context = SearchContext {
+        options: options.clone(),
+        counters: counters.clone(),
+        cancel,
+    };
+    let results =
+        backtracking::count_all_filling_for_targets(board, &trimmed_targets, tiles, context);
+    debug!("Solver statistics: {:?}", counters.snapshot());
+    results
+}
+
+/// Checks whether placing all given tiles on the board, filling it completely, has exactly one
+/// solution.
+///
+/// This is the blocking version of `puzzle_solver::check_uniqueness`, see there for the
+/// details.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `identify_symmetric`: Whether symmetric solutions are treated as identical.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel`: Decides whether the operation is cancelled.
+///
+/// returns: Result<Uniqueness, UnsolvableReason>
+pub fn check_uniqueness(
+    board: Board,
+    tiles: &[Tile],
+    identify_symmetric: bool,
+    options: &SolverOptions,
+    cancel: &dyn Cancellation,
+) -> Result<Uniqueness, UnsolvableReason> {
+    let counters = Arc::new(SearchCounters::new());
+    check_uniqueness_counted(board, tiles, identify_symmetric, options, counters, cancel)
+}
+
+/// Same as `check_uniqueness`, but the statistics are counted with the given counters.
+pub(crate) fn check_uniqueness_counted(
+    board: Board,
+    tiles: &[Tile],
+    identify_symmetric: bool,
+    options: &SolverOptions,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Result<Uniqueness, UnsolvableReason> {
+    let found_second = AtomicBool::new(false);
+    let stop = || found_second.load(Ordering::Relaxed) || cancel.is_cancelled();
+    let mut solutions: Vec<Solution> = Vec::with_capacity(2);
+    let result = enumerate_all_filling(
+        board,
+        tiles,
+        |solution, new_class| {
+            if solutions.len() < 2 && (new_class || !identify_symmetric) {
+                solutions.push(solution);
+                if solutions.len() == 2 {
+                    found_second.store(true, Ordering::Relaxed);
+                }
+            }
+        },
+        options,
+        counters,
+        &stop,
+    );
+
+    let mut solutions = solutions.into_iter();
+    match (solutions.next(), solutions.next()) {
+        (Some(first), Some(second)) => Ok(Uniqueness::Multiple(first, second)),
+        _ if cancel.is_cancelled() => Err(UnsolvableReason::Cancelled),
+        (first, _) => result.map(|_| first.map_or(Uniqueness::None, Uniqueness::Unique)),
+    }
+}
+
+/// Searches the whole search tree and calls `on_solution` for every solution on the untrimmed
+/// board.
+pub(crate) fn enumerate_all_filling<F>(
+    board: Board,
+    tiles: &[Tile],
+    mut on_solution: F,
+    options: &SolverOptions,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Result<SolutionCount, UnsolvableReason>
+where
+    F: FnMut(Solution, bool) + Send,
+{
+    if let Err(reason) = check(&board, tiles, options.mode) {
+        debug!("Plausibility check failed.");
+        return Err(reason);
+    }
+
+    let mut board = board;
+    let trim_sides = board.trim();
+
+    let context = SearchContext {
+        options: options.clone(),
+        counters,
+        cancel,
+    };
+    let result = backtracking::enumerate_all_filling(
+        board,
+        tiles,
+        |solution, new_class| on_solution(untrim_solution(&solution, &trim_sides), new_class),
+        context,
+    );
+    match result {
+        Err(UnsolvableReason::LimitReached { deepest }) => Err(UnsolvableReason::LimitReached {
+            deepest: untrim_solution(&deepest, &trim_sides),
+        }),
+        result => result,
+    }
+}
+
+/// Moves the placements of a solution for a trimmed board back to the untrimmed board.
+fn untrim_solution(solution: &Solution, trim_sides: &TrimSides) -> Solution {
+    let untrimmed_placements: Vec<TilePlacement> = solution
+        .placements()
+        .iter()
+        .map(|placement| {
+            let (x, y) = placement.position();
+            let (untrimmed_x, untrimmed_y) = (x + trim_sides.lower_x, y + trim_sides.lower_y);
+            TilePlacement::new(
+                placement.tile_index(),
+                placement.base().clone(),
+                placement.rotation().clone(),
+                placement.transform(),
+                (untrimmed_x, untrimmed_y),
+            )
+        })
+        .collect();
+    Solution::new(untrimmed_placements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::MAX_BOARD_CELLS;
+    use crate::cancel::NeverCancel;
+    use crate::options::SolverStrategy;
+    use crate::tile::{AllowedOrientations, Transform};
+    use ndarray::{Array2, arr2};
+
+    const ALL_STRATEGIES: [SolverStrategy; 4] = [
+        SolverStrategy::Backtracking,
+        SolverStrategy::DancingLinks,
+        SolverStrategy::FirstEmptyCell,
+        SolverStrategy::MostConstrainedCell,
+    ];
+
+    fn dominoes() -> Vec<Tile> {
+        vec![Tile::new(arr2(&[[true, true]])); 3]
+    }
+
+    #[test]
+    fn test_solve_all_filling() {
+        let board = Board::new((2, 3));
+        for strategy in ALL_STRATEGIES {
+            let options = SolverOptions {
+                strategy,
+                ..Default::default()
+            };
+            let solution =
+                solve_all_filling(board.clone(), &dominoes(), &options, &NeverCancel).unwrap();
+            assert_eq!(solution.placements().len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_count_all_solutions() {
+        for strategy in ALL_STRATEGIES {
+            let options = SolverOptions {
+                strategy,
+                ..Default::default()
+            };
+            let count =
+                count_all_solutions(Board::new((2, 3)), &dominoes(), &options, &NeverCancel)
+                    .unwrap();
+            assert_eq!(count.total(), 3);
+            assert_eq!(count.unique(), 2);
+        }
+    }
+
+    #[test]
+    fn test_enumerate_solutions() {
+        let mut solutions = Vec::new();
+        let count = enumerate_solutions(
+            Board::new((2, 3)),
+            &dominoes(),
+            |solution| solutions.push(solution),
+            &SolverOptions::default(),
+            &NeverCancel,
+        )
+        .unwrap();
+        assert_eq!(count.total(), 3);
+        assert_eq!(solutions.len(), 3);
+    }
+
+    #[test]
+    fn test_enumerate_solutions_reports_transforms_of_padded_base() {
+        // The base has an empty row, so its placed cells do not match any of its orientations.
+        let padded = arr2(&[[true, false], [true, false], [true, false]]);
+        let tiles = vec![
+            Tile::with_orientations(padded, AllowedOrientations::RotationsOnly),
+            Tile::new(arr2(&[[true, true], [true, true], [true, true]])),
+        ];
+        for strategy in ALL_STRATEGIES {
+            let options = SolverOptions {
+                strategy,
+                ..Default::default()
+            };
+            let mut placements = Vec::new();
+            enumerate_solutions(
+                Board::new((3, 3)),
+                &tiles,
+                |solution| placements.extend(solution.placements().to_vec()),
+                &options,
+                &NeverCancel,
+            )
+            .unwrap();
+
+            // The padded tile fills one side of the board in each of its four rotations.
+            let mut rotations: Vec<u8> = placements
+                .iter()
+                .filter(|placement| placement.tile_index() == 0)
+                .map(|placement| placement.transform().rotations())
+                .collect();
+            rotations.sort_unstable();
+            assert_eq!(rotations, vec![0, 1, 2, 3]);
+            let upside_down = placements
+                .iter()
+                .find(|placement| {
+                    placement.tile_index() == 0 && placement.transform() == Transform::new(false, 2)
+                })
+                .unwrap();
+            assert_eq!(upside_down.position(), (0, 2));
+        }
+    }
+
+    #[test]
+    fn test_check_uniqueness() {
+        let result = check_uniqueness(
+            Board::new((2, 3)),
+            &dominoes(),
+            false,
+            &SolverOptions::default(),
+            &NeverCancel,
+        );
+        assert!(matches!(result, Ok(Uniqueness::Multiple(_, _))));
+    }
+
+    #[test]
+    fn test_find_hint() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true]])),
+        ];
+        let hint = find_hint(
+            Board::new((3, 2)),
+            &tiles,
+            HintPolicy::LargestTile,
+            &SolverOptions::default(),
+            &NeverCancel,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(hint.placement().tile_index(), 0);
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let cancel = AtomicBool::new(true);
+        let result = count_all_solutions(
+            Board::new((2, 3)),
+            &dominoes(),
+            &SolverOptions::default(),
+            &cancel,
+        );
+        assert_eq!(result, Err(UnsolvableReason::Cancelled));
+    }
+
+    #[test]
+    fn test_board_too_large() {
+        let cells = MAX_BOARD_CELLS + 1;
+        let tiles = vec![Tile::new(Array2::from_elem((cells, 1), true))];
+        let result = solve_all_filling(
+            Board::new((cells, 1)),
+            &tiles,
+            &SolverOptions::default(),
+            &NeverCancel,
+        );
+        assert_eq!(result, Err(UnsolvableReason::BoardTooLarge { cells }));
+    }
+}
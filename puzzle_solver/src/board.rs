@@ -1,9 +1,16 @@
 use crate::array_util;
 use crate::array_util::TrimSides;
+use crate::bitmask;
 use log::debug;
 use ndarray::Array2;
 use std::ops::{Index, IndexMut};
 
+/// The maximum number of cells of a board the solver can search.
+///
+/// Filled rows and columns at the sides of the board are removed before the search, so they do
+/// not count. The solvers return `UnsolvableReason::BoardTooLarge` for larger boards.
+pub const MAX_BOARD_CELLS: usize = bitmask::MAX_BITS;
+
 /// Represents a 2D board for the puzzle, where each cell is either true (filled) or false (empty).
 /// A filled cell is either outside the puzzle area or blocked by a placed tile.
 /// An empty cell is not blocked by a tile and a tile can be placed there.
@@ -19,7 +26,15 @@ use std::ops::{Index, IndexMut};
 /// board[[2, 3]] = true;
 /// assert_eq!(board[[2, 3]], true);
 /// ```
-pub struct Board(Array2<bool>);
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Board(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::bool_grid"))] Array2<bool>,
+);
 
 impl Board {
     /// Creates a new Board with the given dimensions, initialized to all false (empty).
@@ -0,0 +1,312 @@
+use crate::cancel::Cancellation;
+use crate::options::SolutionSelection;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Tells a branch of the search, whether it can stop searching.
+///
+/// A branch stops, if the search was cancelled or if another branch found a solution, which is
+/// preferred by the solution selection.
+pub(crate) struct BranchStop<'a> {
+    cancel: &'a dyn Cancellation,
+    /// The lowest index of a branch, which found a solution, `usize::MAX` if none did.
+    found: &'a AtomicUsize,
+    branch: usize,
+    /// The selection of the solution, None if all solutions are enumerated.
+    selection: Option<SolutionSelection>,
+}
+
+impl BranchStop<'_> {
+    /// Returns true, if the branch can stop searching.
+    pub(crate) fn should_stop(&self) -> bool {
+        if self.cancel.is_cancelled() {
+            return true;
+        }
+        let found = self.found.load(Ordering::Relaxed);
+        match self.selection {
+            None => false,
+            Some(SolutionSelection::FirstFound) => found != usize::MAX,
+            Some(SolutionSelection::FirstInOrder | SolutionSelection::Seeded(_)) => {
+                found < self.branch
+            }
+        }
+    }
+}
+
+/// The deepest partial placement a search reached.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Deepest {
+    /// The number of placed tiles.
+    depth: usize,
+    /// The index of the placement for each tile, `UNUSED` for the tiles not placed.
+    placements: Vec<usize>,
+}
+
+impl Deepest {
+    /// Remembers the placements, if more tiles are placed than in the deepest placements so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth`: The number of placed tiles.
+    /// * `placements`: Creates the placements, only called if they are remembered.
+    #[inline]
+    pub(crate) fn update(&mut self, depth: usize, placements: impl FnOnce() -> Vec<usize>) {
+        if depth > self.depth {
+            self.depth = depth;
+            self.placements = placements();
+        }
+    }
+
+    /// Returns the deeper of both placements.
+    fn deeper(self, other: Deepest) -> Deepest {
+        if other.depth > self.depth {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Searches every branch and returns the solution chosen by the selection.
+///
+/// With the `rayon` feature the branches are searched in parallel on the rayon thread pool,
+/// otherwise one after another on the calling thread.
+///
+/// # Arguments
+///
+/// * `branches`: The solvers for the branches in the order of the search.
+/// * `selection`: Which solution to return.
+/// * `cancel`: The cancellation of the search.
+/// * `search`: Searches a single branch and returns its solution or the deepest placements it
+///   reached.
+///
+/// returns: Result<Vec<usize>, Vec<usize>>: The placements of the selected solution, or the
+/// deepest partial placements of all branches, if no branch found a solution or the search was
+/// cancelled.
+pub(crate) fn search_branches<S, F>(
+    branches: Vec<S>,
+    selection: SolutionSelection,
+    cancel: &dyn Cancellation,
+    search: F,
+) -> Result<Vec<usize>, Vec<usize>>
+where
+    S: Send,
+    F: Fn(S, &BranchStop) -> Result<Vec<usize>, Deepest> + Sync,
+{
+    let found = AtomicUsize::new(usize::MAX);
+    let search_branch = |(branch, solver): (usize, S)| {
+        let stop = BranchStop {
+            cancel,
+            found: &found,
+            branch,
+            selection: Some(selection),
+        };
+        if stop.should_stop() {
+            return Err(Deepest::default());
+        }
+        let result = search(solver, &stop);
+        if result.is_ok() {
+            found.fetch_min(branch, Ordering::Relaxed);
+        }
+        result
+    };
+
+    #[cfg(feature = "rayon")]
+    let results: Vec<Result<Vec<usize>, Deepest>> = branches
+        .into_par_iter()
+        .enumerate()
+        .map(search_branch)
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let results: Vec<Result<Vec<usize>, Deepest>> = branches
+        .into_iter()
+        .enumerate()
+        .map(search_branch)
+        .collect();
+
+    // After cancelling, an earlier branch may have stopped before finding a solution preferred
+    // by the selection. A solution found is still returned, because it solves the puzzle.
+    let mut deepest = Deepest::default();
+    for result in results {
+        match result {
+            Ok(placements) => return Ok(placements),
+            Err(branch_deepest) => deepest = deepest.deeper(branch_deepest),
+        }
+    }
+    Err(deepest.placements)
+}
+
+/// Searches every branch completely.
+///
+/// The solutions have to be reported by the search of a branch itself.
+///
+/// # Arguments
+///
+/// * `branches`: The solvers for the branches.
+/// * `cancel`: The cancellation of the search.
+/// * `search`: Searches a single branch and returns the deepest placements it reached.
+///
+/// returns: Result<(), Vec<usize>>: Ok if every branch was searched completely, the deepest
+/// placements of all branches if the search was cancelled.
+pub(crate) fn enumerate_branches<S, F>(
+    branches: Vec<S>,
+    cancel: &dyn Cancellation,
+    search: F,
+) -> Result<(), Vec<usize>>
+where
+    S: Send,
+    F: Fn(S, &BranchStop) -> Deepest + Sync,
+{
+    let found = AtomicUsize::new(usize::MAX);
+    let search_branch = |(branch, solver): (usize, S)| {
+        let stop = BranchStop {
+            cancel,
+            found: &found,
+            branch,
+            selection: None,
+        };
+        if stop.should_stop() {
+            return Deepest::default();
+        }
+        search(solver, &stop)
+    };
+
+    #[cfg(feature = "rayon")]
+    let deepest = branches
+        .into_par_iter()
+        .enumerate()
+        .map(search_branch)
+        .reduce(Deepest::default, Deepest::deeper);
+    #[cfg(not(feature = "rayon"))]
+    let deepest = branches
+        .into_iter()
+        .enumerate()
+        .map(search_branch)
+        .fold(Deepest::default(), Deepest::deeper);
+
+    if cancel.is_cancelled() {
+        Err(deepest.placements)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancel::NeverCancel;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicBool;
+
+    /// Searches a branch, which finds a solution after the given number of steps, or never if
+    /// it is None.
+    fn search_steps(steps: Option<usize>, stop: &BranchStop) -> Result<Vec<usize>, Deepest> {
+        let mut deepest = Deepest::default();
+        for step in 0.. {
+            if Some(step) == steps {
+                return Ok(vec![step]);
+            }
+            deepest.update(step, || vec![step]);
+            if steps.is_none() && step > 100 {
+                break;
+            }
+            if stop.should_stop() {
+                break;
+            }
+            std::thread::yield_now();
+        }
+        Err(deepest)
+    }
+
+    #[test]
+    fn test_search_branches_first_in_order() {
+        let branches = vec![None, Some(1000), Some(1)];
+        let result = search_branches(
+            branches,
+            SolutionSelection::FirstInOrder,
+            &NeverCancel,
+            search_steps,
+        );
+        assert_eq!(result, Ok(vec![1000]));
+    }
+
+    #[test]
+    fn test_search_branches_first_found() {
+        let branches = vec![None, Some(3), None];
+        let result = search_branches(
+            branches,
+            SolutionSelection::FirstFound,
+            &NeverCancel,
+            search_steps,
+        );
+        assert_eq!(result, Ok(vec![3]));
+    }
+
+    #[test]
+    fn test_search_branches_cancelled() {
+        let cancel = AtomicBool::new(true);
+        let result = search_branches(
+            vec![Some(1)],
+            SolutionSelection::FirstFound,
+            &cancel,
+            search_steps,
+        );
+        assert_eq!(result, Err(vec![]));
+    }
+
+    #[test]
+    fn test_search_branches_cancelled_after_solution() {
+        let cancel = AtomicBool::new(false);
+        let result = search_branches(
+            vec![Some(1)],
+            SolutionSelection::FirstInOrder,
+            &cancel,
+            |steps, stop| {
+                let result = search_steps(steps, stop);
+                cancel.store(true, Ordering::Relaxed);
+                result
+            },
+        );
+        assert_eq!(result, Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_search_branches_deepest() {
+        let result = search_branches(
+            vec![None, None],
+            SolutionSelection::FirstFound,
+            &NeverCancel,
+            search_steps,
+        );
+        assert_eq!(result, Err(vec![101]));
+    }
+
+    #[test]
+    fn test_enumerate_branches() {
+        let searched = Mutex::new(Vec::new());
+        let result = enumerate_branches(vec![0, 1, 2], &NeverCancel, |branch, _| {
+            searched.lock().unwrap().push(branch);
+            Deepest::default()
+        });
+        assert_eq!(result, Ok(()));
+        let mut searched = searched.into_inner().unwrap();
+        searched.sort();
+        assert_eq!(searched, vec![0, 1, 2]);
+
+        let cancel = AtomicBool::new(true);
+        let result = enumerate_branches(vec![0], &cancel, |_, _| Deepest::default());
+        assert_eq!(result, Err(vec![]));
+    }
+
+    #[test]
+    fn test_enumerate_branches_deepest() {
+        let cancel = AtomicBool::new(false);
+        let result = enumerate_branches(vec![None, None], &cancel, |steps, stop| {
+            let deepest = search_steps(steps, stop).unwrap_err();
+            cancel.store(true, Ordering::Relaxed);
+            deepest
+        });
+        assert_eq!(result, Err(vec![101]));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::options::SearchLimits;
+use crate::statistics::SearchCounters;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Decides whether a running search should stop.
+///
+/// The solver checks the cancellation from time to time while searching, so a search may run
+/// a little longer after it was cancelled.
+/// It is implemented for `AtomicBool`, for closures returning a bool and, with the `tokio`
+/// feature, for `CancellationToken`.
+pub trait Cancellation: Sync {
+    /// Returns true, if the search should stop.
+    fn is_cancelled(&self) -> bool;
+}
+
+impl Cancellation for AtomicBool {
+    fn is_cancelled(&self) -> bool {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+impl<F> Cancellation for F
+where
+    F: Fn() -> bool + Sync,
+{
+    fn is_cancelled(&self) -> bool {
+        self()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Cancellation for tokio_util::sync::CancellationToken {
+    fn is_cancelled(&self) -> bool {
+        self.is_cancelled()
+    }
+}
+
+/// A cancellation, which never cancels the search.
+pub struct NeverCancel;
+
+impl Cancellation for NeverCancel {
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Cancels the search, if the given cancellation does or if a limit of the search is reached.
+pub(crate) struct LimitedCancellation<'a> {
+    cancel: &'a dyn Cancellation,
+    limits: SearchLimits,
+    counters: &'a SearchCounters,
+    limit_reached: AtomicBool,
+}
+
+impl<'a> LimitedCancellation<'a> {
+    pub(crate) fn new(
+        cancel: &'a dyn Cancellation,
+        limits: SearchLimits,
+        counters: &'a SearchCounters,
+    ) -> Self {
+        LimitedCancellation {
+            cancel,
+            limits,
+            counters,
+            limit_reached: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns true, if the search was stopped, because a limit was reached.
+    pub(crate) fn limit_reached(&self) -> bool {
+        self.limit_reached.load(Ordering::Relaxed)
+    }
+}
+
+impl Cancellation for LimitedCancellation<'_> {
+    fn is_cancelled(&self) -> bool {
+        if self.cancel.is_cancelled() {
+            return true;
+        }
+        if self.limit_reached() {
+            return true;
+        }
+        if self.limits == SearchLimits::default() {
+            return false;
+        }
+        let statistics = self.counters.snapshot();
+        let exceeded = self
+            .limits
+            .max_nodes
+            .is_some_and(|max_nodes| statistics.nodes_visited() >= max_nodes)
+            || self
+                .limits
+                .max_duration
+                .is_some_and(|max_duration| statistics.elapsed() >= max_duration);
+        if exceeded {
+            self.limit_reached.store(true, Ordering::Relaxed);
+        }
+        exceeded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_bool() {
+        let cancel = AtomicBool::new(false);
+        assert!(!Cancellation::is_cancelled(&cancel));
+        cancel.store(true, Ordering::Relaxed);
+        assert!(Cancellation::is_cancelled(&cancel));
+    }
+
+    #[test]
+    fn test_closure() {
+        assert!(Cancellation::is_cancelled(&|| true));
+        assert!(!Cancellation::is_cancelled(&|| false));
+        assert!(!NeverCancel.is_cancelled());
+    }
+
+    #[test]
+    fn test_limited_cancellation() {
+        let counters = SearchCounters::new();
+        let limits = SearchLimits {
+            max_nodes: Some(10),
+            ..Default::default()
+        };
+        let cancel = LimitedCancellation::new(&NeverCancel, limits, &counters);
+        assert!(!cancel.is_cancelled());
+        counters.add_nodes_visited(10);
+        assert!(cancel.is_cancelled());
+        assert!(cancel.limit_reached());
+
+        let cancel = LimitedCancellation::new(&|| true, limits, &counters);
+        assert!(cancel.is_cancelled());
+        assert!(!cancel.limit_reached());
+    }
+}
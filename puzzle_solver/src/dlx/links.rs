@@ -0,0 +1,326 @@
+/// The index of the root node, which links all column headers.
+const ROOT: usize = 0;
+
+/// A sparse 0/1 matrix of an exact cover problem stored as dancing links.
+///
+/// Every 1 in the matrix is a node, which is linked to its neighbours in the same row
+/// (left/right) and the same column (up/down). Both directions are circular.
+/// Node 0 is the root, nodes 1..=num_columns are the column headers and all other nodes are
+/// the entries of the rows.
+///
+/// Covering a column removes it and all rows intersecting it from the matrix. Uncovering it in
+/// reverse order restores the matrix exactly, which allows backtracking without copying.
+#[derive(Clone)]
+pub(crate) struct DancingLinks {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The column header of each node.
+    column: Vec<usize>,
+    /// The row index of each node. Undefined for the root and column headers.
+    row: Vec<usize>,
+    /// The number of nodes left in each column, indexed by the column header.
+    size: Vec<usize>,
+}
+
+impl DancingLinks {
+    /// Creates the dancing links for the given rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_columns`: The number of columns, which all have to be covered exactly once.
+    /// * `rows`: The column indices of the 1s in each row. The indices are zero based.
+    ///
+    /// returns: DancingLinks
+    #[cfg(test)]
+    pub(crate) fn new(num_columns: usize, rows: &[Vec<usize>]) -> Self {
+        Self::with_secondary_columns(num_columns, rows, |_| false)
+    }
+
+    /// Creates the dancing links for the given rows, where some columns are secondary.
+    ///
+    /// A secondary column has to be covered at most once instead of exactly once. Its header is
+    /// not linked to the root, so it is never chosen and the matrix is solved without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_columns`: The number of columns.
+    /// * `rows`: The column indices of the 1s in each row. The indices are zero based.
+    /// * `is_secondary`: Returns true for the zero based index of a secondary column.
+    ///
+    /// returns: DancingLinks
+    pub(crate) fn with_secondary_columns<F>(
+        num_columns: usize,
+        rows: &[Vec<usize>],
+        is_secondary: F,
+    ) -> Self
+    where
+        F: Fn(usize) -> bool,
+    {
+        let num_nodes = 1 + num_columns + rows.iter().map(|row| row.len()).sum::<usize>();
+        let mut links = DancingLinks {
+            left: Vec::with_capacity(num_nodes),
+            right: Vec::with_capacity(num_nodes),
+            up: Vec::with_capacity(num_nodes),
+            down: Vec::with_capacity(num_nodes),
+            column: Vec::with_capacity(num_nodes),
+            row: Vec::with_capacity(num_nodes),
+            size: vec![0; num_columns + 1],
+        };
+
+        for header in 0..=num_columns {
+            links.left.push(header);
+            links.right.push(header);
+            links.up.push(header);
+            links.down.push(header);
+            links.column.push(header);
+            links.row.push(usize::MAX);
+        }
+        for header in (1..=num_columns).filter(|header| !is_secondary(header - 1)) {
+            let last = links.left[ROOT];
+            links.right[last] = header;
+            links.left[header] = last;
+            links.right[header] = ROOT;
+            links.left[ROOT] = header;
+        }
+
+        for (row_index, columns) in rows.iter().enumerate() {
+            let first = links.column.len();
+            let last = first + columns.len() - 1;
+            for (i, &column) in columns.iter().enumerate() {
+                let node = first + i;
+                let header = column + 1;
+                let left = if node == first { last } else { node - 1 };
+                let right = if node == last { first } else { node + 1 };
+                links.left.push(left);
+                links.right.push(right);
+                links.up.push(links.up[header]);
+                links.down.push(header);
+                links.column.push(header);
+                links.row.push(row_index);
+
+                let bottom = links.up[header];
+                links.down[bottom] = node;
+                links.up[header] = node;
+                links.size[header] += 1;
+            }
+        }
+
+        links
+    }
+
+    /// Returns true if all columns are covered.
+    pub(crate) fn is_solved(&self) -> bool {
+        self.right[ROOT] == ROOT
+    }
+
+    /// Returns the header of the uncovered column with the fewest nodes or None if all columns
+    /// are covered.
+    pub(crate) fn choose_column(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        let mut header = self.right[ROOT];
+        while header != ROOT {
+            if best.is_none_or(|best| self.size[header] < self.size[best]) {
+                best = Some(header);
+                if self.size[header] == 0 {
+                    break;
+                }
+            }
+            header = self.right[header];
+        }
+        best
+    }
+
+    /// Returns the number of nodes left in the column of the given header.
+    pub(crate) fn size(&self, header: usize) -> usize {
+        self.size[header]
+    }
+
+    /// Returns the next node below the given node.
+    ///
+    /// Starting at a column header, this iterates all rows of the column and returns the header
+    /// again after the last row.
+    pub(crate) fn down(&self, node: usize) -> usize {
+        self.down[node]
+    }
+
+    /// Returns the index of the row the given node belongs to.
+    pub(crate) fn row(&self, node: usize) -> usize {
+        self.row[node]
+    }
+
+    /// Removes the column and all rows intersecting it from the matrix.
+    pub(crate) fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+
+        let mut row_node = self.down[header];
+        while row_node != header {
+            let mut node = self.right[row_node];
+            while node != row_node {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row_node = self.down[row_node];
+        }
+    }
+
+    /// Reverts `cover` for the column. Columns have to be uncovered in reverse order.
+    pub(crate) fn uncover(&mut self, header: usize) {
+        let mut row_node = self.up[header];
+        while row_node != header {
+            let mut node = self.left[row_node];
+            while node != row_node {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+            row_node = self.up[row_node];
+        }
+
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    /// Adds the row of the node to the partial solution by covering all other columns of the
+    /// row. The column of the node itself has to be covered already.
+    pub(crate) fn select(&mut self, row_node: usize) {
+        let mut node = self.right[row_node];
+        while node != row_node {
+            self.cover(self.column[node]);
+            node = self.right[node];
+        }
+    }
+
+    /// Reverts `select` for the row of the node.
+    pub(crate) fn deselect(&mut self, row_node: usize) {
+        let mut node = self.left[row_node];
+        while node != row_node {
+            self.uncover(self.column[node]);
+            node = self.left[node];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Knuth's example from the dancing links paper with the unique solution rows 0, 3 and 4.
+    fn knuth_example() -> DancingLinks {
+        DancingLinks::new(
+            7,
+            &[
+                vec![2, 4, 5],
+                vec![0, 3, 6],
+                vec![1, 2, 5],
+                vec![0, 3],
+                vec![1, 6],
+                vec![3, 4, 6],
+            ],
+        )
+    }
+
+    fn all_solutions(
+        links: &mut DancingLinks,
+        partial: &mut Vec<usize>,
+        out: &mut Vec<Vec<usize>>,
+    ) {
+        let Some(header) = links.choose_column() else {
+            let mut solution = partial.clone();
+            solution.sort();
+            out.push(solution);
+            return;
+        };
+        links.cover(header);
+        let mut node = links.down(header);
+        while node != header {
+            partial.push(links.row(node));
+            links.select(node);
+            all_solutions(links, partial, out);
+            links.deselect(node);
+            partial.pop();
+            node = links.down(node);
+        }
+        links.uncover(header);
+    }
+
+    #[test]
+    fn test_new() {
+        let links = knuth_example();
+        assert!(!links.is_solved());
+        assert_eq!(links.size(1), 2);
+        assert_eq!(links.size(7), 3);
+    }
+
+    #[test]
+    fn test_choose_column() {
+        let links = knuth_example();
+        assert_eq!(links.choose_column(), Some(1));
+    }
+
+    #[test]
+    fn test_choose_column_empty() {
+        let links = DancingLinks::new(0, &[]);
+        assert!(links.is_solved());
+        assert_eq!(links.choose_column(), None);
+    }
+
+    #[test]
+    fn test_cover_uncover() {
+        let mut links = knuth_example();
+        let before = (links.left.clone(), links.right.clone(), links.up.clone());
+        let before_down = links.down.clone();
+        let before_size = links.size.clone();
+
+        links.cover(1);
+        assert_eq!(links.size(4), 1);
+        assert_eq!(links.size(7), 2);
+        links.uncover(1);
+
+        assert_eq!(
+            (links.left.clone(), links.right.clone(), links.up.clone()),
+            before
+        );
+        assert_eq!(links.down, before_down);
+        assert_eq!(links.size, before_size);
+    }
+
+    #[test]
+    fn test_all_solutions() {
+        let mut links = knuth_example();
+        let mut solutions = Vec::new();
+        all_solutions(&mut links, &mut Vec::new(), &mut solutions);
+        assert_eq!(solutions, vec![vec![0, 3, 4]]);
+    }
+
+    #[test]
+    fn test_all_solutions_multiple() {
+        let mut links = DancingLinks::new(2, &[vec![0], vec![1], vec![0, 1], vec![0]]);
+        let mut solutions = Vec::new();
+        all_solutions(&mut links, &mut Vec::new(), &mut solutions);
+        solutions.sort();
+        assert_eq!(solutions, vec![vec![0, 1], vec![1, 3], vec![2]]);
+    }
+
+    #[test]
+    fn test_secondary_columns() {
+        let mut links =
+            DancingLinks::with_secondary_columns(3, &[vec![0, 2], vec![1], vec![1, 2]], |column| {
+                column == 2
+            });
+        assert_eq!(links.choose_column(), Some(1));
+
+        let mut solutions = Vec::new();
+        all_solutions(&mut links, &mut Vec::new(), &mut solutions);
+        solutions.sort();
+        assert_eq!(solutions, vec![vec![0, 1]]);
+
+        let links = DancingLinks::with_secondary_columns(1, &[vec![0]], |_| true);
+        assert!(links.is_solved());
+    }
+}
@@ -0,0 +1,350 @@
+use crate::backtracking::UNUSED;
+use crate::backtracking::positioned::PositionedTile;
+use crate::bitmask::Bitmask;
+use crate::branches::{self, BranchStop, Deepest};
+use crate::cancel::Cancellation;
+use crate::dlx::links::DancingLinks;
+use crate::options::{SolutionSelection, SolveMode};
+use crate::statistics::SearchCounters;
+use log::debug;
+use std::sync::Arc;
+
+mod links;
+
+/// Searches a placement for every tile, so that the board is filled completely, using
+/// Algorithm X.
+///
+/// Every tile and every empty cell of the board is a column of the exact cover problem, every
+/// placement of a tile is a row covering the tile and the cells it occupies.
+/// Depending on the mode, the tiles or the cells only have to be covered at most once.
+///
+/// returns: Result<Vec<usize>, Vec<usize>>: The index of the placement for each tile if a
+/// solution is found, otherwise the deepest partial placements reached.
+pub fn solve_filling<const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    mode: SolveMode,
+    selection: SolutionSelection,
+    counters: Arc<SearchCounters>,
+    cancel: &dyn Cancellation,
+) -> Result<Vec<usize>, Vec<usize>> {
+    let (links, shared) = prepare(board_bitmask, positioned_tiles, mode, counters, None);
+    if links.is_solved() {
+        return Ok(Vec::new());
+    }
+
+    let solvers = prepare_solvers(&links, &shared);
+    shared.counters.set_branches_total(solvers.len());
+
+    let result = branches::search_branches(solvers, selection, cancel, |mut solver, stop| {
+        solver.solve(&shared, stop)
+    });
+    debug!("Solver finished.");
+    result
+}
+
+/// Searches the whole search tree using Algorithm X and calls `on_solution` for every solution
+/// found.
+///
+/// The solutions are passed as the index of the placement for each tile.
+/// The order of the solutions is not defined and `on_solution` may be called from several
+/// threads.
+///
+/// returns: Result<(), Vec<usize>>: Ok if the whole search tree was searched, the deepest
+/// partial placements reached if it was cancelled.
+pub fn enumerate_filling<const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    mode: SolveMode,
+    counters: Arc<SearchCounters>,
+    on_solution: &(dyn Fn(Vec<usize>) + Sync),
+    cancel: &dyn Cancellation,
+) -> Result<(), Vec<usize>> {
+    let (links, shared) = prepare(
+        board_bitmask,
+        positioned_tiles,
+        mode,
+        counters,
+        Some(on_solution),
+    );
+    if links.is_solved() {
+        on_solution(Vec::new());
+        return Ok(());
+    }
+
+    let solvers = prepare_solvers(&links, &shared);
+    shared.counters.set_branches_total(solvers.len());
+
+    let result = branches::enumerate_branches(solvers, cancel, |mut solver, stop| {
+        solver.solve(&shared, stop).err().unwrap_or_default()
+    });
+    debug!("All solvers finished.");
+    result
+}
+
+/// Builds the exact cover matrix for the board and the placements of the tiles.
+///
+/// The first columns are the tiles, followed by the empty cells of the board.
+/// The tile columns are secondary, if not all tiles have to be used, and the cell columns are
+/// secondary, if not all cells have to be covered.
+fn prepare<'a, const N: usize>(
+    board_bitmask: &Bitmask<N>,
+    positioned_tiles: &[PositionedTile<N>],
+    mode: SolveMode,
+    counters: Arc<SearchCounters>,
+    on_solution: Option<&'a (dyn Fn(Vec<usize>) + Sync)>,
+) -> (DancingLinks, DlxShared<'a>) {
+    let num_tiles = positioned_tiles.len();
+    let mut cell_columns: Vec<Option<usize>> = vec![None; board_bitmask.relevant_bits()];
+    let mut num_columns = num_tiles;
+    for (bit, cell_column) in cell_columns.iter_mut().enumerate() {
+        if !board_bitmask.get_bit(bit) {
+            *cell_column = Some(num_columns);
+            num_columns += 1;
+        }
+    }
+
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    let mut placements: Vec<(usize, usize)> = Vec::new();
+    for (tile_index, positioned_tile) in positioned_tiles.iter().enumerate() {
+        for (placement_index, placement) in positioned_tile.bitmasks().iter().enumerate() {
+            if !board_bitmask.and_is_zero(placement) {
+                continue;
+            }
+            let mut columns = vec![tile_index];
+            columns.extend(
+                cell_columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| placement.get_bit(*bit))
+                    .filter_map(|(_, cell_column)| *cell_column),
+            );
+            rows.push(columns);
+            placements.push((tile_index, placement_index));
+        }
+    }
+
+    let previous_copies: Vec<Option<usize>> = positioned_tiles
+        .iter()
+        .map(|positioned_tile| positioned_tile.previous_copy())
+        .collect();
+    let has_copies = |tile_index: usize| {
+        previous_copies[tile_index].is_some() || previous_copies.contains(&Some(tile_index))
+    };
+    // If all tiles have to be used, the area of the tiles equals the area of the board. So
+    // tiles with copies are used, if all cells are covered, and their columns can be secondary.
+    let links = DancingLinks::with_secondary_columns(num_columns, &rows, |column| match mode {
+        SolveMode::FillWithAllTiles => column < num_tiles && has_copies(column),
+        SolveMode::FillWithSubset => column < num_tiles,
+        SolveMode::PlaceAllTiles => column >= num_tiles,
+    });
+    let shared = DlxShared {
+        num_tiles,
+        placements,
+        previous_copies,
+        counters,
+        on_solution,
+    };
+    (links, shared)
+}
+
+/// Splits the search into one solver for each row of the most constrained column, so the
+/// solvers can run in parallel.
+fn prepare_solvers(links: &DancingLinks, shared: &DlxShared) -> Vec<DlxSolver> {
+    let no_placements = vec![UNUSED; shared.num_tiles];
+    let Some(header) = shared.choose_column(links, &no_placements) else {
+        return Vec::new();
+    };
+    let mut solvers = Vec::with_capacity(links.size(header));
+
+    let mut node = links.down(header);
+    while node != header {
+        let (tile_index, placement_index) = shared.placements[links.row(node)];
+        if !shared.is_allowed(header, tile_index, placement_index, &no_placements) {
+            node = links.down(node);
+            continue;
+        }
+        let mut solver_links = links.clone();
+        solver_links.cover(header);
+        solver_links.select(node);
+        let mut tile_placements = no_placements.clone();
+        tile_placements[tile_index] = placement_index;
+        solvers.push(DlxSolver {
+            links: solver_links,
+            tile_placements,
+            placed_tiles: 1,
+            stop_counter: 0,
+            stopped: false,
+            deepest: Deepest::default(),
+            nodes_visited: 0,
+        });
+        node = links.down(node);
+    }
+
+    solvers
+}
+
+/// Shared data for the DlxSolver.
+struct DlxShared<'a> {
+    num_tiles: usize,
+    /// The (tile index, placement index) of each row.
+    placements: Vec<(usize, usize)>,
+    /// For each tile, the index of the previous identical tile.
+    previous_copies: Vec<Option<usize>>,
+    counters: Arc<SearchCounters>,
+    /// If set, all solutions are passed to this function and the search continues after a
+    /// solution was found.
+    on_solution: Option<&'a (dyn Fn(Vec<usize>) + Sync)>,
+}
+
+impl DlxShared<'_> {
+    /// Chooses the column with the fewest remaining rows.
+    ///
+    /// If it is the column of a tile with unused identical tiles before it, the column of the
+    /// first unused copy is chosen instead, which has the same rows.
+    fn choose_column(&self, links: &DancingLinks, tile_placements: &[usize]) -> Option<usize> {
+        let mut header = links.choose_column()?;
+        // The headers of the tile columns are 1..=num_tiles
+        while header <= self.num_tiles
+            && let Some(previous_copy) = self.previous_copies[header - 1]
+            && tile_placements[previous_copy] == UNUSED
+        {
+            header = previous_copy + 1;
+        }
+        Some(header)
+    }
+
+    /// Returns true if the row of the placement may be selected, when branching on the column
+    /// of the given header.
+    ///
+    /// Identical tiles are interchangeable, so a placement is only selected for the first
+    /// unused copy. When branching on the column of a copy, its placement has to come after the
+    /// one of the previous copy. This way every solution is only found once.
+    fn is_allowed(
+        &self,
+        header: usize,
+        tile_index: usize,
+        placement_index: usize,
+        tile_placements: &[usize],
+    ) -> bool {
+        match self.previous_copies[tile_index] {
+            None => true,
+            Some(previous_copy) => {
+                tile_placements[previous_copy] != UNUSED
+                    && (header > self.num_tiles || tile_placements[previous_copy] < placement_index)
+            }
+        }
+    }
+}
+
+/// Solver for filling the board with all tiles using Algorithm X.
+struct DlxSolver {
+    links: DancingLinks,
+    /// The index of the selected placement for each tile, or UNUSED.
+    tile_placements: Vec<usize>,
+    /// The number of selected rows, each places one tile.
+    placed_tiles: usize,
+    stop_counter: u32,
+    /// Set when the branch should stop, so the search returns without visiting more nodes.
+    stopped: bool,
+    deepest: Deepest,
+    /// The nodes visited since the statistics were last added to the shared counters.
+    nodes_visited: u64,
+}
+
+impl DlxSolver {
+    /// The entry point for the DlxSolver to start solving the puzzle.
+    ///
+    /// This function will only return, if a solution is found, or it is proven that no solution
+    /// exists.
+    /// When enumerating all solutions, it only returns after the whole search tree was searched.
+    ///
+    /// returns: Result<Vec<usize>, Deepest>: The placements if a solution is found, the deepest
+    /// placements reached otherwise.
+    fn solve(&mut self, shared: &DlxShared, stop: &BranchStop) -> Result<Vec<usize>, Deepest> {
+        let solved = self.search(shared, stop);
+        self.flush_statistics(shared);
+        if !solved && !stop.should_stop() {
+            shared.counters.branch_exhausted();
+        }
+        if solved {
+            Ok(self.tile_placements.clone())
+        } else {
+            Err(std::mem::take(&mut self.deepest))
+        }
+    }
+
+    /// The recursive search of Algorithm X.
+    ///
+    /// Covers the column with the fewest remaining rows and tries each of its rows in turn.
+    /// A column without rows means that a cell can not be filled or a tile can not be placed
+    /// anymore, so the search backtracks.
+    ///
+    /// From time to time it checks, whether the branch should stop.
+    ///
+    /// returns: bool: true if a solution was found.
+    fn search(&mut self, shared: &DlxShared, stop: &BranchStop) -> bool {
+        if self.stopped {
+            return false;
+        }
+        self.stop_counter += 1;
+        self.nodes_visited += 1;
+        if self.stop_counter & 0xff == 0 {
+            self.flush_statistics(shared);
+            if stop.should_stop() {
+                self.stopped = true;
+                return false;
+            }
+        }
+        self.deepest
+            .update(self.placed_tiles, || self.tile_placements.clone());
+
+        let Some(header) = shared.choose_column(&self.links, &self.tile_placements) else {
+            return self.submit_solution(shared);
+        };
+        if self.links.size(header) == 0 {
+            return false;
+        }
+
+        self.links.cover(header);
+        let mut node = self.links.down(header);
+        while node != header {
+            let (tile_index, placement_index) = shared.placements[self.links.row(node)];
+            if shared.is_allowed(header, tile_index, placement_index, &self.tile_placements) {
+                self.tile_placements[tile_index] = placement_index;
+                self.links.select(node);
+                self.placed_tiles += 1;
+                if self.search(shared, stop) {
+                    return true;
+                }
+                self.placed_tiles -= 1;
+                self.links.deselect(node);
+                self.tile_placements[tile_index] = UNUSED;
+            }
+            node = self.links.down(node);
+        }
+        self.links.uncover(header);
+
+        false
+    }
+
+    /// Adds the locally counted statistics to the shared counters.
+    fn flush_statistics(&mut self, shared: &DlxShared) {
+        shared.counters.add_nodes_visited(self.nodes_visited);
+        self.nodes_visited = 0;
+    }
+
+    /// Handles a complete cover of the board.
+    ///
+    /// When enumerating all solutions, the solution is passed to `on_solution` and false is
+    /// returned to continue the search. Otherwise, it returns true.
+    fn submit_solution(&self, shared: &DlxShared) -> bool {
+        let placements = self.tile_placements.clone();
+        debug!("Solution found with tile placements: {:?}", placements);
+        if let Some(on_solution) = shared.on_solution {
+            on_solution(placements);
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,230 @@
+use crate::array_util::place_on_all_positions;
+use crate::board::Board;
+use crate::result::{Solution, TilePlacement};
+use crate::tile::Tile;
+use ndarray::Array2;
+
+/// Which placement of a solution is suggested as the next move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HintPolicy {
+    /// The placement covering the empty cell with the fewest fitting placements.
+    ///
+    /// This cell lies in the most constrained region of the board, so it is usually the move a
+    /// player has to figure out next.
+    #[default]
+    MostConstrained,
+    /// The placement of the largest tile.
+    LargestTile,
+    /// The placement of the tile with the given index in the tiles passed to the solver, e.g.
+    /// the tile the player is hovering.
+    ///
+    /// If the tile is not part of the solution, the most constrained placement is suggested.
+    Tile(usize),
+}
+
+/// Why the placement of a hint was chosen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintExplanation {
+    /// The placement covers the empty cell with the fewest fitting placements.
+    MostConstrainedCell {
+        /// The (x, y) position of the cell on the board.
+        cell: (usize, usize),
+        /// The number of placements of any tile, which fit on the board and cover the cell.
+        candidates: usize,
+    },
+    /// The placement is the one of the largest tile.
+    LargestTile {
+        /// The number of cells of the tile.
+        area: usize,
+    },
+    /// The placement is the one of the requested tile.
+    RequestedTile,
+}
+
+/// A single placement of a solution, which is suggested as the next move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    placement: TilePlacement,
+    explanation: HintExplanation,
+}
+
+impl Hint {
+    /// Returns the suggested placement.
+    pub fn placement(&self) -> &TilePlacement {
+        &self.placement
+    }
+
+    /// Returns why the placement was chosen.
+    pub fn explanation(&self) -> &HintExplanation {
+        &self.explanation
+    }
+}
+
+/// Chooses the placement of a solution, which is suggested as the next move.
+///
+/// # Arguments
+///
+/// * `board`: The board the solution was searched for.
+/// * `tiles`: The tiles the solution was searched for.
+/// * `solution`: The solution to choose the placement from.
+/// * `policy`: How the placement is chosen.
+///
+/// returns: Option<Hint>: None, if the solution has no placements.
+pub(crate) fn choose_hint(
+    board: &Board,
+    tiles: &[Tile],
+    solution: &Solution,
+    policy: HintPolicy,
+) -> Option<Hint> {
+    let placements = solution.placements();
+    let hint = |placement: &TilePlacement, explanation| Hint {
+        placement: placement.clone(),
+        explanation,
+    };
+
+    match policy {
+        HintPolicy::LargestTile => placements
+            .iter()
+            .max_by_key(|placement| (area(placement.base()), usize::MAX - placement.tile_index()))
+            .map(|placement| {
+                let area = area(placement.base());
+                hint(placement, HintExplanation::LargestTile { area })
+            }),
+        HintPolicy::Tile(tile_index) => placements
+            .iter()
+            .find(|placement| placement.tile_index() == tile_index)
+            .map(|placement| hint(placement, HintExplanation::RequestedTile))
+            .or_else(|| choose_hint(board, tiles, solution, HintPolicy::MostConstrained)),
+        HintPolicy::MostConstrained => {
+            let candidates = count_candidates(board, tiles);
+            placements
+                .iter()
+                .flat_map(|placement| covered_cells(placement).map(move |cell| (cell, placement)))
+                .min_by_key(|&((x, y), _)| (candidates[[x, y]], x, y))
+                .map(|(cell, placement)| {
+                    let candidates = candidates[[cell.0, cell.1]];
+                    hint(
+                        placement,
+                        HintExplanation::MostConstrainedCell { cell, candidates },
+                    )
+                })
+        }
+    }
+}
+
+/// Counts for every cell of the board the placements of any tile covering it, which fit on the
+/// empty cells.
+///
+/// Identical orientations of different tiles are only counted once.
+fn count_candidates(board: &Board, tiles: &[Tile]) -> Array2<usize> {
+    let mut orientations: Vec<&Array2<bool>> = Vec::new();
+    for orientation in tiles.iter().flat_map(|tile| tile.all_rotations.iter()) {
+        if !orientations.contains(&orientation) {
+            orientations.push(orientation);
+        }
+    }
+
+    let array = board.get_array();
+    let mut candidates = Array2::zeros(array.dim());
+    for orientation in orientations {
+        for placed in place_on_all_positions(array, orientation) {
+            for ((cell, &filled), &was_filled) in placed.indexed_iter().zip(array.iter()) {
+                if filled && !was_filled {
+                    candidates[cell] += 1;
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// Returns the (x, y) positions of the cells covered by the placement.
+fn covered_cells(placement: &TilePlacement) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let (x, y) = placement.position();
+    placement
+        .rotation()
+        .indexed_iter()
+        .filter(|(_, cell)| **cell)
+        .map(move |((dx, dy), _)| (x + dx, y + dy))
+}
+
+fn area(base: &Array2<bool>) -> usize {
+    base.iter().filter(|&&cell| cell).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Transform;
+    use ndarray::arr2;
+
+    /// A 3x2 board with the cells (x, 0) covered by an I tromino and the other cells by a domino
+    /// and a monomino.
+    fn solved() -> (Board, Vec<Tile>, Solution) {
+        let board = Board::new((3, 2));
+        let tiles = vec![
+            Tile::new(arr2(&[[true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+        let vertical = Transform::new(false, 1);
+        let solution = Solution::new(vec![
+            TilePlacement::placed(1, arr2(&[[true, true, true]]), vertical, (0, 0)),
+            TilePlacement::placed(2, arr2(&[[true, true]]), vertical, (0, 1)),
+            TilePlacement::placed(0, arr2(&[[true]]), Transform::default(), (2, 1)),
+        ]);
+        (board, tiles, solution)
+    }
+
+    #[test]
+    fn test_largest_tile() {
+        let (board, tiles, solution) = solved();
+        let hint = choose_hint(&board, &tiles, &solution, HintPolicy::LargestTile).unwrap();
+        assert_eq!(hint.placement().tile_index(), 1);
+        assert_eq!(
+            hint.explanation(),
+            &HintExplanation::LargestTile { area: 3 }
+        );
+    }
+
+    #[test]
+    fn test_requested_tile() {
+        let (board, tiles, solution) = solved();
+        let hint = choose_hint(&board, &tiles, &solution, HintPolicy::Tile(2)).unwrap();
+        assert_eq!(hint.placement().tile_index(), 2);
+        assert_eq!(hint.explanation(), &HintExplanation::RequestedTile);
+
+        let hint = choose_hint(&board, &tiles, &solution, HintPolicy::Tile(5)).unwrap();
+        assert!(matches!(
+            hint.explanation(),
+            HintExplanation::MostConstrainedCell { .. }
+        ));
+    }
+
+    #[test]
+    fn test_most_constrained() {
+        let (board, tiles, solution) = solved();
+        let hint = choose_hint(&board, &tiles, &solution, HintPolicy::MostConstrained).unwrap();
+        // A corner is covered by the monomino, both domino orientations and one tromino
+        // orientation.
+        assert_eq!(
+            hint.explanation(),
+            &HintExplanation::MostConstrainedCell {
+                cell: (0, 0),
+                candidates: 4
+            }
+        );
+        assert_eq!(hint.placement().tile_index(), 1);
+    }
+
+    #[test]
+    fn test_empty_solution() {
+        let hint = choose_hint(
+            &Board::new((1, 1)),
+            &[],
+            &Solution::new(Vec::new()),
+            HintPolicy::default(),
+        );
+        assert!(hint.is_none());
+    }
+}
@@ -1,34 +1,69 @@
-use crate::bitmask::Bitmask;
+#[cfg(feature = "tokio")]
+use crate::blocking::lock_placements;
+#[cfg(feature = "tokio")]
 use crate::board::Board;
-use crate::plausibility::check;
-use crate::result::{Solution, TilePlacement, UnsolvableReason};
+#[cfg(feature = "tokio")]
+use crate::hint::{Hint, HintPolicy};
+#[cfg(feature = "tokio")]
+use crate::options::SolverOptions;
+#[cfg(feature = "tokio")]
+use crate::result::{
+    Solution, SolutionCount, SolverStatistics, TilePlacement, Uniqueness, UnsolvableReason,
+};
+#[cfg(feature = "tokio")]
+use crate::statistics::{SearchCounters, report_progress};
+#[cfg(feature = "tokio")]
 use crate::tile::Tile;
-use log::debug;
+#[cfg(feature = "tokio")]
+use std::sync::Arc;
+#[cfg(feature = "tokio")]
+use tokio::sync::mpsc::UnboundedSender;
+#[cfg(feature = "tokio")]
 use tokio_util::sync::CancellationToken;
 
 mod array_util;
+pub mod ascii;
 mod backtracking;
 mod bitmask;
+pub mod blocking;
 pub mod board;
+mod branches;
+pub mod cancel;
+mod dlx;
+pub mod hint;
+pub mod options;
 mod plausibility;
 pub mod result;
+#[cfg(feature = "serde")]
+mod serialization;
+mod statistics;
+mod symmetry;
 pub mod tile;
 
 /// Tries to place all given tiles on the board, filling it completely.
 /// If successful, returns a Solution; otherwise, returns an UnsolvableReason.
-/// TODO the solution currently does not contain the placements.
 /// A successful result is reached, if all tiles were placed on the board without overlapping
 /// and all empty cells on the board are covered.
+/// With a different `SolveMode` in the options, only a subset of the tiles has to be used or
+/// not all empty cells have to be covered. Unused tiles are not part of the solution.
+/// If the puzzle has more than one solution, the `SolutionSelection` in the options decides
+/// which one is returned and whether it is the same on every run.
 ///
 /// The cancellation token can be used to cancel the operation.
 /// The operation may be cancelled at any time, in which case it will return
 /// after some time. It may still be successful if it was close to finishing.
-/// It may also return an error if it was cancelled before it could find a solution.
+/// It may also return `UnsolvableReason::Cancelled` if it was cancelled before it could find a
+/// solution.
+/// If a limit of the `SearchLimits` in the options is reached before a solution is found,
+/// `UnsolvableReason::LimitReached` is returned with the deepest partial solution reached.
+/// The board may have at most `board::MAX_BOARD_CELLS` cells, otherwise
+/// `UnsolvableReason::BoardTooLarge` is returned.
 ///
 /// # Arguments
 ///
 /// * `board`: The board to place the tiles on to fill it completely.
 /// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
 /// * `cancel_token`: A cancellation token to cancel the operation.
 ///
 /// returns: Result<Solution, UnsolvableReason>
@@ -38,6 +73,7 @@ pub mod tile;
 /// ```
 /// use ndarray::arr2;
 /// use puzzle_solver::board::Board;
+/// use puzzle_solver::options::SolverOptions;
 /// use puzzle_solver::tile::Tile;
 /// use puzzle_solver::solve_all_filling;
 /// use tokio_util::sync::CancellationToken;
@@ -48,57 +84,549 @@ pub mod tile;
 ///     Tile::new(arr2(&[[true, true, true], [true, true, true]])),
 ///     Tile::new(arr2(&[[true, true, true], [true, true, false]])),
 /// ];
+///
+/// let options = SolverOptions::default();
 /// let cancel_token = CancellationToken::new();
 ///
-/// let result = tokio::runtime::Runtime::new().unwrap().block_on(solve_all_filling(board, &tiles, cancel_token));
+/// let result = tokio::runtime::Runtime::new().unwrap().block_on(solve_all_filling(board, &tiles, &options, cancel_token));
 /// assert!(result.is_ok());
 /// ```
+#[cfg(feature = "tokio")]
 pub async fn solve_all_filling(
     board: Board,
     tiles: &[Tile],
+    options: &SolverOptions,
     cancel_token: CancellationToken,
 ) -> Result<Solution, UnsolvableReason> {
-    if !check(&board, &tiles) {
-        debug!("Plausibility check failed.");
-        return Err(UnsolvableReason::NoFit);
-    }
+    let (result, _) = solve_all_filling_with_statistics(board, tiles, options, cancel_token).await;
+    result
+}
 
-    let mut board = board;
-    let trim_sides = board.trim();
+/// Same as `solve_all_filling`, but additionally returns the statistics of the search.
+///
+/// The statistics contain the nodes visited, the prunes, the progress through the first level
+/// of the search tree and the elapsed time.
+/// If a progress sender is set in the options, the statistics are also sent to it periodically
+/// while the solver is running.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: (Result<Solution, UnsolvableReason>, SolverStatistics)
+#[cfg(feature = "tokio")]
+pub async fn solve_all_filling_with_statistics(
+    board: Board,
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> (Result<Solution, UnsolvableReason>, SolverStatistics) {
+    let tiles = tiles.to_vec();
+    let options_clone = options.clone();
+    run_blocking(options, move |counters| {
+        let result = blocking::solve_all_filling_counted(
+            board,
+            &tiles,
+            &options_clone,
+            counters.clone(),
+            &cancel_token,
+        );
+        (result, counters.snapshot())
+    })
+    .await
+}
 
-    if board.get_array().iter().filter(|c| !*c).count() > Bitmask::max_bits() {
-        debug!("Board too large for bitmask representation.");
-        return Err(UnsolvableReason::BoardTooLarge);
-    }
+/// Same as `solve_all_filling`, but some tiles are already placed on the board and can not be
+/// moved anymore.
+///
+/// The cells covered by the locked placements are filled before the search is started, so only
+/// the free tiles are placed. The solution only contains the placements of the free tiles and
+/// their tile indices refer to the given `tiles`.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `locked`: The placements of the tiles, which are already placed on the board.
+/// * `tiles`: The free tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Result<Solution, UnsolvableReason>: `UnsolvableReason::InvalidLockedPlacement` if
+/// a locked placement lies outside the board or overlaps a filled cell.
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::result::TilePlacement;
+/// use puzzle_solver::tile::{Tile, Transform};
+/// use puzzle_solver::solve_with_locked;
+/// use tokio_util::sync::CancellationToken;
+///
+/// let board = Board::new((2, 3));
+/// let locked = vec![TilePlacement::placed(0, arr2(&[[true, true, true]]), Transform::default(), (0, 0))];
+/// let tiles = vec![Tile::new(arr2(&[[true], [true], [true]]))];
+///
+/// let options = SolverOptions::default();
+/// let cancel_token = CancellationToken::new();
+///
+/// let result = tokio::runtime::Runtime::new().unwrap().block_on(solve_with_locked(board, &locked, &tiles, &options, cancel_token));
+/// let solution = result.unwrap();
+/// assert_eq!(solution.placements().len(), 1);
+/// assert_eq!(solution.placements()[0].position(), (1, 0));
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn solve_with_locked(
+    board: Board,
+    locked: &[TilePlacement],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<Solution, UnsolvableReason> {
+    let board = lock_placements(board, locked, None)?;
+    solve_all_filling(board, tiles, options, cancel_token).await
+}
 
-    let result = backtracking::solve_all_filling(board, tiles, cancel_token).await;
-    match &result {
-        Ok(solution) => {
-            let trim_adjusted_placements: Vec<TilePlacement> = solution
-                .placements()
-                .iter()
-                .map(|placement| {
-                    let (x, y) = placement.position();
-                    let (trimmed_x, trimmed_y) = (x + trim_sides.lower_x, y + trim_sides.lower_y);
-                    TilePlacement::new(
-                        placement.base().clone(),
-                        placement.rotation().clone(),
-                        (trimmed_x, trimmed_y),
-                    )
-                })
-                .collect();
-            Ok(Solution::new(trim_adjusted_placements))
-        }
-        Err(_) => result,
-    }
+/// Searches a solution and returns one of its placements as a hint for the next move.
+///
+/// The `HintPolicy` decides which placement of the solution is suggested, so the hints do not
+/// depend on the order the solver places the tiles in. The hint explains, why its placement
+/// was chosen. With `SolutionSelection::FirstInOrder` in the options, the same puzzle always
+/// gets the same hint.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `policy`: Which placement of the solution is suggested.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Result<Option<Hint>, UnsolvableReason>: None, if the board is already filled, or
+/// the reason why no solution was found.
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::find_hint;
+/// use puzzle_solver::hint::{HintExplanation, HintPolicy};
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::tile::Tile;
+/// use tokio_util::sync::CancellationToken;
+///
+/// let board = Board::new((3, 2));
+/// let tiles = vec![
+///     Tile::new(arr2(&[[true, true, true]])),
+///     Tile::new(arr2(&[[true, true]])),
+///     Tile::new(arr2(&[[true]])),
+/// ];
+///
+/// let options = SolverOptions::default();
+/// let cancel_token = CancellationToken::new();
+///
+/// let result = tokio::runtime::Runtime::new().unwrap().block_on(find_hint(board, &tiles, HintPolicy::LargestTile, &options, cancel_token));
+/// let hint = result.unwrap().unwrap();
+/// assert_eq!(hint.placement().tile_index(), 0);
+/// assert_eq!(hint.explanation(), &HintExplanation::LargestTile { area: 3 });
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn find_hint(
+    board: Board,
+    tiles: &[Tile],
+    policy: HintPolicy,
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<Option<Hint>, UnsolvableReason> {
+    let solution = solve_all_filling(board.clone(), tiles, options, cancel_token).await?;
+    Ok(hint::choose_hint(&board, tiles, &solution, policy))
+}
+
+/// Searches a locked placement, which prevents the puzzle from being solved.
+///
+/// First the puzzle is solved with all locked placements. If this fails, the locked placements
+/// are freed one after another and the puzzle is solved again with the freed tile added to the
+/// free tiles. The first placement, whose removal makes the puzzle solvable, is returned.
+/// A freed tile is taken from `locked_tiles`, so it may only be placed in its allowed
+/// orientations.
+///
+/// This runs one search per locked placement in the worst case, so it is much slower than
+/// `solve_with_locked`.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `locked`: The placements of the tiles, which are already placed on the board.
+/// * `locked_tiles`: The tiles of the locked placements, indexed by their tile index.
+/// * `tiles`: The free tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Result<Option<usize>, UnsolvableReason>: The index of the wrong placement in
+/// `locked`, None if the puzzle is solvable with all locked placements, or the reason why the
+/// puzzle is unsolvable if removing a single placement does not help.
+/// `UnsolvableReason::InvalidLockedPlacement` if the tile index of a locked placement is not
+/// an index of `locked_tiles`.
+#[cfg(feature = "tokio")]
+pub async fn find_wrong_placement(
+    board: Board,
+    locked: &[TilePlacement],
+    locked_tiles: &[Tile],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<Option<usize>, UnsolvableReason> {
+    let locked = locked.to_vec();
+    let locked_tiles = locked_tiles.to_vec();
+    let tiles = tiles.to_vec();
+    let options_clone = options.clone();
+    run_blocking(options, move |_| {
+        blocking::find_wrong_placement(
+            board,
+            &locked,
+            &locked_tiles,
+            &tiles,
+            &options_clone,
+            &cancel_token,
+        )
+    })
+    .await
+}
+
+/// Counts all solutions for placing all given tiles on the board, filling it completely.
+///
+/// In contrast to `solve_all_filling`, the whole search tree is searched.
+/// The solutions are counted in total and without duplicates, which are equal up to a rotation
+/// or mirroring of the board or swapping identical tiles.
+///
+/// If the operation is cancelled before the whole search tree is searched,
+/// `UnsolvableReason::Cancelled` is returned. If a limit of the `SearchLimits` in the options
+/// is reached first, `UnsolvableReason::LimitReached` is returned.
+/// The board may have at most `board::MAX_BOARD_CELLS` cells, otherwise
+/// `UnsolvableReason::BoardTooLarge` is returned.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Result<SolutionCount, UnsolvableReason>
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::tile::Tile;
+/// use puzzle_solver::count_all_solutions;
+/// use tokio_util::sync::CancellationToken;
+///
+/// let board = Board::new((2, 3));
+/// let tiles = vec![
+///     Tile::new(arr2(&[[true, true]])),
+///     Tile::new(arr2(&[[true, true]])),
+///     Tile::new(arr2(&[[true, true]])),
+/// ];
+///
+/// let options = SolverOptions::default();
+/// let cancel_token = CancellationToken::new();
+///
+/// let result = tokio::runtime::Runtime::new().unwrap().block_on(count_all_solutions(board, &tiles, &options, cancel_token));
+/// assert_eq!(result.unwrap().unique(), 2);
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn count_all_solutions(
+    board: Board,
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<SolutionCount, UnsolvableReason> {
+    enumerate_all_filling(board, tiles, |_, _| {}, options, cancel_token).await
+}
+
+/// Searches all solutions for placing all given tiles on the board, filling it completely.
+///
+/// Every solution is sent to the given sender as soon as it is found.
+/// This includes solutions, which are equal up to a rotation or mirroring of the board.
+/// Identical tiles are interchangeable, so solutions which only differ by swapping them are
+/// only sent once.
+/// When the whole search tree is searched, the number of solutions is returned.
+///
+/// If the operation is cancelled before the whole search tree is searched,
+/// `UnsolvableReason::Cancelled` is returned. The solutions found until then are still sent.
+/// The board may have at most `board::MAX_BOARD_CELLS` cells, otherwise
+/// `UnsolvableReason::BoardTooLarge` is returned.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `solution_sender`: The sender to send the solutions to.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Result<SolutionCount, UnsolvableReason>
+#[cfg(feature = "tokio")]
+pub async fn enumerate_solutions(
+    board: Board,
+    tiles: &[Tile],
+    solution_sender: UnboundedSender<Solution>,
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<SolutionCount, UnsolvableReason> {
+    enumerate_all_filling(
+        board,
+        tiles,
+        move |solution, _| {
+            let _ = solution_sender.send(solution);
+        },
+        options,
+        cancel_token,
+    )
+    .await
 }
 
-#[cfg(test)]
+/// Counts the solutions for placing all given tiles on the board for each of the given targets.
+///
+/// A target is a list of cells, which are filled on the board in addition to its filled cells,
+/// like the date of a Puzzle-a-Day. Every target is solved like `count_all_solutions` with its
+/// cells filled, but the placements of the tiles and the banned patterns of the pruner are
+/// only computed once for the board and reused for all targets.
+/// Cells of a target, which are not on the board, are ignored.
+///
+/// If a progress sender is set in the options, the progress reports how many targets are done.
+/// If the operation is cancelled, the remaining targets are not searched and
+/// `UnsolvableReason::Cancelled` is returned for them.
+/// The `SearchLimits` in the options apply to each target separately.
+/// The board may have at most `board::MAX_BOARD_CELLS` cells, otherwise
+/// `UnsolvableReason::BoardTooLarge` is returned for every target.
+///
+/// # Arguments
+///
+/// * `board`: The board shared by all targets.
+/// * `targets`: The cells to fill for each target as (x, y) positions on the board.
+/// * `tiles`: The tiles to place on the board.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Vec<Result<SolutionCount, UnsolvableReason>>: The result for each target in the
+/// order of the targets.
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::tile::Tile;
+/// use puzzle_solver::count_solutions_for_targets;
+/// use tokio_util::sync::CancellationToken;
+///
+/// let board = Board::new((2, 3));
+/// let targets = vec![vec![(0, 0)], vec![(0, 1)]];
+/// let tiles = vec![
+///     Tile::new(arr2(&[[true, true]])),
+///     Tile::new(arr2(&[[true, true], [true, false]])),
+/// ];
+///
+/// let options = SolverOptions::default();
+/// let cancel_token = CancellationToken::new();
+///
+/// let results = tokio::runtime::Runtime::new().unwrap().block_on(count_solutions_for_targets(board, &targets, &tiles, &options, cancel_token));
+/// assert_eq!(results[0].as_ref().unwrap().total(), 2);
+/// assert_eq!(results[1].as_ref().unwrap().total(), 2);
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn count_solutions_for_targets(
+    board: Board,
+    targets: &[Vec<(usize, usize)>],
+    tiles: &[Tile],
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Vec<Result<SolutionCount, UnsolvableReason>> {
+    let targets = targets.to_vec();
+    let tiles = tiles.to_vec();
+    let options_clone = options.clone();
+    run_blocking(options, move |counters| {
+        blocking::count_solutions_for_targets_counted(
+            board,
+            &targets,
+            &tiles,
+            &options_clone,
+            counters,
+            &cancel_token,
+        )
+    })
+    .await
+}
+
+/// Checks whether placing all given tiles on the board, filling it completely, has exactly one
+/// solution.
+///
+/// The search stops as soon as a second distinct solution is found, so this is usually much
+/// faster than `count_all_solutions` for puzzles with many solutions. A unique puzzle still
+/// requires searching the whole search tree.
+/// Solutions which only differ by swapping identical tiles are never distinct. If
+/// `identify_symmetric` is true, solutions which are equal up to a rotation or mirroring of the
+/// board are not distinct either.
+///
+/// If the operation is cancelled before the search is finished, `UnsolvableReason::Cancelled`
+/// is returned.
+/// The board may have at most `board::MAX_BOARD_CELLS` cells, otherwise
+/// `UnsolvableReason::BoardTooLarge` is returned.
+///
+/// # Arguments
+///
+/// * `board`: The board to place the tiles on to fill it completely.
+/// * `tiles`: The tiles to place on the board.
+/// * `identify_symmetric`: Whether symmetric solutions are treated as identical.
+/// * `options`: The options to configure the search, e.g. the strategy used.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Result<Uniqueness, UnsolvableReason>: `Uniqueness::None` if the whole search tree
+/// was searched without finding a solution, or the reason why the plausibility check failed.
+///
+/// # Examples
+///
+/// ```
+/// use ndarray::arr2;
+/// use puzzle_solver::board::Board;
+/// use puzzle_solver::options::SolverOptions;
+/// use puzzle_solver::result::Uniqueness;
+/// use puzzle_solver::tile::Tile;
+/// use puzzle_solver::check_uniqueness;
+/// use tokio_util::sync::CancellationToken;
+///
+/// let board = Board::new((2, 3));
+/// let tiles = vec![
+///     Tile::new(arr2(&[[true, true, true]])),
+///     Tile::new(arr2(&[[true, true, true]])),
+/// ];
+///
+/// let options = SolverOptions::default();
+/// let cancel_token = CancellationToken::new();
+///
+/// let result = tokio::runtime::Runtime::new().unwrap().block_on(check_uniqueness(board, &tiles, false, &options, cancel_token));
+/// assert!(matches!(result, Ok(Uniqueness::Unique(_))));
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn check_uniqueness(
+    board: Board,
+    tiles: &[Tile],
+    identify_symmetric: bool,
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<Uniqueness, UnsolvableReason> {
+    let tiles = tiles.to_vec();
+    let options_clone = options.clone();
+    run_blocking(options, move |counters| {
+        blocking::check_uniqueness_counted(
+            board,
+            &tiles,
+            identify_symmetric,
+            &options_clone,
+            counters,
+            &cancel_token,
+        )
+    })
+    .await
+}
+
+/// Runs the search on the blocking threads of the tokio runtime and reports its progress to
+/// the progress sender of the options while waiting.
+///
+/// A panic of the search is resumed on the awaiting task.
+///
+/// # Arguments
+///
+/// * `options`: The options with the progress sender.
+/// * `search`: The blocking search, which gets the counters to report the progress of.
+///
+/// returns: The output of the search.
+#[cfg(feature = "tokio")]
+async fn run_blocking<T, F>(options: &SolverOptions, search: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce(Arc<SearchCounters>) -> T + Send + 'static,
+{
+    let counters = Arc::new(SearchCounters::new());
+    let task = tokio::task::spawn_blocking({
+        let counters = counters.clone();
+        move || search(counters)
+    });
+    let result = report_progress(task, &counters, options.progress_sender.as_ref()).await;
+    result.unwrap_or_else(|error| std::panic::resume_unwind(error.into_panic()))
+}
+
+#[cfg(feature = "tokio")]
+async fn enumerate_all_filling<F>(
+    board: Board,
+    tiles: &[Tile],
+    on_solution: F,
+    options: &SolverOptions,
+    cancel_token: CancellationToken,
+) -> Result<SolutionCount, UnsolvableReason>
+where
+    F: FnMut(Solution, bool) + Send + 'static,
+{
+    let tiles = tiles.to_vec();
+    let options_clone = options.clone();
+    run_blocking(options, move |counters| {
+        blocking::enumerate_all_filling(
+            board,
+            &tiles,
+            on_solution,
+            &options_clone,
+            counters,
+            &cancel_token,
+        )
+    })
+    .await
+}
+#[cfg(all(test, feature = "tokio"))]
 mod tests {
     use super::*;
-    use ndarray::arr2;
+    use crate::options::{
+        PrunerStages, SearchLimits, SolutionSelection, SolveMode, SolverStrategy,
+    };
+    use crate::tile::{AllowedOrientations, Transform};
+    use ndarray::{Array2, arr2};
+    use std::time::Duration;
     use tokio_util::sync::CancellationToken;
 
+    const ALL_STRATEGIES: [SolverStrategy; 4] = [
+        SolverStrategy::Backtracking,
+        SolverStrategy::DancingLinks,
+        SolverStrategy::FirstEmptyCell,
+        SolverStrategy::MostConstrainedCell,
+    ];
+
+    fn all_strategies() -> Vec<SolverOptions> {
+        ALL_STRATEGIES
+            .iter()
+            .map(|&strategy| SolverOptions {
+                strategy,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn dancing_links() -> SolverOptions {
+        SolverOptions {
+            strategy: SolverStrategy::DancingLinks,
+            ..Default::default()
+        }
+    }
+
     #[tokio::test]
     async fn test_solve_all_filling_success() {
         let mut board = Board::new((3, 4));
@@ -108,7 +636,13 @@ mod tests {
             Tile::new(arr2(&[[true, true, true], [true, true, false]])),
         ];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_ok());
     }
 
@@ -120,7 +654,13 @@ mod tests {
             Tile::new(arr2(&[[true, true, true], [true, true, false]])),
         ];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -129,7 +669,13 @@ mod tests {
         let board = Board::new((3, 4));
         let tiles = vec![];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -138,7 +684,13 @@ mod tests {
         let board = Board::new((3, 4));
         let tiles = vec![Tile::new(arr2(&[[true, true, true], [true, true, true]]))];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -151,7 +703,13 @@ mod tests {
             Tile::new(arr2(&[[true, false, true], [true, true, true]])),
         ];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -164,7 +722,13 @@ mod tests {
             Tile::new(arr2(&[[true, true, true], [true, true, true]])),
         ];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -178,7 +742,13 @@ mod tests {
         }
         let tiles = vec![];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_ok());
     }
 
@@ -201,7 +771,1138 @@ mod tests {
             ])),
         ];
 
-        let result = solve_all_filling(board, &tiles, CancellationToken::new()).await;
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_dancing_links_success() {
+        let mut board = Board::new((3, 4));
+        board[[0, 0]] = true;
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+        ];
+
+        let result =
+            solve_all_filling(board, &tiles, &dancing_links(), CancellationToken::new()).await;
+        let solution = result.unwrap();
+        assert_eq!(solution.placements().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_dancing_links_failure() {
+        let mut board = Board::new((3, 4));
+        board[[0, 0]] = true;
+        let tiles = vec![
+            Tile::new(arr2(&[[true, false, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        let result =
+            solve_all_filling(board, &tiles, &dancing_links(), CancellationToken::new()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_dancing_links_solved_without_tiles() {
+        let mut board = Board::new((2, 2));
+        board[[0, 0]] = true;
+        board[[0, 1]] = true;
+        board[[1, 0]] = true;
+        board[[1, 1]] = true;
+
+        let result =
+            solve_all_filling(board, &[], &dancing_links(), CancellationToken::new()).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_with_statistics() {
+        let board = Board::new((4, 4));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, false, false]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true]])),
+        ];
+
+        let (result, statistics) = solve_all_filling_with_statistics(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
         assert!(result.is_ok());
+        assert!(statistics.nodes_visited() > 0);
+        assert!(statistics.branches_total() > 0);
+        assert!(statistics.branches_exhausted() < statistics.branches_total());
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_with_statistics_exhausted() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, false, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        for options in all_strategies() {
+            let mut board = Board::new((3, 4));
+            board[[0, 0]] = true;
+            let (result, statistics) = solve_all_filling_with_statistics(
+                board,
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            assert_eq!(result.unwrap_err(), UnsolvableReason::NoFit);
+            assert_eq!(statistics.branches_exhausted(), statistics.branches_total());
+            assert_eq!(statistics.exhausted_fraction(), 1.0);
+            assert_eq!(statistics.estimated_remaining(), Some(Duration::ZERO));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_with_statistics_implausible() {
+        let board = Board::new((3, 4));
+        let tiles = vec![Tile::new(arr2(&[[true, true, true], [true, true, true]]))];
+
+        let (result, statistics) = solve_all_filling_with_statistics(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(
+            result.unwrap_err(),
+            UnsolvableReason::AreaMismatch {
+                board_area: 12,
+                tiles_area: 6
+            }
+        );
+        assert_eq!(statistics.nodes_visited(), 0);
+        assert_eq!(statistics.estimated_remaining(), None);
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_cancelled() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, false, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        for options in all_strategies() {
+            let mut board = Board::new((3, 4));
+            board[[0, 0]] = true;
+            let cancel_token = CancellationToken::new();
+            cancel_token.cancel();
+
+            let result = solve_all_filling(board, &tiles, &options, cancel_token).await;
+            assert_eq!(result.unwrap_err(), UnsolvableReason::Cancelled);
+        }
+    }
+
+    /// An 8x8 board without two opposite corners, which can never be filled with dominoes.
+    fn mutilated_board() -> (Board, Vec<Tile>) {
+        let mut board = Board::new((8, 8));
+        board[[0, 0]] = true;
+        board[[7, 7]] = true;
+        (board, vec![Tile::new(arr2(&[[true, true]])); 31])
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_node_limit() {
+        let (board, tiles) = mutilated_board();
+        for options in all_strategies() {
+            let options = SolverOptions {
+                limits: SearchLimits {
+                    max_nodes: Some(1000),
+                    ..Default::default()
+                },
+                ..options
+            };
+            let (result, statistics) = solve_all_filling_with_statistics(
+                board.clone(),
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            let Err(UnsolvableReason::LimitReached { deepest }) = result else {
+                panic!("Expected the node limit to be reached, got {:?}", result);
+            };
+            assert!(!deepest.placements().is_empty());
+            assert!(deepest.placements().len() < tiles.len());
+            assert!(statistics.nodes_visited() >= 1000);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_time_limit() {
+        let (board, tiles) = mutilated_board();
+        let options = SolverOptions {
+            limits: SearchLimits {
+                max_duration: Some(Duration::ZERO),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = count_all_solutions(board, &tiles, &options, CancellationToken::new()).await;
+        assert!(matches!(result, Err(UnsolvableReason::LimitReached { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_node_limit() {
+        let (board, tiles) = mutilated_board();
+        for options in all_strategies() {
+            let options = SolverOptions {
+                limits: SearchLimits {
+                    max_nodes: Some(1000),
+                    ..Default::default()
+                },
+                ..options
+            };
+            let result =
+                count_all_solutions(board.clone(), &tiles, &options, CancellationToken::new())
+                    .await;
+            let Err(UnsolvableReason::LimitReached { deepest }) = result else {
+                panic!("Expected the node limit to be reached, got {:?}", result);
+            };
+            assert!(!deepest.placements().is_empty());
+            assert!(deepest.placements().len() < tiles.len());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_reports_transforms() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        for options in all_strategies() {
+            let mut board = Board::new((3, 4));
+            board[[0, 0]] = true;
+            let result = solve_all_filling(board, &tiles, &options, CancellationToken::new()).await;
+            for placement in result.unwrap().placements() {
+                assert_eq!(
+                    &placement.transform().apply(placement.base()),
+                    placement.rotation()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_reports_tile_indices() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        for options in all_strategies() {
+            let mut board = Board::new((3, 4));
+            board[[0, 0]] = true;
+            let result = solve_all_filling(board, &tiles, &options, CancellationToken::new()).await;
+            let solution = result.unwrap();
+            assert_eq!(solution.placements().len(), 2);
+            for placement in solution.placements() {
+                assert_eq!(placement.base(), tiles[placement.tile_index()].base());
+            }
+        }
+    }
+
+    fn many_solutions_tiles() -> Vec<Tile> {
+        vec![
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true]])),
+        ]
+    }
+
+    fn placements_of(solution: &Solution) -> Vec<(usize, (usize, usize), Array2<bool>)> {
+        solution
+            .placements()
+            .iter()
+            .map(|placement| {
+                (
+                    placement.tile_index(),
+                    placement.position(),
+                    placement.rotation().clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_solve_all_filling_first_in_order() {
+        let tiles = many_solutions_tiles();
+
+        for strategy in ALL_STRATEGIES {
+            let options = SolverOptions {
+                strategy,
+                selection: SolutionSelection::FirstInOrder,
+                ..Default::default()
+            };
+            let mut solutions = Vec::new();
+            for _ in 0..5 {
+                let result = solve_all_filling(
+                    Board::new((4, 4)),
+                    &tiles,
+                    &options,
+                    CancellationToken::new(),
+                )
+                .await;
+                solutions.push(placements_of(&result.unwrap()));
+            }
+            assert!(solutions.iter().all(|solution| *solution == solutions[0]));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_solve_all_filling_seeded() {
+        let tiles = many_solutions_tiles();
+
+        for strategy in ALL_STRATEGIES {
+            let mut solutions = Vec::new();
+            for seed in 0..5 {
+                let options = SolverOptions {
+                    strategy,
+                    selection: SolutionSelection::Seeded(seed),
+                    ..Default::default()
+                };
+                let first = solve_all_filling(
+                    Board::new((4, 4)),
+                    &tiles,
+                    &options,
+                    CancellationToken::new(),
+                )
+                .await;
+                let second = solve_all_filling(
+                    Board::new((4, 4)),
+                    &tiles,
+                    &options,
+                    CancellationToken::new(),
+                )
+                .await;
+                let first = placements_of(&first.unwrap());
+                assert_eq!(first, placements_of(&second.unwrap()));
+                solutions.push(first);
+            }
+            assert!(solutions.iter().any(|solution| *solution != solutions[0]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_strategies_agree() {
+        let tiles = many_solutions_tiles();
+
+        let mut counts = Vec::new();
+        for options in all_strategies() {
+            let count = count_all_solutions(
+                Board::new((4, 4)),
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+            counts.push(count);
+        }
+        assert!(counts.iter().all(|count| *count == counts[0]));
+        assert!(counts[0].unique() > 1);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_pruner_stages() {
+        let all_stages = PrunerStages {
+            banned_patterns: true,
+            region_sizes: true,
+            checkerboard_parity: true,
+        };
+        let no_stages = PrunerStages {
+            banned_patterns: false,
+            region_sizes: false,
+            checkerboard_parity: false,
+        };
+        let puzzles = [
+            (
+                SolveMode::FillWithAllTiles,
+                vec![
+                    Tile::new(arr2(&[[true, true, true]])),
+                    Tile::new(arr2(&[[true, true], [true, false]])),
+                    Tile::new(arr2(&[[true, true], [true, false]])),
+                    Tile::new(arr2(&[[true, true]])),
+                    Tile::new(arr2(&[[true]])),
+                ],
+                (3, 4),
+            ),
+            (
+                SolveMode::PlaceAllTiles,
+                vec![
+                    Tile::new(arr2(&[[true, true, true]])),
+                    Tile::new(arr2(&[[true, true], [true, false]])),
+                    Tile::new(arr2(&[[true, true]])),
+                ],
+                (3, 3),
+            ),
+        ];
+
+        for strategy in ALL_STRATEGIES {
+            for (mode, tiles, dims) in &puzzles {
+                let mut counts = Vec::new();
+                for pruning in [PrunerStages::default(), all_stages, no_stages] {
+                    let options = SolverOptions {
+                        strategy,
+                        mode: *mode,
+                        pruning,
+                        ..Default::default()
+                    };
+                    let count = count_all_solutions(
+                        Board::new(*dims),
+                        tiles,
+                        &options,
+                        CancellationToken::new(),
+                    )
+                    .await
+                    .unwrap();
+                    counts.push(count);
+                }
+                assert!(counts.iter().all(|count| *count == counts[0]));
+            }
+        }
+    }
+
+    fn domino_at(tile_index: usize, x: usize) -> TilePlacement {
+        TilePlacement::placed(
+            tile_index,
+            arr2(&[[true], [true]]),
+            Transform::default(),
+            (x, 0),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_solve_with_locked() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true], [true]])),
+        ];
+        let vertical_domino = arr2(&[[true, true]]);
+        let locked = vec![
+            TilePlacement::placed(0, vertical_domino.clone(), Transform::default(), (0, 0)),
+            TilePlacement::placed(1, vertical_domino.clone(), Transform::default(), (1, 0)),
+        ];
+
+        for options in all_strategies() {
+            let result = solve_with_locked(
+                Board::new((4, 2)),
+                &locked,
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            let solution = result.unwrap();
+            assert_eq!(solution.placements().len(), 2);
+            let mut tile_indices: Vec<usize> = solution
+                .placements()
+                .iter()
+                .map(|placement| placement.tile_index())
+                .collect();
+            tile_indices.sort();
+            assert_eq!(tile_indices, vec![0, 1]);
+            for placement in solution.placements() {
+                assert!(placement.position().0 >= 2);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_with_locked_invalid_placement() {
+        let tiles = vec![Tile::new(arr2(&[[true], [true]]))];
+
+        let locked = vec![domino_at(0, 0), domino_at(1, 1)];
+        let result = solve_with_locked(
+            Board::new((4, 1)),
+            &locked,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(
+            result.unwrap_err(),
+            UnsolvableReason::InvalidLockedPlacement { placement_index: 1 }
+        );
+
+        let locked = vec![domino_at(0, 3)];
+        let result = solve_with_locked(
+            Board::new((4, 1)),
+            &locked,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(
+            result.unwrap_err(),
+            UnsolvableReason::InvalidLockedPlacement { placement_index: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_wrong_placement() {
+        let tiles = vec![Tile::new(arr2(&[[true], [true]]))];
+        let dominoes = vec![Tile::new(arr2(&[[true], [true]])); 2];
+
+        let locked = vec![domino_at(0, 0), domino_at(1, 3)];
+        let result = find_wrong_placement(
+            Board::new((6, 1)),
+            &locked,
+            &dominoes,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(result, Ok(Some(1)));
+
+        let locked = vec![domino_at(0, 0), domino_at(1, 2)];
+        let result = find_wrong_placement(
+            Board::new((6, 1)),
+            &locked,
+            &dominoes,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(result, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn test_find_wrong_placement_unsolvable() {
+        let tiles = vec![Tile::new(arr2(&[[true, true, true]]))];
+        let dominoes = vec![Tile::new(arr2(&[[true], [true]]))];
+
+        let locked = vec![domino_at(0, 0)];
+        let result = find_wrong_placement(
+            Board::new((6, 1)),
+            &locked,
+            &dominoes,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(
+            result,
+            Err(UnsolvableReason::AreaMismatch {
+                board_area: 4,
+                tiles_area: 3
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_wrong_placement_keeps_orientations() {
+        let horizontal = arr2(&[[true], [true]]);
+        let vertical = arr2(&[[true, true]]);
+        let tiles = vec![
+            Tile::with_orientations(horizontal.clone(), AllowedOrientations::Fixed),
+            Tile::with_orientations(horizontal.clone(), AllowedOrientations::Fixed),
+        ];
+        let locked_tiles = vec![Tile::with_orientations(
+            vertical.clone(),
+            AllowedOrientations::Fixed,
+        )];
+        let locked = vec![TilePlacement::placed(
+            0,
+            vertical,
+            Transform::default(),
+            (0, 0),
+        )];
+
+        // Only turning the locked tile would solve the puzzle, which its orientation forbids.
+        let result = find_wrong_placement(
+            Board::new((2, 3)),
+            &locked,
+            &locked_tiles,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(result.is_err());
+
+        let result = find_wrong_placement(
+            Board::new((2, 3)),
+            &locked,
+            &[],
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(
+            result,
+            Err(UnsolvableReason::InvalidLockedPlacement { placement_index: 0 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_orientation_constraints() {
+        let l_tile = arr2(&[[true, true, true], [true, false, false]]);
+        let mirrored_l_tile = arr2(&[[true, false, false], [true, true, true]]);
+
+        for options in all_strategies() {
+            let tiles = vec![
+                Tile::with_orientations(l_tile.clone(), AllowedOrientations::RotationsOnly),
+                Tile::with_orientations(l_tile.clone(), AllowedOrientations::RotationsOnly),
+            ];
+            let result = solve_all_filling(
+                Board::new((2, 4)),
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            assert!(result.is_ok());
+
+            let tiles = vec![
+                Tile::with_orientations(l_tile.clone(), AllowedOrientations::Fixed),
+                Tile::with_orientations(mirrored_l_tile.clone(), AllowedOrientations::Fixed),
+            ];
+            let result = solve_all_filling(
+                Board::new((2, 4)),
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            assert_eq!(result.unwrap_err(), UnsolvableReason::NoFit);
+        }
+    }
+
+    fn with_mode(strategy: SolverStrategy, mode: SolveMode) -> SolverOptions {
+        SolverOptions {
+            strategy,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_fill_with_subset() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        for strategy in ALL_STRATEGIES {
+            let board = Board::new((3, 4));
+            let options = with_mode(strategy, SolveMode::FillWithSubset);
+            let result = solve_all_filling(board, &tiles, &options, CancellationToken::new()).await;
+            let solution = result.unwrap();
+            assert_eq!(solution.placements().len(), 2);
+            assert!(
+                solution
+                    .placements()
+                    .iter()
+                    .all(|placement| placement.base() == tiles[0].base())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_fill_with_subset_failure() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true, true, true]])),
+            Tile::new(arr2(&[[true, true, true, true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+        ];
+
+        for strategy in ALL_STRATEGIES {
+            let board = Board::new((3, 4));
+            let options = with_mode(strategy, SolveMode::FillWithSubset);
+            let result = solve_all_filling(board, &tiles, &options, CancellationToken::new()).await;
+            assert_eq!(result.unwrap_err(), UnsolvableReason::NoFit);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_place_all_tiles() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true], [true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+        ];
+
+        for strategy in ALL_STRATEGIES {
+            let board = Board::new((3, 3));
+            let options = with_mode(strategy, SolveMode::PlaceAllTiles);
+            let result = solve_all_filling(board, &tiles, &options, CancellationToken::new()).await;
+            assert_eq!(result.unwrap().placements().len(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_modes() {
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true], [true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+        ];
+
+        for strategy in ALL_STRATEGIES {
+            let board = Board::new((3, 3));
+            let options = with_mode(strategy, SolveMode::PlaceAllTiles);
+            let count = count_all_solutions(board, &tiles, &options, CancellationToken::new())
+                .await
+                .unwrap();
+            assert_eq!(count.total(), 8);
+            assert_eq!(count.unique(), 1);
+
+            let board = Board::new((2, 3));
+            let subset_tiles = vec![tiles[0].clone(), tiles[1].clone(), tiles[1].clone()];
+            let options = with_mode(strategy, SolveMode::FillWithSubset);
+            let count =
+                count_all_solutions(board, &subset_tiles, &options, CancellationToken::new())
+                    .await
+                    .unwrap();
+            assert_eq!(count.total(), 1);
+            assert_eq!(count.unique(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_large_board() {
+        let board = Board::new((20, 21));
+        let tiles = vec![
+            Tile::new(Array2::from_elem((10, 21), true)),
+            Tile::new(Array2::from_elem((10, 21), true)),
+        ];
+
+        let result = solve_all_filling(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution = result.unwrap();
+        assert_eq!(solution.placements().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_solve_all_filling_large_board_dancing_links() {
+        let board = Board::new((20, 21));
+        let tiles = vec![
+            Tile::new(Array2::from_elem((10, 21), true)),
+            Tile::new(Array2::from_elem((10, 21), true)),
+        ];
+
+        let result =
+            solve_all_filling(board, &tiles, &dancing_links(), CancellationToken::new()).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_dominoes() {
+        let board = Board::new((2, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+
+        let result = count_all_solutions(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 3);
+        assert_eq!(solution_count.unique(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_monominoes() {
+        let tiles = vec![Tile::new(arr2(&[[true]])); 6];
+
+        for options in all_strategies() {
+            let board = Board::new((2, 3));
+            let solution_count =
+                count_all_solutions(board, &tiles, &options, CancellationToken::new())
+                    .await
+                    .unwrap();
+            assert_eq!(solution_count.total(), 1);
+            assert_eq!(solution_count.unique(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_no_solution() {
+        let mut board = Board::new((3, 4));
+        board[[0, 0]] = true;
+        let tiles = vec![
+            Tile::new(arr2(&[[true, false, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        let result = count_all_solutions(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 0);
+        assert_eq!(solution_count.unique(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_cancelled() {
+        let mut board = Board::new((3, 4));
+        board[[0, 0]] = true;
+        let tiles = vec![
+            Tile::new(arr2(&[[true, false, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+        let cancel_token = CancellationToken::new();
+        cancel_token.cancel();
+
+        let result =
+            count_all_solutions(board, &tiles, &SolverOptions::default(), cancel_token).await;
+        assert_eq!(result.unwrap_err(), UnsolvableReason::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_solved_without_tiles() {
+        let mut board = Board::new((2, 2));
+        board[[0, 0]] = true;
+        board[[0, 1]] = true;
+        board[[1, 0]] = true;
+        board[[1, 1]] = true;
+
+        let result = count_all_solutions(
+            board,
+            &[],
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 1);
+        assert_eq!(solution_count.unique(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_symmetric_board() {
+        let board = Board::new((4, 4));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, false, false]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true]])),
+        ];
+
+        let result = count_all_solutions(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 280);
+        assert_eq!(solution_count.unique(), 35);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_rectangular_board() {
+        let board = Board::new((3, 5));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, false, false]])),
+            Tile::new(arr2(&[[true, true, false], [false, true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+
+        let result = count_all_solutions(
+            board,
+            &tiles,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 24);
+        assert_eq!(solution_count.unique(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_dancing_links() {
+        let board = Board::new((4, 4));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, false, false]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true]])),
+        ];
+
+        let result =
+            count_all_solutions(board, &tiles, &dancing_links(), CancellationToken::new()).await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 280);
+        assert_eq!(solution_count.unique(), 35);
+    }
+
+    #[tokio::test]
+    async fn test_count_all_solutions_dancing_links_identical_tiles() {
+        let board = Board::new((2, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+
+        let result =
+            count_all_solutions(board, &tiles, &dancing_links(), CancellationToken::new()).await;
+        let solution_count = result.unwrap();
+        assert_eq!(solution_count.total(), 3);
+        assert_eq!(solution_count.unique(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_enumerate_solutions() {
+        let board = Board::new((3, 4));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+        ];
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = enumerate_solutions(
+            board,
+            &tiles,
+            sender,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        let solution_count = result.unwrap();
+        let mut received = 0;
+        while let Ok(solution) = receiver.try_recv() {
+            assert_eq!(solution.placements().len(), 4);
+            for placement in solution.placements() {
+                assert!(placement.position().0 < 3);
+                assert!(placement.position().1 < 4);
+            }
+            received += 1;
+        }
+        assert!(solution_count.total() > 0);
+        assert_eq!(received, solution_count.total());
+    }
+
+    #[tokio::test]
+    async fn test_check_uniqueness_unique() {
+        let board = Board::new((2, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+        ];
+
+        for options in all_strategies() {
+            let result = check_uniqueness(
+                board.clone(),
+                &tiles,
+                false,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            let Ok(Uniqueness::Unique(solution)) = result else {
+                panic!("Expected a unique solution, got {:?}", result);
+            };
+            assert_eq!(solution.placements().len(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_uniqueness_multiple() {
+        let board = Board::new((4, 4));
+        let tiles = many_solutions_tiles();
+
+        for options in all_strategies() {
+            let result = check_uniqueness(
+                board.clone(),
+                &tiles,
+                true,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            let Ok(Uniqueness::Multiple(first, second)) = result else {
+                panic!("Expected multiple solutions, got {:?}", result);
+            };
+            assert_ne!(placements_of(&first), placements_of(&second));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_uniqueness_symmetric() {
+        let board = Board::new((2, 2));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+
+        for options in all_strategies() {
+            let result = check_uniqueness(
+                board.clone(),
+                &tiles,
+                false,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            assert!(matches!(result, Ok(Uniqueness::Multiple(_, _))));
+
+            let result = check_uniqueness(
+                board.clone(),
+                &tiles,
+                true,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            assert!(matches!(result, Ok(Uniqueness::Unique(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_uniqueness_no_solution() {
+        let mut board = Board::new((3, 4));
+        board[[0, 0]] = true;
+        let tiles = vec![
+            Tile::new(arr2(&[[true, false, true], [true, true, true]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, true]])),
+        ];
+
+        let result = check_uniqueness(
+            board,
+            &tiles,
+            false,
+            &SolverOptions::default(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(matches!(result, Ok(Uniqueness::None)));
+    }
+
+    #[tokio::test]
+    async fn test_check_uniqueness_cancelled() {
+        let board = Board::new((4, 4));
+        let cancel_token = CancellationToken::new();
+        cancel_token.cancel();
+
+        let result = check_uniqueness(
+            board,
+            &many_solutions_tiles(),
+            false,
+            &SolverOptions::default(),
+            cancel_token,
+        )
+        .await;
+        assert_eq!(result.unwrap_err(), UnsolvableReason::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_count_solutions_for_targets() {
+        let mut board = Board::new((4, 5));
+        board[[3, 4]] = true;
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, false, false]])),
+            Tile::new(arr2(&[[true, true, true], [false, true, false]])),
+            Tile::new(arr2(&[[true, true], [true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+        let targets: Vec<Vec<(usize, usize)>> = (0..4)
+            .flat_map(|x| (0..4).map(move |y| vec![(x, y), (3 - x, 4)]))
+            .collect();
+
+        for options in all_strategies() {
+            let results = count_solutions_for_targets(
+                board.clone(),
+                &targets,
+                &tiles,
+                &options,
+                CancellationToken::new(),
+            )
+            .await;
+            assert_eq!(results.len(), targets.len());
+
+            for (target, result) in targets.iter().zip(results) {
+                let mut target_board = board.clone();
+                for &(x, y) in target {
+                    target_board[[x, y]] = true;
+                }
+                let expected =
+                    count_all_solutions(target_board, &tiles, &options, CancellationToken::new())
+                        .await;
+                assert_eq!(result, expected, "target {:?}", target);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_solutions_for_targets_cancelled() {
+        let board = Board::new((2, 3));
+        let targets = vec![vec![(0, 0)], vec![(1, 2)]];
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true], [true, false]])),
+        ];
+        let cancel_token = CancellationToken::new();
+        cancel_token.cancel();
+
+        let results = count_solutions_for_targets(
+            board,
+            &targets,
+            &tiles,
+            &SolverOptions::default(),
+            cancel_token,
+        )
+        .await;
+        assert_eq!(
+            results,
+            vec![
+                Err(UnsolvableReason::Cancelled),
+                Err(UnsolvableReason::Cancelled)
+            ]
+        );
     }
 }
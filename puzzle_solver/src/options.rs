@@ -0,0 +1,126 @@
+#[cfg(feature = "tokio")]
+use crate::result::SolverStatistics;
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio::sync::mpsc::UnboundedSender;
+
+/// The algorithm used to search for solutions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SolverStrategy {
+    /// Places the tiles one after another, largest tile first, and prunes boards with areas,
+    /// which can not be filled anymore.
+    #[default]
+    Backtracking,
+    /// Solves the puzzle as an exact cover problem using Knuth's Algorithm X with dancing links.
+    ///
+    /// The cell or tile with the fewest remaining placements is always covered next, which
+    /// handles puzzles with many small or identical tiles better than backtracking.
+    DancingLinks,
+    /// Fills the first empty cell of the board next and only tries the placements covering it.
+    FirstEmptyCell,
+    /// Fills the empty cell with the fewest fitting placements next and only tries the
+    /// placements covering it.
+    ///
+    /// Choosing the cell costs more time per step, but a cell which can not be covered anymore
+    /// is found immediately, so much less of the search tree is visited on large boards.
+    MostConstrainedCell,
+}
+
+/// Which tiles have to be placed and which cells of the board have to be covered by a solution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SolveMode {
+    /// All tiles have to be placed and every empty cell of the board has to be covered.
+    #[default]
+    FillWithAllTiles,
+    /// Every empty cell of the board has to be covered, but not all tiles have to be used.
+    ///
+    /// The unused tiles are not part of the solution.
+    FillWithSubset,
+    /// All tiles have to be placed, but not every empty cell of the board has to be covered.
+    ///
+    /// The number of cells left empty is the difference between the area of the board and the
+    /// area of the tiles.
+    PlaceAllTiles,
+}
+
+/// Which solution is returned, if a puzzle has more than one solution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SolutionSelection {
+    /// The first solution found by any of the parallel searches.
+    ///
+    /// This is the fastest selection, but the solution may differ between runs.
+    #[default]
+    FirstFound,
+    /// The first solution in the order the placements are searched.
+    ///
+    /// The same input always returns the same solution on any machine. A solution found by a
+    /// later branch of the search is only returned after all earlier branches are searched
+    /// completely, so this may be slower than `FirstFound`.
+    FirstInOrder,
+    /// Like `FirstInOrder`, but the placements are shuffled with the given seed before the
+    /// search.
+    ///
+    /// The same seed always returns the same solution, different seeds likely return different
+    /// solutions.
+    Seeded(u64),
+}
+
+/// The rules the pruner of the backtracking strategies uses to detect boards, which can not be
+/// completed anymore.
+///
+/// Every rule costs time for each visited board, so disabling a rule may be faster for puzzles,
+/// where it rarely prunes. The dancing links strategy does not use the pruner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrunerStages {
+    /// Bans small empty areas next to filled cells, which no tile can fill.
+    pub banned_patterns: bool,
+    /// Checks that every isolated empty region can be sized by the remaining tiles, so its size
+    /// is a multiple of the greatest common divisor of their areas and not smaller than the
+    /// smallest of them.
+    pub region_sizes: bool,
+    /// Checks that the difference between empty black and white cells in a checkerboard
+    /// coloring can be covered by the remaining tiles.
+    pub checkerboard_parity: bool,
+}
+
+impl Default for PrunerStages {
+    fn default() -> Self {
+        PrunerStages {
+            banned_patterns: true,
+            region_sizes: false,
+            checkerboard_parity: false,
+        }
+    }
+}
+
+/// Limits of the work a single solver run may do.
+///
+/// If a limit is reached, the search stops and returns `UnsolvableReason::LimitReached`. The
+/// limits are checked from time to time while searching, so a search may run a little longer
+/// than allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// The maximum number of nodes of the search tree to visit, None for no limit.
+    pub max_nodes: Option<u64>,
+    /// The maximum time to search, None for no limit.
+    pub max_duration: Option<Duration>,
+}
+
+/// Options to configure how the solver searches for solutions.
+#[derive(Debug, Clone, Default)]
+pub struct SolverOptions {
+    /// The algorithm used to search for solutions.
+    pub strategy: SolverStrategy,
+    /// Which tiles have to be placed and which cells have to be covered.
+    pub mode: SolveMode,
+    /// Which solution is returned, if there is more than one.
+    pub selection: SolutionSelection,
+    /// The rules used to prune the search by the backtracking strategies.
+    pub pruning: PrunerStages,
+    /// The limits of the search.
+    pub limits: SearchLimits,
+    /// If set, the statistics of the search are sent to this sender periodically while the
+    /// solver is running. Only the async API reports the progress.
+    #[cfg(feature = "tokio")]
+    pub progress_sender: Option<UnboundedSender<SolverStatistics>>,
+}
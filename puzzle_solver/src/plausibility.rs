@@ -1,32 +1,120 @@
+use crate::array_util::place_on_all_positions;
 use crate::board::Board;
+use crate::options::SolveMode;
+use crate::result::UnsolvableReason;
 use crate::tile::Tile;
 use log::debug;
+use ndarray::Array2;
 
 /// Performs a plausibility check for the given board and tiles.
 /// It checks the following conditions:
 ///
-/// 1. The total area of the tiles must equal the area of the board.
-/// 2. Each tile must fit within the board dimensions in at least one rotation. TODO
+/// 1. The total area of the tiles must equal the area of the board. If not all tiles have to be
+///    used, the tiles must be able to cover the board. If not all cells have to be covered, the
+///    tiles must fit on the board.
+/// 2. Each tile must fit on the empty cells of the board in at least one rotation, if all tiles
+///    have to be placed.
+/// 3. Each connected area of empty cells must be at least as big as the smallest tile, if all
+///    cells have to be covered.
 ///
-/// If all conditions are met, the function returns true; otherwise, it returns false.
+/// If all conditions are met, the function returns Ok; otherwise, it returns the reason of the
+/// first failed condition.
 ///
 /// # Arguments
 ///
 /// * `board`: The board the tiles should be placed on.
 /// * `tiles`: A slice of tiles to be placed on the board.
+/// * `mode`: Which tiles have to be placed and which cells have to be covered.
 ///
-/// returns: bool
-pub(crate) fn check(board: &Board, tiles: &[Tile]) -> bool {
+/// returns: Result<(), UnsolvableReason>
+pub(crate) fn check(
+    board: &Board,
+    tiles: &[Tile],
+    mode: SolveMode,
+) -> Result<(), UnsolvableReason> {
     let board_area = board.get_array().iter().filter(|&&cell| !cell).count();
-    let tiles_area: usize = tiles
-        .iter()
-        .map(|tile| tile.base.iter().filter(|&&cell| cell).count())
-        .sum();
+    let tiles_area: usize = tiles.iter().map(tile_area).sum();
     debug!(
         "Plausibility check: board area = {}, tiles area = {}",
         board_area, tiles_area
     );
-    tiles_area == board_area
+    let area_plausible = match mode {
+        SolveMode::FillWithAllTiles => tiles_area == board_area,
+        SolveMode::FillWithSubset => tiles_area >= board_area,
+        SolveMode::PlaceAllTiles => tiles_area <= board_area,
+    };
+    if !area_plausible {
+        return Err(UnsolvableReason::AreaMismatch {
+            board_area,
+            tiles_area,
+        });
+    }
+
+    if mode != SolveMode::FillWithSubset
+        && let Some(tile_index) = tiles.iter().position(|tile| !fits_anywhere(board, tile))
+    {
+        debug!("Plausibility check: tile {} fits nowhere", tile_index);
+        return Err(UnsolvableReason::TileFitsNowhere { tile_index });
+    }
+
+    if mode != SolveMode::PlaceAllTiles
+        && let Some(smallest_tile_area) = tiles.iter().map(tile_area).min()
+        && let Some(cells) = empty_regions(board)
+            .into_iter()
+            .find(|region| region.len() < smallest_tile_area)
+    {
+        debug!("Plausibility check: isolated region {:?}", cells);
+        return Err(UnsolvableReason::IsolatedRegion { cells });
+    }
+
+    Ok(())
+}
+
+fn tile_area(tile: &Tile) -> usize {
+    tile.base.iter().filter(|&&cell| cell).count()
+}
+
+/// Returns true if the tile can be placed on the empty cells of the board in any rotation.
+fn fits_anywhere(board: &Board, tile: &Tile) -> bool {
+    tile.all_rotations
+        .iter()
+        .any(|rotation| !place_on_all_positions(board.get_array(), rotation).is_empty())
+}
+
+/// Returns the cells of every connected area of empty cells of the board.
+fn empty_regions(board: &Board) -> Vec<Vec<(usize, usize)>> {
+    let array = board.get_array();
+    let (rows, cols) = array.dim();
+    let mut visited = Array2::from_elem(array.dim(), false);
+    let mut regions = Vec::new();
+
+    for ((x, y), &filled) in array.indexed_iter() {
+        if filled || visited[[x, y]] {
+            continue;
+        }
+        let mut region = Vec::new();
+        let mut stack = vec![(x, y)];
+        visited[[x, y]] = true;
+        while let Some((cx, cy)) = stack.pop() {
+            region.push((cx, cy));
+            let neighbours = [
+                (cx.wrapping_sub(1), cy),
+                (cx + 1, cy),
+                (cx, cy.wrapping_sub(1)),
+                (cx, cy + 1),
+            ];
+            for (nx, ny) in neighbours {
+                if nx < rows && ny < cols && !array[[nx, ny]] && !visited[[nx, ny]] {
+                    visited[[nx, ny]] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+        region.sort();
+        regions.push(region);
+    }
+
+    regions
 }
 
 #[cfg(test)]
@@ -43,7 +131,7 @@ mod tests {
         let tile2 = Tile::new(arr2(&[[false, true], [false, true], [true, true]]));
         let tiles = vec![tile1, tile2];
 
-        assert!(check(&board, &tiles));
+        assert_eq!(check(&board, &tiles, SolveMode::FillWithAllTiles), Ok(()));
     }
 
     #[test]
@@ -53,6 +141,81 @@ mod tests {
         let tile2 = Tile::new(arr2(&[[false, true], [false, true]]));
         let tiles = vec![tile1, tile2];
 
-        assert!(!check(&board, &tiles));
+        assert_eq!(
+            check(&board, &tiles, SolveMode::FillWithAllTiles),
+            Err(UnsolvableReason::AreaMismatch {
+                board_area: 9,
+                tiles_area: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_tile_fits_nowhere() {
+        let board = Board::new((2, 4));
+        let tile1 = Tile::new(arr2(&[[true, true, true]]));
+        let tile2 = Tile::new(arr2(&[[true, true, true]]));
+        let tile3 = Tile::new(arr2(&[[true], [true]]));
+        let tile4 = Tile::new(arr2(&[[true, true, true, true, true, true]]));
+        let tiles = vec![tile1, tile2, tile3];
+        assert_eq!(check(&board, &tiles, SolveMode::FillWithAllTiles), Ok(()));
+
+        let tiles = vec![Tile::new(arr2(&[[true, true]])), tile4];
+        assert_eq!(
+            check(&board, &tiles, SolveMode::FillWithAllTiles),
+            Err(UnsolvableReason::TileFitsNowhere { tile_index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_check_isolated_region() {
+        let mut board = Board::new((3, 3));
+        board[[0, 1]] = true;
+        board[[1, 0]] = true;
+        let tile1 = Tile::new(arr2(&[[true, true], [true, true]]));
+        let tile2 = Tile::new(arr2(&[[true, true, true]]));
+        let tiles = vec![tile1, tile2];
+
+        assert_eq!(
+            check(&board, &tiles, SolveMode::FillWithAllTiles),
+            Err(UnsolvableReason::IsolatedRegion {
+                cells: vec![(0, 0)]
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_fill_with_subset() {
+        let board = Board::new((2, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true, true]])),
+            Tile::new(arr2(&[[true, true, true, true]])),
+        ];
+        assert_eq!(check(&board, &tiles, SolveMode::FillWithSubset), Ok(()));
+
+        let tiles = vec![Tile::new(arr2(&[[true, true, true]]))];
+        assert_eq!(
+            check(&board, &tiles, SolveMode::FillWithSubset),
+            Err(UnsolvableReason::AreaMismatch {
+                board_area: 6,
+                tiles_area: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_place_all_tiles() {
+        let mut board = Board::new((3, 3));
+        board[[0, 1]] = true;
+        board[[1, 0]] = true;
+        let tiles = vec![Tile::new(arr2(&[[true, true], [true, true]]))];
+        assert_eq!(check(&board, &tiles, SolveMode::PlaceAllTiles), Ok(()));
+
+        let tiles = vec![Tile::new(arr2(&[[true, true, true, true]]))];
+        assert_eq!(
+            check(&board, &tiles, SolveMode::PlaceAllTiles),
+            Err(UnsolvableReason::TileFitsNowhere { tile_index: 0 })
+        );
     }
 }
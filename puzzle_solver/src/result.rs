@@ -1,7 +1,10 @@
+use crate::tile::Transform;
 use ndarray::Array2;
+use std::time::Duration;
 
 /// Represents a successful solution to the puzzle.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Solution {
     placements: Vec<TilePlacement>,
 }
@@ -18,31 +21,218 @@ impl Solution {
     }
 }
 
-/// Represents the placement of a tile at a specific position in the puzzle.
+/// The number of solutions of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolutionCount {
+    /// The number of solutions found by the search.
+    total: u64,
+    /// The number of solutions, which are not equal up to a rotation or mirroring of the board
+    /// or swapping identical tiles.
+    unique: u64,
+}
+
+impl SolutionCount {
+    /// Creates a new `SolutionCount` with the given totals.
+    pub(crate) fn new(total: u64, unique: u64) -> Self {
+        Self { total, unique }
+    }
+
+    /// Returns the number of solutions found by the search.
+    ///
+    /// Solutions which only differ by swapping identical tiles are counted once, but solutions
+    /// which are equal up to a rotation or mirroring of the board are counted separately.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the number of solutions, which are not equal up to a rotation or mirroring of the
+    /// board or swapping identical tiles.
+    pub fn unique(&self) -> u64 {
+        self.unique
+    }
+}
+
+/// Whether a puzzle has exactly one solution.
 #[derive(Debug)]
+pub enum Uniqueness {
+    /// The puzzle has exactly one solution.
+    Unique(Solution),
+    /// The puzzle has more than one solution. Contains the first two distinct solutions found.
+    Multiple(Solution, Solution),
+    /// The puzzle has no solution.
+    None,
+}
+
+/// Statistics about the search of a solver run.
+///
+/// While the solver is running, snapshots are sent to the progress sender of the
+/// `SolverOptions`. The final statistics are returned when the solver finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SolverStatistics {
+    /// The number of nodes of the search tree visited so far.
+    nodes_visited: u64,
+    /// The number of placements rejected, because the pruner found an area, which can not be
+    /// filled anymore.
+    prunes: u64,
+    /// The number of branches the search was split into on the first level.
+    branches_total: usize,
+    /// The number of first level branches, which were searched completely.
+    branches_exhausted: usize,
+    /// The time since the solver was started.
+    elapsed: Duration,
+}
+
+impl SolverStatistics {
+    /// Creates new `SolverStatistics` with the given counters.
+    pub(crate) fn new(
+        nodes_visited: u64,
+        prunes: u64,
+        branches_total: usize,
+        branches_exhausted: usize,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            nodes_visited,
+            prunes,
+            branches_total,
+            branches_exhausted,
+            elapsed,
+        }
+    }
+
+    /// Returns the number of nodes of the search tree visited so far.
+    pub fn nodes_visited(&self) -> u64 {
+        self.nodes_visited
+    }
+
+    /// Returns the number of placements rejected by the pruner.
+    pub fn prunes(&self) -> u64 {
+        self.prunes
+    }
+
+    /// Returns the number of branches the search was split into on the first level.
+    pub fn branches_total(&self) -> usize {
+        self.branches_total
+    }
+
+    /// Returns the number of first level branches, which were searched completely.
+    pub fn branches_exhausted(&self) -> usize {
+        self.branches_exhausted
+    }
+
+    /// Returns the fraction of first level branches, which were searched completely.
+    ///
+    /// returns: f64: A value between 0 and 1. It is 0 if the search was not split yet.
+    pub fn exhausted_fraction(&self) -> f64 {
+        if self.branches_total == 0 {
+            return 0.0;
+        }
+        self.branches_exhausted as f64 / self.branches_total as f64
+    }
+
+    /// Returns the time since the solver was started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Estimates the time until the whole search tree is searched based on the fraction of
+    /// exhausted branches.
+    ///
+    /// The branches differ in size, so this is only a rough estimate.
+    ///
+    /// returns: Option<Duration>: None if no branch was exhausted yet.
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        let fraction = self.exhausted_fraction();
+        if fraction <= 0.0 {
+            return None;
+        }
+        Some(self.elapsed.mul_f64((1.0 - fraction) / fraction))
+    }
+}
+
+/// Represents the placement of a tile at a specific position in the puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "crate::serialization::TilePlacementData",
+        into = "crate::serialization::TilePlacementData"
+    )
+)]
 pub struct TilePlacement {
+    /// The index of the tile in the tiles passed to the solver.
+    tile_index: usize,
     /// The base of the tile being placed.
     base: Array2<bool>,
     /// The rotation in which the tile is placed.
     rotation: Array2<bool>,
+    /// The transform creating the rotation from the base.
+    transform: Transform,
     /// The (x, y) position where the tile is placed.
     position: (usize, usize),
 }
 
 impl TilePlacement {
-    /// Creates a new `TilePlacement` with the given base, rotation, and position.
+    /// Creates a new `TilePlacement` with the given tile index, base, rotation, transform, and
+    /// position.
     pub(crate) fn new(
+        tile_index: usize,
         base: Array2<bool>,
         rotation: Array2<bool>,
+        transform: Transform,
         position: (usize, usize),
     ) -> Self {
         Self {
+            tile_index,
             base,
             rotation,
+            transform,
             position,
         }
     }
 
+    /// Creates a `TilePlacement` for a tile, which is already placed on the board, e.g. by the
+    /// player.
+    ///
+    /// # Arguments
+    ///
+    /// * `tile_index`: The index used by the caller to identify the tile.
+    /// * `base`: The base layout of the tile.
+    /// * `transform`: The transform applied to the base to get the placed rotation.
+    /// * `position`: The (x, y) position of the top left corner of the placed rotation.
+    ///
+    /// returns: TilePlacement
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use puzzle_solver::result::TilePlacement;
+    /// use puzzle_solver::tile::Transform;
+    ///
+    /// let base = arr2(&[[true, true], [true, false]]);
+    /// let placement = TilePlacement::placed(0, base, Transform::new(false, 1), (2, 3));
+    /// assert_eq!(placement.rotation(), &arr2(&[[true, true], [false, true]]));
+    /// ```
+    pub fn placed(
+        tile_index: usize,
+        base: Array2<bool>,
+        transform: Transform,
+        position: (usize, usize),
+    ) -> Self {
+        let rotation = transform.apply(&base);
+        Self::new(tile_index, base, rotation, transform, position)
+    }
+
+    /// Returns the index of the tile in the tiles passed to the solver.
+    ///
+    /// Identical tiles are interchangeable, so the index of one of them may be reported for the
+    /// placement of another one.
+    pub fn tile_index(&self) -> usize {
+        self.tile_index
+    }
+
     /// Returns a reference to the base layout of the tile.
     pub fn base(&self) -> &Array2<bool> {
         &self.base
@@ -53,6 +243,11 @@ impl TilePlacement {
         &self.rotation
     }
 
+    /// Returns the transform, which was applied to the base to get the rotation.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
     /// Returns the (x, y) position of the tile.
     pub fn position(&self) -> (usize, usize) {
         self.position
@@ -61,11 +256,49 @@ impl TilePlacement {
 
 /// Represents the reason why a puzzle is unsolvable.
 ///
-/// Currently, the only reason is `NoFit`, indicating that no tiles can fit in the remaining spaces.
-///
-/// In the future, more reasons can be added as needed.
-#[derive(Debug)]
+/// The first reasons are found by the plausibility check before the search is started, so they
+/// are returned immediately and point to the part of the puzzle, which can not be solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum UnsolvableReason {
+    /// The number of empty cells of the board differs from the number of cells of all tiles.
+    AreaMismatch {
+        /// The number of empty cells of the board.
+        board_area: usize,
+        /// The number of cells of all tiles together.
+        tiles_area: usize,
+    },
+    /// A tile can not be placed anywhere on the board in any rotation.
+    TileFitsNowhere {
+        /// The index of the tile in the tiles passed to the solver.
+        tile_index: usize,
+    },
+    /// A connected area of empty cells is smaller than the smallest tile, so it can never be
+    /// filled.
+    IsolatedRegion {
+        /// The cells of the area as (x, y) positions on the board.
+        cells: Vec<(usize, usize)>,
+    },
+    /// The whole search tree was searched without finding a solution.
     NoFit,
-    BoardTooLarge,
+    /// The search was cancelled before it was finished.
+    Cancelled,
+    /// The search stopped, because a limit of the `SearchLimits` was reached.
+    LimitReached {
+        /// The placements of the deepest partial solution reached by the search, which places
+        /// the most tiles without overlapping. When all solutions are searched, it is a
+        /// complete solution, if one was found before the limit was reached.
+        deepest: Solution,
+    },
+    /// The board has more cells than `puzzle_solver::board::MAX_BOARD_CELLS`, the most the
+    /// solver can search.
+    BoardTooLarge {
+        /// The number of cells of the board without the filled rows and columns at its sides.
+        cells: usize,
+    },
+    /// A locked placement lies outside the board or overlaps a filled cell or another locked
+    /// placement.
+    InvalidLockedPlacement {
+        /// The index of the placement in the locked placements passed to the solver.
+        placement_index: usize,
+    },
 }
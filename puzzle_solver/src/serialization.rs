@@ -0,0 +1,187 @@
+use crate::result::TilePlacement;
+use crate::tile::{AllowedOrientations, Tile, Transform};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+/// Serializes an `Array2<bool>` as a list of its rows, so a grid is written the same way as it
+/// is created with `ndarray::arr2`.
+pub(crate) mod bool_grid {
+    use ndarray::Array2;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        array: &Array2<bool>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let rows: Vec<Vec<bool>> = array.rows().into_iter().map(|row| row.to_vec()).collect();
+        rows.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Array2<bool>, D::Error> {
+        let rows = Vec::<Vec<bool>>::deserialize(deserializer)?;
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(D::Error::custom(
+                "all rows of a grid must have the same length",
+            ));
+        }
+        let cells: Vec<bool> = rows.into_iter().flatten().collect();
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        Array2::from_shape_vec((height, width), cells).map_err(D::Error::custom)
+    }
+}
+
+/// The serialized form of a `Tile`.
+///
+/// The orientations of a tile are derived from its base, so only the base and the allowed
+/// orientations are stored.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TileData {
+    #[serde(with = "bool_grid")]
+    base: Array2<bool>,
+    #[serde(default)]
+    allowed_orientations: AllowedOrientations,
+}
+
+impl From<Tile> for TileData {
+    fn from(tile: Tile) -> Self {
+        TileData {
+            allowed_orientations: tile.allowed_orientations(),
+            base: tile.base,
+        }
+    }
+}
+
+impl From<TileData> for Tile {
+    fn from(data: TileData) -> Self {
+        Tile::with_orientations(data.base, data.allowed_orientations)
+    }
+}
+
+/// The serialized form of a `Transform`, which may contain more than three rotations.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TransformData {
+    #[serde(default)]
+    flipped: bool,
+    #[serde(default)]
+    rotations: u8,
+}
+
+impl From<Transform> for TransformData {
+    fn from(transform: Transform) -> Self {
+        TransformData {
+            flipped: transform.flipped(),
+            rotations: transform.rotations(),
+        }
+    }
+}
+
+impl From<TransformData> for Transform {
+    fn from(data: TransformData) -> Self {
+        Transform::new(data.flipped, data.rotations)
+    }
+}
+
+/// The serialized form of a `TilePlacement`.
+///
+/// The placed rotation is derived from the base and the transform, so it is not stored.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TilePlacementData {
+    tile_index: usize,
+    #[serde(with = "bool_grid")]
+    base: Array2<bool>,
+    #[serde(default)]
+    transform: Transform,
+    position: (usize, usize),
+}
+
+impl From<TilePlacement> for TilePlacementData {
+    fn from(placement: TilePlacement) -> Self {
+        TilePlacementData {
+            tile_index: placement.tile_index(),
+            base: placement.base().clone(),
+            transform: placement.transform(),
+            position: placement.position(),
+        }
+    }
+}
+
+impl From<TilePlacementData> for TilePlacement {
+    fn from(data: TilePlacementData) -> Self {
+        TilePlacement::placed(data.tile_index, data.base, data.transform, data.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::result::{Solution, TilePlacement};
+    use crate::tile::{AllowedOrientations, Tile, Transform};
+    use ndarray::arr2;
+
+    #[test]
+    fn test_board() {
+        let mut board = Board::new((2, 3));
+        board[[1, 2]] = true;
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, "[[false,false,false],[false,false,true]]");
+        let read: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, board);
+    }
+
+    #[test]
+    fn test_board_ragged_rows() {
+        let result = serde_json::from_str::<Board>("[[false,false],[false]]");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_tile() {
+        let tile = Tile::with_orientations(
+            arr2(&[[true, true], [true, false]]),
+            AllowedOrientations::RotationsOnly,
+        );
+
+        let json = serde_json::to_string(&tile).unwrap();
+        assert_eq!(
+            json,
+            r#"{"base":[[true,true],[true,false]],"allowed_orientations":"RotationsOnly"}"#
+        );
+        let read: Tile = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, tile);
+
+        let read: Tile = serde_json::from_str(r#"{"base":[[true]]}"#).unwrap();
+        assert_eq!(read, Tile::new(arr2(&[[true]])));
+    }
+
+    #[test]
+    fn test_solution() {
+        let solution = Solution::new(vec![
+            TilePlacement::placed(
+                0,
+                arr2(&[[true, true], [true, false]]),
+                Transform::new(true, 3),
+                (1, 0),
+            ),
+            TilePlacement::placed(1, arr2(&[[true]]), Transform::default(), (0, 0)),
+        ]);
+
+        let json = serde_json::to_string(&solution).unwrap();
+        let read: Solution = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, solution);
+    }
+
+    #[test]
+    fn test_transform_rotations_normalized() {
+        let placement: TilePlacement = serde_json::from_str(
+            r#"{"tile_index":0,"base":[[true,true]],"transform":{"rotations":5},"position":[0,0]}"#,
+        )
+        .unwrap();
+        assert_eq!(placement.transform(), Transform::new(false, 1));
+        assert_eq!(placement.rotation(), &arr2(&[[true], [true]]));
+    }
+}
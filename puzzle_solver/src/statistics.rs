@@ -0,0 +1,144 @@
+use crate::result::SolverStatistics;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+use std::time::Instant;
+#[cfg(feature = "tokio")]
+use tokio::sync::mpsc::UnboundedSender;
+
+/// The time between two progress reports sent to the progress sender.
+#[cfg(feature = "tokio")]
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Counters shared between all tasks of a single solver run.
+///
+/// The solver tasks count locally and add their counts from time to time, so the atomic
+/// operations do not slow down the search.
+pub(crate) struct SearchCounters {
+    start: Instant,
+    nodes_visited: AtomicU64,
+    prunes: AtomicU64,
+    branches_total: AtomicUsize,
+    branches_exhausted: AtomicUsize,
+}
+
+impl SearchCounters {
+    /// Creates new counters, starting the timer for the elapsed time.
+    pub(crate) fn new() -> Self {
+        SearchCounters {
+            start: Instant::now(),
+            nodes_visited: AtomicU64::new(0),
+            prunes: AtomicU64::new(0),
+            branches_total: AtomicUsize::new(0),
+            branches_exhausted: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn add_nodes_visited(&self, nodes_visited: u64) {
+        self.nodes_visited
+            .fetch_add(nodes_visited, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_prunes(&self, prunes: u64) {
+        self.prunes.fetch_add(prunes, Ordering::Relaxed);
+    }
+
+    /// Sets the number of branches the search was split into on the first level.
+    pub(crate) fn set_branches_total(&self, branches_total: usize) {
+        self.branches_total.store(branches_total, Ordering::Relaxed);
+    }
+
+    /// Marks one first level branch as searched completely.
+    pub(crate) fn branch_exhausted(&self) {
+        self.branches_exhausted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current state of the counters.
+    pub(crate) fn snapshot(&self) -> SolverStatistics {
+        SolverStatistics::new(
+            self.nodes_visited.load(Ordering::Relaxed),
+            self.prunes.load(Ordering::Relaxed),
+            self.branches_total.load(Ordering::Relaxed),
+            self.branches_exhausted.load(Ordering::Relaxed),
+            self.start.elapsed(),
+        )
+    }
+}
+
+/// Awaits the search and periodically sends the current statistics to the progress sender
+/// while waiting.
+///
+/// # Arguments
+///
+/// * `search`: The future searching for solutions.
+/// * `counters`: The counters updated by the search.
+/// * `progress_sender`: The sender to report the progress to. If None, the search is only
+///   awaited.
+///
+/// returns: The output of the search.
+#[cfg(feature = "tokio")]
+pub(crate) async fn report_progress<F>(
+    search: F,
+    counters: &SearchCounters,
+    progress_sender: Option<&UnboundedSender<SolverStatistics>>,
+) -> F::Output
+where
+    F: Future,
+{
+    let Some(progress_sender) = progress_sender else {
+        return search.await;
+    };
+
+    let mut search = std::pin::pin!(search);
+    let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+    loop {
+        tokio::select! {
+            output = &mut search => return output,
+            _ = interval.tick() => {
+                let _ = progress_sender.send(counters.snapshot());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let counters = SearchCounters::new();
+        counters.add_nodes_visited(10);
+        counters.add_nodes_visited(5);
+        counters.add_prunes(3);
+        counters.set_branches_total(4);
+        counters.branch_exhausted();
+
+        let statistics = counters.snapshot();
+        assert_eq!(statistics.nodes_visited(), 15);
+        assert_eq!(statistics.prunes(), 3);
+        assert_eq!(statistics.branches_total(), 4);
+        assert_eq!(statistics.branches_exhausted(), 1);
+        assert_eq!(statistics.exhausted_fraction(), 0.25);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_report_progress() {
+        let counters = SearchCounters::new();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let output = report_progress(
+            async {
+                tokio::time::sleep(PROGRESS_INTERVAL * 2).await;
+                42
+            },
+            &counters,
+            Some(&sender),
+        )
+        .await;
+
+        assert_eq!(output, 42);
+        assert!(receiver.try_recv().is_ok());
+    }
+}
@@ -0,0 +1,456 @@
+use crate::backtracking::UNUSED;
+use crate::backtracking::positioned::PositionedTile;
+use crate::bitmask::Bitmask;
+use crate::board::Board;
+use std::collections::HashMap;
+
+/// A symmetry of the board, represented as a permutation of the bit indices used in a
+/// `Bitmask`.
+///
+/// The bit at index `i` is moved to index `permutation[i]` when the symmetry is applied.
+pub(crate) struct Symmetry {
+    permutation: Vec<usize>,
+}
+
+impl Symmetry {
+    /// Applies the symmetry to the given bitmask and returns the transformed bitmask.
+    ///
+    /// # Arguments
+    ///
+    /// * `bitmask`: The bitmask to transform.
+    ///
+    /// returns: Bitmask
+    pub(crate) fn apply<const N: usize>(&self, bitmask: &Bitmask<N>) -> Bitmask<N> {
+        let mut transformed = Bitmask::new(bitmask.relevant_bits());
+        for index in 0..bitmask.relevant_bits() {
+            if bitmask.get_bit(index) {
+                transformed.set_bit(self.permutation[index]);
+            }
+        }
+        transformed
+    }
+
+    /// Returns the sorted cell indices of the given bitmask after applying the symmetry.
+    fn apply_to_cells<const N: usize>(&self, bitmask: &Bitmask<N>) -> Vec<usize> {
+        let mut cells: Vec<usize> = (0..bitmask.relevant_bits())
+            .filter(|index| bitmask.get_bit(*index))
+            .map(|index| self.permutation[index])
+            .collect();
+        cells.sort_unstable();
+        cells
+    }
+}
+
+/// The symmetries of a board together with the tiles to place on it.
+///
+/// A symmetry is only included, if it maps the board onto itself and every set of placements
+/// of a tile onto itself. This way, applying a symmetry to a solution always results in
+/// another valid solution.
+/// The identity is always included as the first symmetry.
+pub(crate) struct BoardSymmetries<const N: usize> {
+    symmetries: Vec<Symmetry>,
+    /// For each tile, the index of the first tile with the same set of placements.
+    /// Tiles with the same class are interchangeable in a solution.
+    tile_classes: Vec<usize>,
+    /// For each tile, the index of each placement in the positioned tile.
+    placement_indices: Vec<HashMap<Bitmask<N>, usize>>,
+}
+
+impl<const N: usize> BoardSymmetries<N> {
+    /// Detects the symmetries of the given board for the given positioned tiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `board`: The board to detect the symmetries of.
+    /// * `positioned_tiles`: The tiles with all their placements on the board.
+    ///
+    /// returns: BoardSymmetries
+    pub(crate) fn new(board: &Board, positioned_tiles: &[PositionedTile<N>]) -> Self {
+        let placement_indices: Vec<HashMap<Bitmask<N>, usize>> = positioned_tiles
+            .iter()
+            .map(|positioned_tile| {
+                positioned_tile
+                    .bitmasks()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, bitmask)| (bitmask.clone(), index))
+                    .collect()
+            })
+            .collect();
+
+        let symmetries = candidate_permutations(board)
+            .into_iter()
+            .map(|permutation| Symmetry { permutation })
+            .filter(|symmetry| {
+                placement_indices.iter().all(|placements| {
+                    placements
+                        .keys()
+                        .all(|placement| placements.contains_key(&symmetry.apply(placement)))
+                })
+            })
+            .collect();
+
+        let tile_classes = (0..placement_indices.len())
+            .map(|i| {
+                (0..i)
+                    .find(|&j| {
+                        placement_indices[j].len() == placement_indices[i].len()
+                            && placement_indices[j]
+                                .keys()
+                                .all(|placement| placement_indices[i].contains_key(placement))
+                    })
+                    .unwrap_or(i)
+            })
+            .collect();
+
+        BoardSymmetries {
+            symmetries,
+            tile_classes,
+            placement_indices,
+        }
+    }
+
+    /// Restricts the placements of one tile to one placement per symmetry class.
+    ///
+    /// Every solution then has exactly one symmetric solution, which uses the remaining
+    /// placements. Searching with the restricted tiles therefore only finds one representative
+    /// per symmetry class of solutions.
+    ///
+    /// A tile can only be restricted, if no other tile has the same shape and none of its
+    /// placements is mapped onto itself by a symmetry other than the identity.
+    /// The first tile matching these conditions is restricted.
+    ///
+    /// # Arguments
+    ///
+    /// * `positioned_tiles`: The tiles with all their placements on the board.
+    ///
+    /// returns: Option<Vec<PositionedTile<N>>>: The tiles with one restricted tile, or None if the
+    /// board has no symmetries or no tile can be restricted.
+    pub(crate) fn restrict_placements(
+        &self,
+        positioned_tiles: &[PositionedTile<N>],
+    ) -> Option<Vec<PositionedTile<N>>> {
+        if self.symmetries.len() <= 1 {
+            return None;
+        }
+
+        let restricted_tile_index = (0..positioned_tiles.len()).find(|&tile_index| {
+            let unique_shape = self
+                .tile_classes
+                .iter()
+                .enumerate()
+                .all(|(other_index, &class)| {
+                    other_index == tile_index || class != self.tile_classes[tile_index]
+                });
+            unique_shape
+                && positioned_tiles[tile_index]
+                    .bitmasks()
+                    .iter()
+                    .all(|placement| {
+                        self.symmetries
+                            .iter()
+                            .skip(1)
+                            .all(|symmetry| symmetry.apply(placement) != *placement)
+                    })
+        })?;
+
+        let placement_indices = &self.placement_indices[restricted_tile_index];
+        let restricted_tile = positioned_tiles[restricted_tile_index].filter(|index, placement| {
+            self.symmetries
+                .iter()
+                .all(|symmetry| placement_indices[&symmetry.apply(placement)] >= index)
+        });
+
+        let mut restricted_tiles = positioned_tiles.to_vec();
+        restricted_tiles[restricted_tile_index] = restricted_tile;
+        Some(restricted_tiles)
+    }
+
+    /// Creates all solutions, which are symmetric to a solution found with restricted
+    /// placements. The given solution itself is included.
+    ///
+    /// The placements of identical tiles are sorted, so every solution is created only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `placements`: The index of the placement for each tile in the restricted tiles.
+    /// * `restricted_tiles`: The tiles with restricted placements used for the search.
+    ///
+    /// returns: Vec<Vec<usize>>: The index of the placement for each tile in the positioned
+    /// tiles used to create the symmetries.
+    pub(crate) fn symmetric_solutions(
+        &self,
+        placements: &[usize],
+        restricted_tiles: &[PositionedTile<N>],
+    ) -> Vec<Vec<usize>> {
+        self.symmetries
+            .iter()
+            .map(|symmetry| {
+                let mut symmetric: Vec<usize> = placements
+                    .iter()
+                    .enumerate()
+                    .map(|(tile_index, &placement_index)| {
+                        let placement = &restricted_tiles[tile_index].bitmasks()[placement_index];
+                        self.placement_indices[tile_index][&symmetry.apply(placement)]
+                    })
+                    .collect();
+                self.sort_copies(&mut symmetric);
+                symmetric
+            })
+            .collect()
+    }
+
+    /// Sorts the placement indices of interchangeable tiles, so identical tiles are placed in
+    /// the order of their placements like in the search.
+    fn sort_copies(&self, placements: &mut [usize]) {
+        for (tile_index, &class) in self.tile_classes.iter().enumerate() {
+            if class != tile_index {
+                continue;
+            }
+            let copies: Vec<usize> = (tile_index..placements.len())
+                .filter(|&copy| self.tile_classes[copy] == class)
+                .collect();
+            let mut copy_placements: Vec<usize> =
+                copies.iter().map(|&copy| placements[copy]).collect();
+            copy_placements.sort_unstable();
+            for (copy, placement) in copies.into_iter().zip(copy_placements) {
+                placements[copy] = placement;
+            }
+        }
+    }
+
+    /// Creates a key for a solution, which is the same for all solutions that are equal up to
+    /// a symmetry of the board and swapping interchangeable tiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `placements`: The index of the placement for each tile. Unused tiles are ignored.
+    /// * `positioned_tiles`: The tiles with all their placements on the board.
+    ///
+    /// returns: Vec<(usize, Vec<usize>)>
+    pub(crate) fn canonical_key(
+        &self,
+        placements: &[usize],
+        positioned_tiles: &[PositionedTile<N>],
+    ) -> Vec<(usize, Vec<usize>)> {
+        self.symmetries
+            .iter()
+            .map(|symmetry| {
+                let mut key: Vec<(usize, Vec<usize>)> = placements
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, placement_index)| **placement_index != UNUSED)
+                    .map(|(tile_index, &placement_index)| {
+                        let bitmask = &positioned_tiles[tile_index].bitmasks()[placement_index];
+                        (
+                            self.tile_classes[tile_index],
+                            symmetry.apply_to_cells(bitmask),
+                        )
+                    })
+                    .collect();
+                key.sort_unstable();
+                key
+            })
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+/// Maps the cell (x, y) of a board with the given rows and columns to another cell.
+type Transformation = fn(usize, usize, usize, usize) -> (usize, usize);
+
+/// Creates the permutations of the bit indices for all transformations of the board, which map
+/// the board onto itself.
+/// Only the rotations and mirrors, which keep the dimensions of the board, are considered.
+fn candidate_permutations(board: &Board) -> Vec<Vec<usize>> {
+    let (rows, cols) = board.get_array().dim();
+    let mut transformations: Vec<Transformation> = vec![
+        |x, y, _, _| (x, y),
+        |x, y, rows, cols| (rows - 1 - x, cols - 1 - y),
+        |x, y, rows, _| (rows - 1 - x, y),
+        |x, y, _, cols| (x, cols - 1 - y),
+    ];
+    if rows == cols {
+        transformations.extend_from_slice(&[
+            |x, y, _, _| (y, x),
+            |x, y, rows, cols| (cols - 1 - y, rows - 1 - x),
+            |x, y, rows, _| (y, rows - 1 - x),
+            |x, y, _, cols| (cols - 1 - y, x),
+        ]);
+    }
+
+    transformations
+        .into_iter()
+        .filter_map(|transformation| {
+            let mut permutation = vec![0; rows * cols];
+            for ((x, y), &filled) in board.get_array().indexed_iter() {
+                let (tx, ty) = transformation(x, y, rows, cols);
+                if board[[tx, ty]] != filled {
+                    return None;
+                }
+                // This index has to match the index in the Bitmask. See Bitmask::from(&Array2<bool>)
+                permutation[y * rows + x] = ty * rows + tx;
+            }
+            Some(permutation)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtracking::pruner::Pruner;
+    use crate::tile::Tile;
+    use ndarray::arr2;
+
+    fn positioned_tiles(board: &Board, tiles: &[Tile]) -> Vec<PositionedTile<1>> {
+        let pruner = Pruner::new_for_filling(board, tiles);
+        tiles
+            .iter()
+            .map(|tile| PositionedTile::new(tile, board, &pruner))
+            .collect()
+    }
+
+    #[test]
+    fn test_symmetries_square() {
+        let board = Board::new((4, 4));
+        let tiles = vec![Tile::new(arr2(&[[true, true], [true, true]]))];
+
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles(&board, &tiles));
+        assert_eq!(symmetries.symmetries.len(), 8);
+    }
+
+    #[test]
+    fn test_symmetries_rectangle() {
+        let board = Board::new((2, 4));
+        let tiles = vec![Tile::new(arr2(&[[true, true], [true, true]]))];
+
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles(&board, &tiles));
+        assert_eq!(symmetries.symmetries.len(), 4);
+    }
+
+    #[test]
+    fn test_symmetries_asymmetric_board() {
+        let mut board = Board::new((3, 3));
+        board[[0, 0]] = true;
+        board[[0, 1]] = true;
+        let tiles = vec![Tile::new(arr2(&[[true]]))];
+
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles(&board, &tiles));
+        assert_eq!(symmetries.symmetries.len(), 1);
+    }
+
+    #[test]
+    fn test_restrict_placements_asymmetric_tile() {
+        let board = Board::new((5, 5));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true], [true, true]])),
+        ];
+        let positioned_tiles = positioned_tiles(&board, &tiles);
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles);
+
+        let restricted_tiles = symmetries.restrict_placements(&positioned_tiles).unwrap();
+        assert_eq!(
+            restricted_tiles[0].bitmasks().len() * 8,
+            positioned_tiles[0].bitmasks().len()
+        );
+        assert_eq!(
+            restricted_tiles[1].bitmasks().len(),
+            positioned_tiles[1].bitmasks().len()
+        );
+    }
+
+    #[test]
+    fn test_restrict_placements_symmetric_tile() {
+        let board = Board::new((4, 4));
+        let tiles = vec![Tile::new(arr2(&[[true, true], [true, false]]))];
+        let positioned_tiles = positioned_tiles(&board, &tiles);
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles);
+
+        assert!(symmetries.restrict_placements(&positioned_tiles).is_none());
+    }
+
+    #[test]
+    fn test_restrict_placements_identical_tiles() {
+        let board = Board::new((4, 4));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+        ];
+        let positioned_tiles = positioned_tiles(&board, &tiles);
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles);
+
+        assert!(symmetries.restrict_placements(&positioned_tiles).is_none());
+    }
+
+    #[test]
+    fn test_symmetric_solutions() {
+        let board = Board::new((3, 5));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true, true], [true, true, false]])),
+            Tile::new(arr2(&[[true]])),
+        ];
+        let positioned_tiles = positioned_tiles(&board, &tiles);
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles);
+        let restricted_tiles = symmetries.restrict_placements(&positioned_tiles).unwrap();
+
+        let solutions = symmetries.symmetric_solutions(&[0, 0], &restricted_tiles);
+        assert_eq!(solutions.len(), 4);
+        assert_eq!(solutions[0], vec![0, 0]);
+        for (i, solution) in solutions.iter().enumerate() {
+            for other in solutions.iter().skip(i + 1) {
+                assert_ne!(solution[0], other[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_key_mirrored() {
+        let board = Board::new((2, 3));
+        let tiles = vec![
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+            Tile::new(arr2(&[[true, true]])),
+        ];
+        let positioned_tiles = positioned_tiles(&board, &tiles);
+        let symmetries = BoardSymmetries::new(&board, &positioned_tiles);
+
+        let find = |tile_index: usize, cells: &[[usize; 2]]| {
+            let mut array = arr2(&[[false, false, false], [false, false, false]]);
+            for cell in cells {
+                array[*cell] = true;
+            }
+            let bitmask = Bitmask::from(&array);
+            positioned_tiles[tile_index]
+                .bitmasks()
+                .iter()
+                .position(|b| *b == bitmask)
+                .unwrap()
+        };
+        let left = vec![
+            find(0, &[[0, 0], [0, 1]]),
+            find(1, &[[1, 0], [1, 1]]),
+            find(2, &[[0, 2], [1, 2]]),
+        ];
+        let right = vec![
+            find(0, &[[0, 0], [1, 0]]),
+            find(1, &[[1, 1], [1, 2]]),
+            find(2, &[[0, 1], [0, 2]]),
+        ];
+        let vertical = vec![
+            find(0, &[[0, 0], [1, 0]]),
+            find(1, &[[0, 1], [1, 1]]),
+            find(2, &[[0, 2], [1, 2]]),
+        ];
+
+        assert_eq!(
+            symmetries.canonical_key(&left, &positioned_tiles),
+            symmetries.canonical_key(&right, &positioned_tiles)
+        );
+        assert_ne!(
+            symmetries.canonical_key(&left, &positioned_tiles),
+            symmetries.canonical_key(&vertical, &positioned_tiles)
+        );
+    }
+}
@@ -1,18 +1,120 @@
 use crate::array_util::{debug_print, rotate_90};
 use log::debug;
-use ndarray::Array2;
-use std::collections::HashSet;
+use ndarray::{Array2, Axis};
+
+/// The orientations a tile may be placed in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AllowedOrientations {
+    /// All rotations and mirror images of the tile.
+    #[default]
+    All,
+    /// All rotations, but no mirror images. This is used for one-sided pieces.
+    RotationsOnly,
+    /// Only the base orientation of the tile.
+    Fixed,
+}
+
+/// A rotation and mirroring of a tile relative to its base.
+///
+/// The base is mirrored first, if `flipped` is set, and then rotated clockwise by 90 degrees
+/// `rotations` times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "crate::serialization::TransformData",
+        into = "crate::serialization::TransformData"
+    )
+)]
+pub struct Transform {
+    /// Whether the base is mirrored by reversing the order of its rows.
+    flipped: bool,
+    /// The number of clockwise rotations by 90 degrees, from 0 to 3.
+    rotations: u8,
+}
+
+impl Transform {
+    /// Creates a new Transform.
+    ///
+    /// # Arguments
+    ///
+    /// * `flipped`: Whether the base is mirrored by reversing the order of its rows.
+    /// * `rotations`: The number of clockwise rotations by 90 degrees. Only the remainder of a
+    ///   division by 4 is used.
+    ///
+    /// returns: Transform
+    pub fn new(flipped: bool, rotations: u8) -> Self {
+        Transform {
+            flipped,
+            rotations: rotations % 4,
+        }
+    }
+
+    /// Returns whether the base is mirrored by reversing the order of its rows.
+    pub fn flipped(&self) -> bool {
+        self.flipped
+    }
+
+    /// Returns the number of clockwise rotations by 90 degrees, from 0 to 3.
+    pub fn rotations(&self) -> u8 {
+        self.rotations
+    }
+
+    /// Applies the transform to the given base.
+    ///
+    /// # Arguments
+    ///
+    /// * `base`: The base to transform.
+    ///
+    /// returns: Array2<bool>
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use puzzle_solver::tile::Transform;
+    /// use ndarray::arr2;
+    ///
+    /// let base = arr2(&[[true, true], [true, false]]);
+    /// let transformed = Transform::new(false, 1).apply(&base);
+    /// assert_eq!(transformed, arr2(&[[true, true], [false, true]]));
+    /// ```
+    pub fn apply(&self, base: &Array2<bool>) -> Array2<bool> {
+        let mut array = base.clone();
+        if self.flipped {
+            array.invert_axis(Axis(0));
+        }
+        for _ in 0..self.rotations {
+            array = rotate_90(&array);
+        }
+        array
+    }
+}
 
 /// Represents a tile to place on a board.
 /// It is based on a 2D array of booleans, where `true` indicates the presence of a feature
 /// (e.g., part of a puzzle piece) and `false` indicates its absence.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "crate::serialization::TileData",
+        into = "crate::serialization::TileData"
+    )
+)]
 pub struct Tile {
     /// The base 2D boolean array representing the tile.
     /// This is kept for convenience to give back to users who want the original base.
     pub(crate) base: Array2<bool>,
-    /// All unique rotations and flips of the tile, containing the base orientation as well.
+    /// All unique allowed rotations and flips of the tile, containing the base orientation as
+    /// well. The base orientation is always the first one.
     pub(crate) all_rotations: Vec<Array2<bool>>,
+    /// The transform creating each of `all_rotations` from the base.
+    pub(crate) transforms: Vec<Transform>,
+    /// The orientations the tile may be placed in.
+    allowed_orientations: AllowedOrientations,
 }
 
 impl Tile {
@@ -20,6 +122,7 @@ impl Tile {
     ///
     /// A cell containing `true` indicates the presence of a feature (e.g., part of a puzzle piece),
     /// while `false` indicates its absence.
+    /// The tile may be placed in all rotations and mirror images.
     ///
     /// # Arguments
     ///
@@ -37,34 +140,67 @@ impl Tile {
     /// let tile = Tile::new(base);
     /// ```
     pub fn new(base: Array2<bool>) -> Tile {
-        let mut all_rotations_set: HashSet<Array2<bool>> = HashSet::new();
-
-        all_rotations_set.insert(base.clone());
-
-        let mut tmp = rotate_90(&base);
-        all_rotations_set.insert(tmp.clone());
-        tmp = rotate_90(&tmp);
-        all_rotations_set.insert(tmp.clone());
-        tmp = rotate_90(&tmp);
-        all_rotations_set.insert(tmp.clone());
+        Tile::with_orientations(base, AllowedOrientations::All)
+    }
 
-        tmp = base.clone().reversed_axes();
-        all_rotations_set.insert(tmp.clone());
+    /// Creates a new Tile from a base 2D boolean array, which may only be placed in the given
+    /// orientations.
+    ///
+    /// # Arguments
+    ///
+    /// * `base`: Array2<bool> - The base 2D boolean array representing the tile.
+    /// * `allowed_orientations`: The orientations the tile may be placed in.
+    ///
+    /// returns: Tile
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use puzzle_solver::tile::{AllowedOrientations, Tile};
+    /// use ndarray::arr2;
+    ///
+    /// let base = arr2(&[[true, false], [true, true], [false, true]]);
+    /// let tile = Tile::with_orientations(base, AllowedOrientations::RotationsOnly);
+    /// ```
+    pub fn with_orientations(
+        base: Array2<bool>,
+        allowed_orientations: AllowedOrientations,
+    ) -> Tile {
+        let flips: &[bool] = match allowed_orientations {
+            AllowedOrientations::All => &[false, true],
+            AllowedOrientations::RotationsOnly | AllowedOrientations::Fixed => &[false],
+        };
+        let rotations: u8 = match allowed_orientations {
+            AllowedOrientations::All | AllowedOrientations::RotationsOnly => 4,
+            AllowedOrientations::Fixed => 1,
+        };
 
-        tmp = rotate_90(&tmp);
-        all_rotations_set.insert(tmp.clone());
-        tmp = rotate_90(&tmp);
-        all_rotations_set.insert(tmp.clone());
-        tmp = rotate_90(&tmp);
-        all_rotations_set.insert(tmp.clone());
+        let mut all_rotations: Vec<Array2<bool>> = Vec::new();
+        let mut transforms: Vec<Transform> = Vec::new();
+        for &flipped in flips {
+            for rotation in 0..rotations {
+                let transform = Transform::new(flipped, rotation);
+                let array = transform.apply(&base);
+                if !all_rotations.contains(&array) {
+                    all_rotations.push(array);
+                    transforms.push(transform);
+                }
+            }
+        }
 
-        let all_rotations = all_rotations_set.into_iter().collect();
         Tile {
             base,
             all_rotations,
+            transforms,
+            allowed_orientations,
         }
     }
 
+    /// Returns the orientations the tile may be placed in.
+    pub fn allowed_orientations(&self) -> AllowedOrientations {
+        self.allowed_orientations
+    }
+
     /// Returns a reference to the base 2D boolean array of the tile.
     /// This is the same array that was used to create the Tile.
     ///
@@ -192,4 +328,65 @@ mod tests {
                 .contains(&arr2(&[[true, true, true], [true, true, false]]))
         );
     }
+
+    #[test]
+    fn test_with_orientations_rotations_only() {
+        let base = arr2(&[[true, false], [true, true], [false, true]]);
+        let tile = Tile::with_orientations(base.clone(), AllowedOrientations::RotationsOnly);
+
+        assert_eq!(
+            tile.allowed_orientations(),
+            AllowedOrientations::RotationsOnly
+        );
+        assert_eq!(tile.all_rotations.len(), 2);
+        assert_eq!(tile.all_rotations[0], base);
+        assert!(
+            tile.all_rotations
+                .contains(&arr2(&[[false, true, true], [true, true, false]]))
+        );
+        assert!(
+            !tile
+                .all_rotations
+                .contains(&arr2(&[[false, true], [true, true], [true, false]]))
+        );
+    }
+
+    #[test]
+    fn test_with_orientations_fixed() {
+        let base = arr2(&[[true, false], [true, true]]);
+        let tile = Tile::with_orientations(base.clone(), AllowedOrientations::Fixed);
+
+        assert_eq!(tile.all_rotations, vec![base]);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let base = arr2(&[[true, false], [true, true], [true, false]]);
+        let tile = Tile::new(base.clone());
+
+        assert_eq!(tile.transforms.len(), tile.all_rotations.len());
+        assert_eq!(tile.transforms[0], Transform::default());
+        for (rotation, transform) in tile.all_rotations.iter().zip(&tile.transforms) {
+            assert_eq!(&transform.apply(&base), rotation);
+        }
+
+        let tile = Tile::with_orientations(base, AllowedOrientations::RotationsOnly);
+        assert!(tile.transforms.iter().all(|transform| !transform.flipped()));
+    }
+
+    #[test]
+    fn test_transform_apply() {
+        let base = arr2(&[[true, true, true], [true, false, false]]);
+
+        assert_eq!(Transform::new(false, 0).apply(&base), base);
+        assert_eq!(
+            Transform::new(true, 0).apply(&base),
+            arr2(&[[true, false, false], [true, true, true]])
+        );
+        assert_eq!(
+            Transform::new(false, 2).apply(&base),
+            arr2(&[[false, false, true], [true, true, true]])
+        );
+        assert_eq!(Transform::new(true, 5), Transform::new(true, 1));
+    }
 }
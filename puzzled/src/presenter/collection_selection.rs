@@ -232,6 +232,24 @@ impl CollectionSelectionPresenter {
                     ReadError::InvalidColor { message } => {
                         format!("The collection file contains an invalid color: {}", message)
                     }
+                    ReadError::UnknownImport { collection_id } => {
+                        format!(
+                            "The collection imports the collection '{}', which is not installed.",
+                            collection_id
+                        )
+                    }
+                    ReadError::CyclicImport { collection_ids } => {
+                        format!(
+                            "The collection imports itself through the collections: {}",
+                            collection_ids.join(" -> ")
+                        )
+                    }
+                    ReadError::SchemaViolation { pointer, message } => {
+                        format!(
+                            "The collection file does not match the collection schema at '{}': {}",
+                            pointer, message
+                        )
+                    }
                 };
                 self.show_load_collection_error(message);
             }
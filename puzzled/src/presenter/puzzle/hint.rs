@@ -8,11 +8,12 @@ use adw::glib;
 use gtk::prelude::{ButtonExt, WidgetExt};
 use gtk::Button;
 use log::debug;
-use puzzle_solver::result::{Solution, UnsolvableReason};
+use puzzle_solver::hint::Hint;
+use puzzle_solver::result::UnsolvableReason;
 use std::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
-pub type OnComplete = Box<dyn Fn(Result<Solution, UnsolvableReason>)>;
+pub type OnComplete = Box<dyn Fn(Result<Option<Hint>, UnsolvableReason>)>;
 
 /// Responsible for managing the hint button.
 ///
@@ -41,10 +42,17 @@ impl HintButtonPresenter {
     /// # Arguments
     ///
     /// * `puzzle_state`: The current state of the puzzle.
+    /// * `hovered_tile`: The id of the tile the player is pointing at, which the hint should
+    ///   place.
     /// * `on_complete`: Callback to be called when the solver has finished.
     ///
     /// returns: ()
-    pub fn calculate_hint(&self, puzzle_state: &mut PuzzleState, on_complete: OnComplete) {
+    pub fn calculate_hint(
+        &self,
+        puzzle_state: &mut PuzzleState,
+        hovered_tile: Option<usize>,
+        on_complete: OnComplete,
+    ) {
         let state = get_state();
         let calculate_solvability = match &state.puzzle_type_extension {
             None => true,
@@ -53,13 +61,18 @@ impl HintButtonPresenter {
         };
         drop(state);
         if calculate_solvability {
-            self.calculate_solvability(puzzle_state, on_complete);
+            self.calculate_solvability(puzzle_state, hovered_tile, on_complete);
         } else {
             self.display_state(&HintButtonState::Bulb);
         }
     }
 
-    fn calculate_solvability(&self, puzzle_state: &mut PuzzleState, on_complete: OnComplete) {
+    fn calculate_solvability(
+        &self,
+        puzzle_state: &mut PuzzleState,
+        hovered_tile: Option<usize>,
+        on_complete: OnComplete,
+    ) {
         let mut state = get_state_mut();
 
         let solver_state = &state.solver_state;
@@ -71,7 +84,7 @@ impl HintButtonPresenter {
             _ => {}
         }
 
-        let (tx, rx) = mpsc::channel::<Result<Solution, UnsolvableReason>>();
+        let (tx, rx) = mpsc::channel::<Result<Option<Hint>, UnsolvableReason>>();
         glib::idle_add_local({
             let self_clone = self.clone();
             move || match rx.try_recv() {
@@ -94,9 +107,10 @@ impl HintButtonPresenter {
         };
         self.display_state(&HintButtonState::Calculating);
         drop(state);
-        solver::solve_for_target(
+        solver::find_hint_for_target(
             &call_id,
             &puzzle_state,
+            hovered_tile,
             Box::new(move |result| {
                 let _ = tx.send(result);
             }),
@@ -16,6 +16,8 @@ use adw::prelude::{ActionMapExtManual, NavigationPageExt};
 use adw::{gio, Toast, ToastOverlay};
 use gtk::Label;
 use log::error;
+use puzzle_solver::hint::HintExplanation;
+use puzzle_solver::result::UnsolvableReason;
 use std::cell::Cell;
 use std::rc::Rc;
 
@@ -114,30 +116,38 @@ impl PuzzlePresenter {
     }
 
     fn on_hint_requested(&self) {
+        if get_state().puzzle_config.is_none() {
+            // The shortcut triggers the action while no puzzle is shown as well.
+            return;
+        }
         let puzzle_state = self.puzzle_area_presenter.extract_puzzle_state();
 
         if let Ok(mut puzzle_state) = puzzle_state {
             self.puzzle_area_presenter.remove_hint_tile();
+            let hovered_tile = self.puzzle_area_presenter.hovered_tile();
             self.hint_button_presenter
-                .calculate_hint(&mut puzzle_state, {
+                .calculate_hint(&mut puzzle_state, hovered_tile, {
                     let self_clone = self.clone();
                     Box::new(move |result| {
                         self_clone.toast_overlay.dismiss_all();
                         let hint_count = self_clone.hint_count.get();
                         self_clone.hint_count.replace(hint_count + 1);
                         match result {
-                            Ok(solution) => {
-                                solution.placements().last()
-                                    .map(|placement| self_clone.puzzle_area_presenter.show_hint_tile(placement));
+                            Ok(Some(hint)) => {
+                                self_clone
+                                    .puzzle_area_presenter
+                                    .show_hint_tile(hint.placement());
+                                self_clone
+                                    .toast_overlay
+                                    .add_toast(Toast::new(&hint_message(hint.explanation())));
                             }
-                            Err(_) => {
+                            Ok(None) => {}
+                            Err(reason) => {
                                 self_clone.toast_overlay.add_toast(
                                     Toast::builder()
                                         .custom_title(
                                             &Label::builder()
-                                                .label(
-                                                    "Puzzle is not solvable with the current approach",
-                                                )
+                                                .label(unsolvable_message(&reason))
                                                 .css_classes(vec!["error"])
                                                 .build(),
                                         )
@@ -188,3 +198,39 @@ impl PuzzlePresenter {
         }
     }
 }
+
+/// Returns the message shown to the player, explaining why the hint places this tile.
+fn hint_message(explanation: &HintExplanation) -> String {
+    match explanation {
+        HintExplanation::MostConstrainedCell { candidates: 1, .. } => {
+            "This tile covers a cell, which no other placement can fill".to_string()
+        }
+        HintExplanation::MostConstrainedCell { candidates, .. } => format!(
+            "This tile covers the hardest cell to fill, only {} placements fit there",
+            candidates
+        ),
+        HintExplanation::LargestTile { area } => {
+            format!("The largest tile with {} cells goes here", area)
+        }
+        HintExplanation::RequestedTile => "This is where the tile you pointed at goes".to_string(),
+    }
+}
+
+/// Returns the message shown to the player, explaining why the puzzle can not be solved.
+fn unsolvable_message(reason: &UnsolvableReason) -> &'static str {
+    match reason {
+        UnsolvableReason::AreaMismatch { .. } => {
+            "The remaining tiles do not cover the free cells of the board"
+        }
+        UnsolvableReason::TileFitsNowhere { .. } => "A tile does not fit anywhere on the board",
+        UnsolvableReason::IsolatedRegion { .. } => {
+            "The board has a gap, which is too small for any tile"
+        }
+        UnsolvableReason::BoardTooLarge { .. } => "The board is too large to search for a solution",
+        UnsolvableReason::InvalidLockedPlacement { .. } => "A placed tile overlaps another tile",
+        UnsolvableReason::NoFit | UnsolvableReason::Cancelled => {
+            "Puzzle is not solvable with the current approach"
+        }
+        UnsolvableReason::LimitReached { .. } => "The solver could not decide in time",
+    }
+}
@@ -12,6 +12,8 @@ pub struct PuzzleAreaData {
     pub tile_views: Vec<TileView>,
     pub grid_config: GridConfig,
     pub hint_tile_view: Option<TileView>,
+    /// The id of the tile the pointer is currently over.
+    pub hovered_tile: Option<usize>,
 }
 
 impl PuzzleAreaData {
@@ -300,6 +300,7 @@ impl PuzzleAreaPresenter {
             .drain(..)
             .for_each(|e| fixed.remove(&e));
         data.tile_views.clear();
+        data.hovered_tile = None;
         data.board_view = None;
         if let Some(tile_view) = &data.hint_tile_view {
             fixed.remove(tile_view);
@@ -307,6 +308,11 @@ impl PuzzleAreaPresenter {
         data.hint_tile_view = None;
     }
 
+    /// Returns the id of the tile the pointer is currently over.
+    pub fn hovered_tile(&self) -> Option<usize> {
+        self.data.borrow().hovered_tile
+    }
+
     pub fn extract_puzzle_state(&self) -> Result<PuzzleState, String> {
         let state = get_state();
         let mut state = PuzzleState::new(
@@ -5,7 +5,9 @@ use adw::gdk::{BUTTON_MIDDLE, BUTTON_SECONDARY};
 use gtk::prelude::{
     Cast, EventControllerExt, FixedExt, GestureDragExt, GestureSingleExt, WidgetExt,
 };
-use gtk::{EventController, GestureClick, GestureDrag, PropagationPhase, Widget};
+use gtk::{
+    EventController, EventControllerMotion, GestureClick, GestureDrag, PropagationPhase, Widget,
+};
 use puzzle_config::TileConfig;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -43,6 +45,7 @@ impl TilePresenter {
             tile_view.upcast_ref(),
             on_position_changed.clone(),
         );
+        self.setup_hover_tracking(tile_id, tile_view.upcast_ref());
         let mut data = self.data.borrow_mut();
         data.add_to_fixed(tile_view.upcast_ref(), &start_position.into());
         data.tile_views.push(tile_view);
@@ -172,6 +175,27 @@ impl TilePresenter {
         draggable.add_controller(gesture.upcast::<EventController>());
     }
 
+    /// Keeps track of the tile the pointer is over, so a hint can be given for it.
+    fn setup_hover_tracking(&self, tile_id: usize, hoverable: &Widget) {
+        let motion = EventControllerMotion::new();
+        motion.connect_enter({
+            let self_clone = self.clone();
+            move |_, _x, _y| {
+                self_clone.data.borrow_mut().hovered_tile = Some(tile_id);
+            }
+        });
+        motion.connect_leave({
+            let self_clone = self.clone();
+            move |_| {
+                let mut data = self_clone.data.borrow_mut();
+                if data.hovered_tile == Some(tile_id) {
+                    data.hovered_tile = None;
+                }
+            }
+        });
+        hoverable.add_controller(motion);
+    }
+
     fn setup_tile_updating_gesture<F: Fn(&TileView) -> () + 'static>(
         &self,
         tile_view_index: usize,
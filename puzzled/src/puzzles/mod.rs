@@ -3,7 +3,7 @@ mod community;
 use crate::config;
 use crate::puzzles::community::save_community_collection;
 use adw::gio::{resources_lookup_data, ResourceLookupFlags};
-use log::error;
+use log::{error, info};
 use once_cell::sync::Lazy;
 use puzzle_config::{JsonLoader, PuzzleConfigCollection, ReadError};
 use std::backtrace::Backtrace;
@@ -25,10 +25,14 @@ const CORE_COLLECTIONS: [&str; 9] = [
 static PUZZLE_COLLECTION_STORE: Lazy<Mutex<PuzzleCollectionStore>> =
     Lazy::new(|| Mutex::new(PuzzleCollectionStore::default()));
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct PuzzleCollectionStore {
     core_puzzle_collections: Vec<PuzzleConfigCollection>,
     community_puzzle_collections: Vec<PuzzleConfigCollection>,
+    /// The loader of all collections, with all installed collections registered for imports.
+    json_loader: Option<JsonLoader>,
+    /// The community collections, which import a collection that is not installed yet.
+    pending_community_collections: Vec<String>,
 }
 
 impl PuzzleCollectionStore {
@@ -44,50 +48,90 @@ impl PuzzleCollectionStore {
         &mut self,
         json_str: &str,
     ) -> Result<(), ReadError> {
-        let json_loader = create_json_loader();
-        let collection = json_loader.load_puzzle_collection(json_str)?;
+        let collection = self.json_loader().load_puzzle_collection(json_str)?;
         self.remove_community_collection(collection.id());
+        register_collection(self.json_loader(), json_str);
         save_community_collection(collection.id(), json_str);
         self.community_puzzle_collections.push(collection);
+        self.load_pending_community_collections();
         Ok(())
     }
 
     pub fn remove_community_collection(&mut self, collection_id: &str) {
         self.community_puzzle_collections
             .retain(|collection| collection.id() != collection_id);
+        self.json_loader().unregister_collection(collection_id);
         community::delete_community_collection(collection_id);
     }
+
+    fn json_loader(&mut self) -> &mut JsonLoader {
+        self.json_loader.get_or_insert_with(create_json_loader)
+    }
+
+    /// Loads an installed community collection.
+    /// If it imports a collection, which is not installed, it is loaded again once a collection
+    /// is added.
+    fn load_community_collection(&mut self, json_str: String) {
+        match self.json_loader().load_puzzle_collection(&json_str) {
+            Ok(collection) => self.community_puzzle_collections.push(collection),
+            Err(ReadError::UnknownImport { collection_id }) => {
+                info!(
+                    "Community puzzle collection waits for the collection '{}' to be installed",
+                    collection_id
+                );
+                self.pending_community_collections.push(json_str);
+            }
+            Err(e) => error!(
+                "Failed to load community puzzle collection from JSON string: {:?}",
+                e
+            ),
+        }
+    }
+
+    fn load_pending_community_collections(&mut self) {
+        for json_str in std::mem::take(&mut self.pending_community_collections) {
+            self.load_community_collection(json_str);
+        }
+    }
 }
 
 pub fn init() {
     let mut store = PUZZLE_COLLECTION_STORE.lock().unwrap();
-    let json_loader = create_json_loader();
 
-    for &collection_name in CORE_COLLECTIONS.iter() {
-        let path = format!("/de/til7701/Puzzled/puzzles/{}.json", collection_name);
-        let collection = load_core_from_resource(&path, &json_loader);
-        store.core_puzzle_collections.push(collection);
+    let core_collections: Vec<(String, String)> = CORE_COLLECTIONS
+        .iter()
+        .map(|&collection_name| {
+            let filename = core_collection_path(collection_name);
+            let json_str = read_resource(&filename);
+            (filename, json_str)
+        })
+        .collect();
+    let community_collections = community::load_community_collections();
+
+    // All installed collections are registered first, so they can import each other.
+    let json_loader = store.json_loader();
+    for (_, json_str) in &core_collections {
+        register_collection(json_loader, json_str);
+    }
+    for json_str in &community_collections {
+        register_collection(json_loader, json_str);
     }
 
-    let community_collections = community::load_community_collections();
+    for (filename, json_str) in core_collections {
+        let collection = load_core_collection(&filename, &json_str, store.json_loader());
+        store.core_puzzle_collections.push(collection);
+    }
     for json_str in community_collections {
-        let collection = match json_loader.load_puzzle_collection(&json_str) {
-            Ok(collection) => collection,
-            Err(e) => {
-                error!(
-                    "Failed to load community puzzle collection from JSON string: {:?}",
-                    e
-                );
-                continue;
-            }
-        };
-        store.community_puzzle_collections.push(collection);
+        store.load_community_collection(json_str);
     }
 }
 
-fn load_core_from_resource(filename: &str, json_loader: &JsonLoader) -> PuzzleConfigCollection {
-    let json_str = read_resource(filename);
-    match json_loader.load_puzzle_collection(&json_str) {
+fn load_core_collection(
+    filename: &str,
+    json_str: &str,
+    json_loader: &JsonLoader,
+) -> PuzzleConfigCollection {
+    match json_loader.load_puzzle_collection(json_str) {
         Ok(collection) => collection,
         Err(e) => panic!(
             "Failed to load core puzzle collection from '{}': {:?}",
@@ -96,11 +140,23 @@ fn load_core_from_resource(filename: &str, json_loader: &JsonLoader) -> PuzzleCo
     }
 }
 
+fn core_collection_path(collection_name: &str) -> String {
+    format!("/de/til7701/Puzzled/puzzles/{}.json", collection_name)
+}
+
 fn create_json_loader() -> JsonLoader {
     let predefined_json_str = read_resource("/de/til7701/Puzzled/predefined.json");
     puzzle_config::create_json_loader(&predefined_json_str, config::VERSION).unwrap()
 }
 
+/// Registers an installed collection, so other collections can import its custom tiles and
+/// boards.
+fn register_collection(json_loader: &mut JsonLoader, json_str: &str) {
+    if let Err(e) = json_loader.register_collection(json_str) {
+        error!("Failed to register puzzle collection for imports: {:?}", e);
+    }
+}
+
 fn read_resource(filename: &str) -> String {
     let data = resources_lookup_data(filename, ResourceLookupFlags::NONE).unwrap();
     std::str::from_utf8(&*data).unwrap().to_string()
@@ -125,12 +181,16 @@ pub fn get_puzzle_collection_store() -> MutexGuard<'static, PuzzleCollectionStor
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solver::count_solutions_for_all_targets;
     use puzzle_config::{BoardConfig, PuzzleConfig, PuzzleId};
     use puzzle_solver::board::Board;
+    use puzzle_solver::options::{SearchLimits, SolverOptions};
+    use puzzle_solver::result::UnsolvableReason;
     use puzzle_solver::tile::Tile;
     use std::collections::{HashMap, HashSet};
     use std::fs;
     use std::hash::{DefaultHasher, Hash, Hasher};
+    use std::time::Duration;
     use tokio_util::sync::CancellationToken;
 
     #[test]
@@ -155,13 +215,67 @@ mod tests {
         let json_loader =
             puzzle_config::create_json_loader(&predefined_json_str, config::VERSION).unwrap();
 
-        // (collection_id, puzzle_name) pairs to skip because they are known to be unsolvable or take too long
+        // (collection_id, puzzle_name) pairs to skip because they are known to be unsolvable
         let skip_list = [
-            ("de.til7701.Puzzled.Puzzled", "Large Sandbox"), // Cannot be solved
-            ("de.til7701.Puzzled.RecursiveConstruction", "T4 x 3"), // Takes too long to solve
-            ("de.til7701.Puzzled.Hexominoes", "Holes"),      // Takes too long to solve
-            ("de.til7701.Puzzled.PuzzleADay", "4-Digit Year"), // Unknown if solvable
+            ("de.til7701.Puzzled.Puzzled", "Large Sandbox"), // Tiles do not cover the board
         ];
+        // Budgets of the puzzles, which take longer than the default budget. The test takes at
+        // most the sum of the budgets of all puzzles, which is about 18 minutes, but finishes
+        // within a few minutes, as most puzzles are solved in a fraction of their budget.
+        let budgets = [
+            // No solution is found within minutes
+            (
+                "de.til7701.Puzzled.Hexominoes",
+                "6 x 18 A",
+                Budget::Search(Duration::from_secs(5)),
+            ),
+            (
+                "de.til7701.Puzzled.Hexominoes",
+                "All in Three Almost-Rectangles",
+                Budget::Search(Duration::from_secs(5)),
+            ),
+            (
+                "de.til7701.Puzzled.Hexominoes",
+                "Holes",
+                Budget::Search(Duration::from_secs(5)),
+            ),
+            // Take minutes without optimizations
+            (
+                "de.til7701.Puzzled.Chess",
+                "Board",
+                Budget::Search(Duration::from_secs(5)),
+            ),
+            (
+                "de.til7701.Puzzled.Chess",
+                "Queen",
+                Budget::Search(Duration::from_secs(5)),
+            ),
+            (
+                "de.til7701.Puzzled.Chess",
+                "King",
+                Budget::Search(Duration::from_secs(5)),
+            ),
+            // Takes about a minute without optimizations for all 372 dates
+            (
+                "de.til7701.Puzzled.PuzzleADay",
+                "Original",
+                Budget::Solve(Duration::from_secs(180)),
+            ),
+            // Too many targets to count the solutions of each, the first dates are checked
+            (
+                "de.til7701.Puzzled.PuzzleADay",
+                "2-Digit Year",
+                Budget::Search(Duration::from_secs(30)),
+            ),
+            (
+                "de.til7701.Puzzled.PuzzleADay",
+                "4-Digit Year",
+                Budget::Search(Duration::from_secs(30)),
+            ),
+        ];
+        // Every other core puzzle is solved within a few seconds without optimizations
+        let default_budget = Budget::Solve(Duration::from_secs(15));
+        let mut not_solved = Vec::new();
 
         for collection_name in CORE_COLLECTIONS.iter() {
             let json =
@@ -170,30 +284,44 @@ mod tests {
 
             for puzzle in collection.puzzles() {
                 if skip_list.contains(&(collection.id(), puzzle.name())) {
-                    // Skip puzzles that are known to be unsolvable or take too long
-                    continue;
-                }
-
-                if puzzle.tiles().len() > 12 {
-                    // Skip puzzles with too many tiles to avoid long test times
+                    // Skip puzzles that are known to be unsolvable
                     continue;
                 }
 
+                let budget = budgets
+                    .iter()
+                    .find(|(id, name, _)| *id == collection.id() && *name == puzzle.name())
+                    .map_or(&default_budget, |(_, _, budget)| budget);
+                let limits = SearchLimits {
+                    max_duration: Some(budget.duration()),
+                    ..Default::default()
+                };
+                let tiles: Vec<Tile> = puzzle
+                    .tiles()
+                    .iter()
+                    .map(|tile_config| Tile::new(tile_config.base().clone()))
+                    .collect();
                 let board_config = &puzzle.board_config();
                 match board_config {
                     BoardConfig::Simple { layout } => {
                         let board: Board = layout.map(|e| !e).clone().into();
-                        let tiles: Vec<Tile> = puzzle
-                            .tiles()
-                            .iter()
-                            .map(|tile_config| Tile::new(tile_config.base().clone()))
-                            .collect();
+                        let options = SolverOptions {
+                            limits,
+                            ..Default::default()
+                        };
                         let result = puzzle_solver::solve_all_filling(
                             board,
                             &tiles,
+                            &options,
                             CancellationToken::new(),
                         )
                         .await;
+                        if let Err(UnsolvableReason::LimitReached { .. }) = result {
+                            if budget.must_solve() {
+                                not_solved.push(format!("{}: {}", collection_name, puzzle.name()));
+                            }
+                            continue;
+                        }
                         assert!(
                             result.is_ok(),
                             "Failed to solve puzzle '{}' in collection '{}'",
@@ -201,10 +329,82 @@ mod tests {
                             collection_name
                         );
                     }
-                    BoardConfig::Area { .. } => {}
+                    BoardConfig::Area { .. } => {
+                        // The budget limits each target and the whole batch of targets
+                        let cancel_token = CancellationToken::new();
+                        let deadline = tokio::spawn({
+                            let cancel_token = cancel_token.clone();
+                            let duration = budget.duration();
+                            async move {
+                                tokio::time::sleep(duration).await;
+                                cancel_token.cancel();
+                            }
+                        });
+                        let results = count_solutions_for_all_targets(
+                            board_config,
+                            &tiles,
+                            limits,
+                            cancel_token,
+                        )
+                        .await;
+                        deadline.abort();
+
+                        let mut targets_not_solved = 0;
+                        for (target, result) in results {
+                            if let Err(
+                                UnsolvableReason::LimitReached { .. } | UnsolvableReason::Cancelled,
+                            ) = result
+                            {
+                                targets_not_solved += 1;
+                                continue;
+                            }
+                            assert!(
+                                result.is_ok_and(|count| count.total() > 0),
+                                "Failed to solve puzzle '{}' in collection '{}' for target '{}'",
+                                puzzle.name(),
+                                collection_name,
+                                board_config.format_target(&target)
+                            );
+                        }
+                        if budget.must_solve() && targets_not_solved > 0 {
+                            not_solved.push(format!(
+                                "{}: {} ({} targets)",
+                                collection_name,
+                                puzzle.name(),
+                                targets_not_solved
+                            ));
+                        }
+                    }
                 }
             }
         }
+
+        assert!(
+            not_solved.is_empty(),
+            "Puzzles not solved within their budget: {:?}",
+            not_solved
+        );
+    }
+
+    /// The time the core collection test searches for the solutions of a puzzle.
+    enum Budget {
+        /// The puzzle has to be solved within the time.
+        Solve(Duration),
+        /// The puzzle is searched for the time, but is not expected to be solved within it.
+        /// It only fails, if the search finds it unsolvable.
+        Search(Duration),
+    }
+
+    impl Budget {
+        fn duration(&self) -> Duration {
+            match self {
+                Budget::Solve(duration) | Budget::Search(duration) => *duration,
+            }
+        }
+
+        fn must_solve(&self) -> bool {
+            matches!(self, Budget::Solve(_))
+        }
     }
 
     #[test]
@@ -1,10 +1,13 @@
 use crate::global::runtime::get_runtime;
 use crate::global::state::SolverState::Done;
 use crate::global::state::{get_state_mut, SolverState, State};
-use crate::presenter::puzzle_area::puzzle_state::{Cell, PuzzleState};
+use crate::presenter::puzzle_area::puzzle_state::{Cell, PuzzleState, UnusedTile};
 use log::debug;
+use puzzle_config::{BoardConfig, Target, TargetIndex};
 use puzzle_solver::board::Board;
-use puzzle_solver::result::{Solution, UnsolvableReason};
+use puzzle_solver::hint::{Hint, HintPolicy};
+use puzzle_solver::options::{SearchLimits, SolutionSelection, SolverOptions, SolverStrategy};
+use puzzle_solver::result::{SolutionCount, UnsolvableReason};
 use puzzle_solver::tile::Tile;
 use std::cmp::PartialEq;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -17,8 +20,8 @@ use tokio_util::sync::CancellationToken;
 pub struct SolverCallId(u64);
 
 /// Callback type to be invoked upon solver completion.
-/// It receives the final `SolverState` as an argument.
-pub type OnCompleteCallback = Box<dyn Fn(Result<Solution, UnsolvableReason>) + Send>;
+/// It receives the hint found by the solver or the reason why the puzzle can not be solved.
+pub type OnCompleteCallback = Box<dyn Fn(Result<Option<Hint>, UnsolvableReason>) + Send>;
 
 static SOLVER_CALL_ID_ATOMIC_COUNTER: AtomicU64 = AtomicU64::new(0);
 
@@ -27,18 +30,40 @@ pub fn create_solver_call_id() -> SolverCallId {
     SolverCallId(SOLVER_CALL_ID_ATOMIC_COUNTER.fetch_add(1, Ordering::SeqCst))
 }
 
-pub fn solve_for_target(
+/// Searches a solution for the current puzzle state and chooses the placement shown as a hint.
+///
+/// # Arguments
+///
+/// * `solver_call_id`: The id of this solver call.
+/// * `puzzle_state`: The current state of the puzzle.
+/// * `hovered_tile`: The id of the tile the player is pointing at. Its placement is shown, if
+///   the tile is part of the solution.
+/// * `on_complete`: Callback to be called when the solver has finished.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: ()
+pub fn find_hint_for_target(
     solver_call_id: &SolverCallId,
     puzzle_state: &PuzzleState,
+    hovered_tile: Option<usize>,
     on_complete: OnCompleteCallback,
     cancel_token: CancellationToken,
 ) {
     let board = create_board(puzzle_state);
-    let tiles: Vec<Tile> = puzzle_state
-        .unused_tiles
+    // The tiles are sorted, so the same puzzle state always gives the same hint.
+    let mut unused_tiles: Vec<&UnusedTile> = puzzle_state.unused_tiles.iter().collect();
+    unused_tiles.sort_by_key(|tile_state| tile_state.id);
+    let tiles: Vec<Tile> = unused_tiles
         .iter()
         .map(|tile_state| Tile::new(tile_state.base.clone()))
         .collect();
+    let policy = hovered_tile
+        .and_then(|id| {
+            unused_tiles
+                .iter()
+                .position(|tile_state| tile_state.id == id)
+        })
+        .map_or(HintPolicy::MostConstrained, HintPolicy::Tile);
 
     let runtime = get_runtime();
     let now = Instant::now();
@@ -46,8 +71,13 @@ pub fn solve_for_target(
         let solver_call_id = solver_call_id.clone();
         let cancel_token = cancel_token.clone();
         async move {
-            debug!("Starting Solver task.");
-            let result = puzzle_solver::solve_all_filling(board, &tiles, cancel_token).await;
+            debug!("Starting Solver task with hint policy {:?}.", policy);
+            let options = SolverOptions {
+                selection: SolutionSelection::FirstInOrder,
+                ..Default::default()
+            };
+            let result =
+                puzzle_solver::find_hint(board, &tiles, policy, &options, cancel_token).await;
             let end = Instant::now();
             let duration = end.duration_since(now);
             debug!(
@@ -59,9 +89,63 @@ pub fn solve_for_target(
     });
 }
 
+/// Counts the solutions of a puzzle for every target, which can be selected on its board.
+///
+/// This checks, whether an area puzzle like a Puzzle-a-Day is solvable for every date.
+/// The placements of the tiles are only computed once and reused for all targets.
+/// Cancelling the operation stops the remaining targets, so a cancellation after a deadline
+/// bounds the time of the whole batch.
+///
+/// # Arguments
+///
+/// * `board_config`: The board configuration of the puzzle. A simple board has no targets.
+/// * `tiles`: The tiles to place on the board.
+/// * `limits`: The limits of the search for each target.
+/// * `cancel_token`: A cancellation token to cancel the operation.
+///
+/// returns: Vec<(Target, Result<SolutionCount, UnsolvableReason>)>
+// Not used by the app yet, the tests check the calendars of the core collections with it.
+#[allow(dead_code)]
+pub async fn count_solutions_for_all_targets(
+    board_config: &BoardConfig,
+    tiles: &[Tile],
+    limits: SearchLimits,
+    cancel_token: CancellationToken,
+) -> Vec<(Target, Result<SolutionCount, UnsolvableReason>)> {
+    let board: Board = board_config.layout().map(|on_board| !on_board).into();
+    let targets = board_config.all_targets();
+    let target_cells: Vec<Vec<(usize, usize)>> = targets
+        .iter()
+        .map(|target| {
+            target
+                .indices
+                .iter()
+                .map(|TargetIndex(x, y)| (*x, *y))
+                .collect()
+        })
+        .collect();
+
+    // Counting searches the whole search tree, where choosing the most constrained cell is
+    // much faster than placing the tiles one after another.
+    let options = SolverOptions {
+        strategy: SolverStrategy::MostConstrainedCell,
+        limits,
+        ..Default::default()
+    };
+    let results = puzzle_solver::count_solutions_for_targets(
+        board,
+        &target_cells,
+        tiles,
+        &options,
+        cancel_token,
+    )
+    .await;
+    targets.into_iter().zip(results).collect()
+}
+
 fn handle_on_complete(
     solver_call_id: SolverCallId,
-    result: Result<Solution, UnsolvableReason>,
+    result: Result<Option<Hint>, UnsolvableReason>,
     on_complete: OnCompleteCallback,
 ) {
     let mut state = get_state_mut();